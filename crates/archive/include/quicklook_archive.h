@@ -0,0 +1,148 @@
+#ifndef QUICKLOOK_ARCHIVE_H
+#define QUICKLOOK_ARCHIVE_H
+
+/* 此文件由 cbindgen 自动生成，请勿手动修改 */
+
+#include <stddef.h>
+#include <stdint.h>
+
+// 成功
+#define ARCHIVE_OK 0
+
+// 参数无效（空指针、非 UTF-8 字符串等）
+#define ARCHIVE_ERR_INVALID_ARGUMENT -1
+
+// IO 错误
+#define ARCHIVE_ERR_IO -2
+
+// ZIP 解析错误
+#define ARCHIVE_ERR_ZIP -3
+
+// 7Z 解析错误
+#define ARCHIVE_ERR_SEVENZ -4
+
+// 不支持的格式
+#define ARCHIVE_ERR_UNSUPPORTED_FORMAT -5
+
+// 非法路径（如条目试图解压到目标目录之外）
+#define ARCHIVE_ERR_INVALID_PATH -6
+
+// 条目不存在
+#define ARCHIVE_ERR_ENTRY_NOT_FOUND -7
+
+// 调用方提供的缓冲区太小
+#define ARCHIVE_ERR_BUFFER_TOO_SMALL -8
+
+// 数据损坏
+#define ARCHIVE_ERR_CORRUPT -9
+
+// 数据被截断
+#define ARCHIVE_ERR_TRUNCATED -10
+
+// 需要密码
+#define ARCHIVE_ERR_PASSWORD_REQUIRED -11
+
+// 超出大小/数量等限制
+#define ARCHIVE_ERR_LIMIT_EXCEEDED -12
+
+// 不支持的压缩方法
+#define ARCHIVE_ERR_UNSUPPORTED_COMPRESSION -13
+
+// 其他错误
+#define ARCHIVE_ERR_OTHER -99
+
+// 读取单个条目到内存时允许的最大大小
+#define ARCHIVE_MAX_READ_SIZE (1 << 30)
+
+// 压缩文件格式
+//
+// 取值与 C ABI 中的格式编号一一对应，不要修改已有的数值。
+enum ArchiveFormat
+#if defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+  : int32_t
+#endif // defined(__cplusplus) || __STDC_VERSION__ >= 202311L
+ {
+  ARCHIVE_FORMAT_ZIP = 1,
+  ARCHIVE_FORMAT_TAR = 2,
+  ARCHIVE_FORMAT_TAR_GZ = 3,
+  ARCHIVE_FORMAT_TAR_BZ2 = 4,
+  ARCHIVE_FORMAT_TAR_XZ = 5,
+  ARCHIVE_FORMAT_TAR_ZST = 6,
+  ARCHIVE_FORMAT_SEVEN_Z = 7,
+};
+#ifndef __cplusplus
+#if __STDC_VERSION__ >= 202311L
+typedef enum ArchiveFormat ArchiveFormat;
+#else
+typedef int32_t ArchiveFormat;
+#endif // __STDC_VERSION__ >= 202311L
+#endif // __cplusplus
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// 列举压缩文件条目，结果为树状结构的 JSON 字符串，需使用 `archive_free_string` 释放
+//
+// # Safety
+//
+// `path` 必须是有效的 NUL 结尾字符串，`result` 必须是可写的指针。
+int32_t archive_list_entries(const char *path,
+                             char **result);
+
+// 识别压缩文件格式，结果写入 `format`（取值见 `ArchiveFormat`）
+//
+// # Safety
+//
+// `path` 必须是有效的 NUL 结尾字符串，`format` 必须是可写的指针。
+int32_t archive_detect_format(const char *path, ArchiveFormat *format);
+
+// 读取压缩文件元信息，结果为 JSON 字符串，需使用 `archive_free_string` 释放
+//
+// # Safety
+//
+// `path` 必须是有效的 NUL 结尾字符串，`result` 必须是可写的指针。
+int32_t archive_info(const char *path, char **result);
+
+// 将单个条目的内容读取到调用方提供的缓冲区
+//
+// `written` 总是写入条目的实际大小；`buffer` 为空或 `buffer_len` 不足时返回
+// `ARCHIVE_ERR_BUFFER_TOO_SMALL`，调用方可据此分配缓冲区后重试。
+// 查询大小时只读取条目元数据，不会解压内容。
+//
+// # Safety
+//
+// `path`、`entry` 必须是有效的 NUL 结尾字符串，`written` 必须是可写的指针，
+// `buffer` 为空或指向至少 `buffer_len` 字节的可写内存。
+int32_t archive_read_entry(const char *path,
+                           const char *entry,
+                           uint8_t *buffer,
+                           size_t buffer_len,
+                           size_t *written);
+
+// 将压缩文件安全地解压到目标目录，拒绝会逃出目标目录的条目
+//
+// `count` 不为空时写入解压的条目数。
+//
+// # Safety
+//
+// `path`、`dest` 必须是有效的 NUL 结尾字符串，`count` 为空或是可写的指针。
+int32_t archive_extract(const char *path, const char *dest, size_t *count);
+
+// 当前线程最近一次失败的错误信息，没有错误时返回空指针
+//
+// 返回的字符串由库持有，在当前线程下一次调用本库函数前有效，不要释放。
+const char *archive_last_error(void);
+
+// 释放本库返回的字符串
+//
+// # Safety
+//
+// `s` 必须是由本库返回的字符串指针，且只能释放一次。
+void archive_free_string(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* QUICKLOOK_ARCHIVE_H */
@@ -0,0 +1,288 @@
+use crate::{ArchiveError, ArchiveFormat};
+use quick_xml::{events::Event, Reader};
+use serde::Serialize;
+use std::{cmp::Ordering, path::Path};
+
+/// 可作为漫画页面的图片扩展名
+const PAGE_EXTENSIONS: [&str; 8] = ["jpg", "jpeg", "png", "gif", "webp", "bmp", "avif", "jxl"];
+
+/// 漫画压缩包中的一页
+#[derive(Debug, Clone, Serialize)]
+pub struct ComicPage {
+    /// 页码（从 0 开始）
+    pub index: usize,
+    /// 压缩包内的条目名
+    pub name: String,
+    /// 图片大小
+    pub size: u64,
+}
+
+/// ComicInfo.xml 中的元数据
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ComicInfo {
+    pub title: Option<String>,
+    pub series: Option<String>,
+    pub number: Option<String>,
+    pub volume: Option<String>,
+    pub summary: Option<String>,
+    pub year: Option<String>,
+    pub month: Option<String>,
+    pub writer: Option<String>,
+    pub penciller: Option<String>,
+    pub publisher: Option<String>,
+    pub genre: Option<String>,
+    pub language: Option<String>,
+    /// 是否为从右向左阅读的日漫
+    pub manga: Option<String>,
+    pub page_count: Option<String>,
+}
+
+/// 漫画压缩包（CBZ/CB7/CBT），RAR 格式的 CBR 暂不支持
+#[derive(Debug, Clone, Serialize)]
+pub struct Comic {
+    /// 实际的压缩格式
+    pub format: ArchiveFormat,
+    /// 按自然顺序排列的页面
+    pub pages: Vec<ComicPage>,
+    /// ComicInfo.xml 元数据
+    pub info: Option<ComicInfo>,
+}
+
+impl Comic {
+    /// 打开漫画压缩包，列出页面并读取 ComicInfo.xml
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ArchiveError> {
+        let path = path.as_ref();
+        let format = ArchiveFormat::detect(path)?;
+        let entries = format.list_entries(path)?;
+
+        let mut images: Vec<_> = entries
+            .iter()
+            .filter(|e| !e.dir && is_page(&e.name))
+            .collect();
+        images.sort_by(|a, b| natural_cmp(&a.name, &b.name));
+        let pages = images
+            .into_iter()
+            .enumerate()
+            .map(|(index, e)| ComicPage {
+                index,
+                name: e.name.clone(),
+                size: e.size,
+            })
+            .collect();
+
+        let info = match entries
+            .iter()
+            .find(|e| !e.dir && base_name(&e.name).eq_ignore_ascii_case("ComicInfo.xml"))
+        {
+            Some(entry) => {
+                let xml = format.read_entry(path, &entry.name)?;
+                Some(parse_comic_info(&String::from_utf8_lossy(&xml)))
+            },
+            None => None,
+        };
+
+        Ok(Comic { format, pages, info })
+    }
+
+    /// 读取指定页的图片数据
+    pub fn read_page<P: AsRef<Path>>(
+        &self,
+        path: P,
+        index: usize,
+    ) -> Result<Vec<u8>, ArchiveError> {
+        let page = self
+            .pages
+            .get(index)
+            .ok_or_else(|| ArchiveError::EntryNotFound(format!("page {}", index)))?;
+        self.format.read_entry(path, &page.name)
+    }
+
+    /// 一次顺序解码读取所有页面，供无法随机访问的格式整本缓存
+    pub fn read_pages<P: AsRef<Path>>(&self, path: P) -> Result<Vec<Vec<u8>>, ArchiveError> {
+        let names: Vec<&str> = self.pages.iter().map(|page| page.name.as_str()).collect();
+        self.format.read_entries(path, &names)
+    }
+}
+
+/// 根据扩展名推断页面图片的 MIME 类型
+pub fn page_mime(name: &str) -> &'static str {
+    match extension(name).as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "avif" => "image/avif",
+        "jxl" => "image/jxl",
+        _ => "application/octet-stream",
+    }
+}
+
+fn base_name(name: &str) -> &str {
+    let trimmed = name.trim_end_matches('/');
+    trimmed.rsplit(['/', '\\']).next().unwrap_or(trimmed)
+}
+
+fn extension(name: &str) -> String {
+    base_name(name)
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_lowercase())
+        .unwrap_or_default()
+}
+
+/// 是否是页面图片，跳过隐藏文件和 macOS 生成的 `__MACOSX` 目录
+fn is_page(name: &str) -> bool {
+    if name
+        .split(['/', '\\'])
+        .any(|part| part.starts_with('.') || part == "__MACOSX")
+    {
+        return false;
+    }
+    PAGE_EXTENSIONS.contains(&extension(name).as_str())
+}
+
+/// 自然排序：数字部分按数值比较，其余部分忽略大小写比较，使 `2.jpg` 排在 `10.jpg` 之前
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x_num = take_number(&mut a_chars);
+                let y_num = take_number(&mut b_chars);
+                let x_trimmed = x_num.trim_start_matches('0');
+                let y_trimmed = y_num.trim_start_matches('0');
+                let ordering = x_trimmed
+                    .len()
+                    .cmp(&y_trimmed.len())
+                    .then_with(|| x_trimmed.cmp(y_trimmed));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            },
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            },
+        }
+    }
+}
+
+fn take_number(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+    let mut number = String::new();
+    while let Some(c) = chars.peek().copied().filter(char::is_ascii_digit) {
+        number.push(c);
+        chars.next();
+    }
+    number
+}
+
+/// 解析 ComicInfo.xml，只读取根元素下的简单文本字段
+fn parse_comic_info(xml: &str) -> ComicInfo {
+    let mut info = ComicInfo::default();
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut depth = 0;
+    let mut current: Option<String> = None;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                depth += 1;
+                current =
+                    (depth == 2).then(|| String::from_utf8_lossy(e.name().as_ref()).to_string());
+            },
+            Ok(Event::End(_)) => {
+                depth -= 1;
+                current = None;
+            },
+            Ok(Event::Text(text)) => {
+                let (Some(field), Ok(value)) = (current.as_deref(), text.unescape()) else {
+                    continue;
+                };
+                let value = Some(value.to_string());
+                match field {
+                    "Title" => info.title = value,
+                    "Series" => info.series = value,
+                    "Number" => info.number = value,
+                    "Volume" => info.volume = value,
+                    "Summary" => info.summary = value,
+                    "Year" => info.year = value,
+                    "Month" => info.month = value,
+                    "Writer" => info.writer = value,
+                    "Penciller" => info.penciller = value,
+                    "Publisher" => info.publisher = value,
+                    "Genre" => info.genre = value,
+                    "LanguageISO" => info.language = value,
+                    "Manga" => info.manga = value,
+                    "PageCount" => info.page_count = value,
+                    _ => {},
+                }
+            },
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {},
+        }
+    }
+
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn natural_order() {
+        let mut names = vec![
+            "page10.jpg",
+            "Page2.jpg",
+            "page1.jpg",
+            "page02.jpg",
+            "cover.jpg",
+            "page2b.jpg",
+            "page100000000000000000000001.jpg",
+            "page99999999999999999999.jpg",
+        ];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(
+            names,
+            [
+                "cover.jpg",
+                "page1.jpg",
+                "Page2.jpg",
+                "page02.jpg",
+                "page2b.jpg",
+                "page10.jpg",
+                "page99999999999999999999.jpg",
+                "page100000000000000000000001.jpg",
+            ]
+        );
+    }
+
+    #[test]
+    fn ties_fall_back_to_bytes() {
+        // 数值与忽略大小写后相同的名称仍有确定的顺序
+        assert_eq!(natural_cmp("a01", "a1"), Ordering::Less);
+        assert_eq!(natural_cmp("A1", "a1"), Ordering::Less);
+        assert_eq!(natural_cmp("a1", "a1"), Ordering::Equal);
+        assert_eq!(natural_cmp("ch1/2.png", "ch1/10.png"), Ordering::Less);
+        assert_eq!(natural_cmp("ch2/1.png", "ch10/1.png"), Ordering::Less);
+    }
+
+    #[test]
+    fn pages() {
+        assert!(is_page("ch1/001.JPG"));
+        assert!(is_page("cover.webp"));
+        assert!(!is_page("__MACOSX/ch1/._001.jpg"));
+        assert!(!is_page(".thumbs/001.jpg"));
+        assert!(!is_page("ComicInfo.xml"));
+    }
+}
@@ -1,4 +1,5 @@
-use std::fmt;
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::{fmt, io};
 
 #[derive(Debug)]
 pub enum ArchiveError {
@@ -7,9 +8,117 @@ pub enum ArchiveError {
     SevenZError(sevenz_rust::Error),
     UnsupportedFormat(String),
     InvalidPath(String),
+    EntryNotFound(String),
+    /// 数据损坏（校验失败、结构非法等）
+    Corrupt {
+        reason: String,
+        entry: Option<String>,
+        offset: Option<u64>,
+    },
+    /// 数据被截断，读到了意外的文件末尾
+    Truncated {
+        entry: Option<String>,
+        offset: Option<u64>,
+    },
+    /// 条目已加密，需要密码
+    PasswordRequired {
+        entry: Option<String>,
+    },
+    /// 超出大小/数量等限制
+    LimitExceeded {
+        limit: String,
+        entry: Option<String>,
+    },
+    /// 不支持的压缩方法
+    UnsupportedCompression {
+        method: String,
+        entry: Option<String>,
+    },
     Other(String),
 }
 
+impl ArchiveError {
+    /// 稳定的错误码，供前端等调用方区分错误类型，不要修改已有的取值
+    pub fn code(&self) -> &'static str {
+        match self {
+            ArchiveError::IoError(_) => "io",
+            ArchiveError::ZipError(_) => "zip",
+            ArchiveError::SevenZError(_) => "sevenz",
+            ArchiveError::UnsupportedFormat(_) => "unsupported_format",
+            ArchiveError::InvalidPath(_) => "invalid_path",
+            ArchiveError::EntryNotFound(_) => "entry_not_found",
+            ArchiveError::Corrupt { .. } => "corrupt",
+            ArchiveError::Truncated { .. } => "truncated",
+            ArchiveError::PasswordRequired { .. } => "password_required",
+            ArchiveError::LimitExceeded { .. } => "limit_exceeded",
+            ArchiveError::UnsupportedCompression { .. } => "unsupported_compression",
+            ArchiveError::Other(_) => "other",
+        }
+    }
+
+    /// 出错的条目路径
+    pub fn entry(&self) -> Option<&str> {
+        match self {
+            ArchiveError::InvalidPath(path) => Some(path),
+            ArchiveError::EntryNotFound(name) => Some(name),
+            ArchiveError::Corrupt { entry, .. }
+            | ArchiveError::Truncated { entry, .. }
+            | ArchiveError::PasswordRequired { entry }
+            | ArchiveError::LimitExceeded { entry, .. }
+            | ArchiveError::UnsupportedCompression { entry, .. } => entry.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// 出错位置在压缩文件（或解压后数据流）中的字节偏移
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            ArchiveError::Corrupt { offset, .. } | ArchiveError::Truncated { offset, .. } => {
+                *offset
+            },
+            _ => None,
+        }
+    }
+
+    /// 补充出错的条目与偏移，已有的上下文不会被覆盖
+    ///
+    /// 条目读取过程中的 IO 错误会按类型转换为 `Truncated` 或 `Corrupt`。
+    pub fn in_entry(self, name: &str, position: Option<u64>) -> Self {
+        let name = Some(name.to_string());
+        match self {
+            ArchiveError::IoError(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                ArchiveError::Truncated { entry: name, offset: position }
+            },
+            ArchiveError::IoError(err) if err.kind() == io::ErrorKind::InvalidData => {
+                ArchiveError::Corrupt {
+                    reason: err.to_string(),
+                    entry: name,
+                    offset: position,
+                }
+            },
+            ArchiveError::Corrupt { reason, entry, offset } => ArchiveError::Corrupt {
+                reason,
+                entry: entry.or(name),
+                offset: offset.or(position),
+            },
+            ArchiveError::Truncated { entry, offset } => ArchiveError::Truncated {
+                entry: entry.or(name),
+                offset: offset.or(position),
+            },
+            ArchiveError::PasswordRequired { entry } => {
+                ArchiveError::PasswordRequired { entry: entry.or(name) }
+            },
+            ArchiveError::LimitExceeded { limit, entry } => {
+                ArchiveError::LimitExceeded { limit, entry: entry.or(name) }
+            },
+            ArchiveError::UnsupportedCompression { method, entry } => {
+                ArchiveError::UnsupportedCompression { method, entry: entry.or(name) }
+            },
+            other => other,
+        }
+    }
+}
+
 impl fmt::Display for ArchiveError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -18,6 +127,14 @@ impl fmt::Display for ArchiveError {
             ArchiveError::SevenZError(err) => write!(f, "7Z error: {}", err),
             ArchiveError::UnsupportedFormat(format) => write!(f, "Unsupported format: {}", format),
             ArchiveError::InvalidPath(path) => write!(f, "Invalid path: {}", path),
+            ArchiveError::EntryNotFound(name) => write!(f, "Entry not found: {}", name),
+            ArchiveError::Corrupt { reason, .. } => write!(f, "Corrupt data: {}", reason),
+            ArchiveError::Truncated { .. } => write!(f, "Truncated data: unexpected end of file"),
+            ArchiveError::PasswordRequired { .. } => write!(f, "Password required"),
+            ArchiveError::LimitExceeded { limit, .. } => write!(f, "Limit exceeded: {}", limit),
+            ArchiveError::UnsupportedCompression { method, .. } => {
+                write!(f, "Unsupported compression method: {}", method)
+            },
             ArchiveError::Other(msg) => write!(f, "Error: {}", msg),
         }
     }
@@ -25,20 +142,83 @@ impl fmt::Display for ArchiveError {
 
 impl std::error::Error for ArchiveError {}
 
+/// 序列化为 `{ code, message, entry?, offset? }`
+impl Serialize for ArchiveError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ArchiveError", 4)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        match self.entry() {
+            Some(entry) => state.serialize_field("entry", entry)?,
+            None => state.skip_field("entry")?,
+        }
+        match self.offset() {
+            Some(offset) => state.serialize_field("offset", &offset)?,
+            None => state.skip_field("offset")?,
+        }
+        state.end()
+    }
+}
+
 impl From<std::io::Error> for ArchiveError {
     fn from(err: std::io::Error) -> Self {
+        if err.kind() == io::ErrorKind::UnexpectedEof {
+            return ArchiveError::Truncated { entry: None, offset: None };
+        }
         ArchiveError::IoError(err)
     }
 }
 
 impl From<zip::result::ZipError> for ArchiveError {
     fn from(err: zip::result::ZipError) -> Self {
-        ArchiveError::ZipError(err)
+        use zip::result::ZipError;
+
+        match err {
+            ZipError::Io(err) => err.into(),
+            ZipError::InvalidArchive(reason) => ArchiveError::Corrupt {
+                reason: reason.to_string(),
+                entry: None,
+                offset: None,
+            },
+            ZipError::UnsupportedArchive(ZipError::PASSWORD_REQUIRED)
+            | ZipError::InvalidPassword => ArchiveError::PasswordRequired { entry: None },
+            ZipError::UnsupportedArchive(reason) if reason.contains("Compression method") => {
+                ArchiveError::UnsupportedCompression { method: reason.to_string(), entry: None }
+            },
+            err => ArchiveError::ZipError(err),
+        }
     }
 }
 
 impl From<sevenz_rust::Error> for ArchiveError {
     fn from(err: sevenz_rust::Error) -> Self {
-        ArchiveError::SevenZError(err)
+        use sevenz_rust::Error;
+
+        match err {
+            Error::Io(err, _) | Error::FileOpen(err, _) => err.into(),
+            Error::PasswordRequired | Error::MaybeBadPassword(_) => {
+                ArchiveError::PasswordRequired { entry: None }
+            },
+            Error::UnsupportedCompressionMethod(method) => {
+                ArchiveError::UnsupportedCompression { method, entry: None }
+            },
+            Error::MaxMemLimited { max_kb, actaul_kb } => ArchiveError::LimitExceeded {
+                limit: format!("memory {} KiB > {} KiB", actaul_kb, max_kb),
+                entry: None,
+            },
+            Error::BadSignature(_)
+            | Error::ChecksumVerificationFailed
+            | Error::NextHeaderCrcMismatch
+            | Error::BadTerminatedStreamsInfo(_)
+            | Error::BadTerminatedUnpackInfo
+            | Error::BadTerminatedPackInfo(_)
+            | Error::BadTerminatedSubStreamsInfo
+            | Error::BadTerminatedheader(_) => ArchiveError::Corrupt {
+                reason: err.to_string(),
+                entry: None,
+                offset: None,
+            },
+            err => ArchiveError::SevenZError(err),
+        }
     }
 }
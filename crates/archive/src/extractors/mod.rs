@@ -5,3 +5,47 @@ pub mod zip;
 pub use sevenz::*;
 pub use tar::*;
 pub use zip::*;
+
+use crate::ArchiveError;
+use std::path::{Component, Path, PathBuf};
+
+/// 读取单个条目到内存时允许的最大大小
+pub const MAX_READ_SIZE: u64 = 1 << 30;
+
+/// 检查条目大小是否超出 `MAX_READ_SIZE`
+pub(crate) fn check_read_size(name: &str, size: u64) -> Result<(), ArchiveError> {
+    if size > MAX_READ_SIZE {
+        return Err(ArchiveError::LimitExceeded {
+            limit: format!("entry size {} > {} bytes", size, MAX_READ_SIZE),
+            entry: Some(name.to_string()),
+        });
+    }
+    Ok(())
+}
+
+/// 按请求顺序整理批量读取的结果，缺失的条目报 `EntryNotFound`
+pub(crate) fn collect_found(
+    names: &[&str],
+    found: Vec<Option<Vec<u8>>>,
+) -> Result<Vec<Vec<u8>>, ArchiveError> {
+    names
+        .iter()
+        .zip(found)
+        .map(|(name, data)| data.ok_or_else(|| ArchiveError::EntryNotFound(name.to_string())))
+        .collect()
+}
+
+/// 将条目名拼接到目标目录下，拒绝绝对路径和 `..` 等会逃出目标目录的条目
+pub(crate) fn safe_join(dest: &Path, name: &str) -> Result<PathBuf, ArchiveError> {
+    let mut target = dest.to_path_buf();
+    for component in Path::new(&name.replace('\\', "/")).components() {
+        match component {
+            Component::Normal(part) => target.push(part),
+            Component::CurDir => {},
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(ArchiveError::InvalidPath(name.to_string()))
+            },
+        }
+    }
+    Ok(target)
+}
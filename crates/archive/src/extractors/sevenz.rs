@@ -1,5 +1,11 @@
+use super::{check_read_size, collect_found, safe_join};
 use crate::{ArchiveError, Extract};
-use std::{fs::File, io::BufReader, path::Path};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader},
+    path::Path,
+};
 
 /// 列举 7Z 文件条目
 pub fn list_7z_entries<P: AsRef<Path>>(path: P) -> Result<Vec<Extract>, ArchiveError> {
@@ -29,3 +35,121 @@ pub fn seven_zip_extract(path: &str) -> Result<Vec<Extract>, ArchiveError> {
     let tree = Extract::build_tree(entries);
     Ok(tree)
 }
+
+/// 读取 7Z 中单个文件的内容
+pub fn read_7z_entry<P: AsRef<Path>>(path: P, name: &str) -> Result<Vec<u8>, ArchiveError> {
+    let mut reader = sevenz_rust::SevenZReader::open(path, sevenz_rust::Password::empty())?;
+    let name = name.trim_end_matches('/');
+    if let Some(entry) = reader
+        .archive()
+        .files
+        .iter()
+        .find(|e| !e.is_directory && e.name.trim_end_matches('/') == name)
+    {
+        check_read_size(name, entry.size)?;
+    }
+    let mut found = None;
+
+    reader
+        .for_each_entries(|entry, data| {
+            if entry.is_directory || entry.name.trim_end_matches('/') != name {
+                // 固实压缩包中后续条目依赖前面的数据，必须读完当前条目
+                io::copy(data, &mut io::sink())?;
+                return Ok(true);
+            }
+            let mut buf = Vec::with_capacity(entry.size as usize);
+            data.read_to_end(&mut buf)?;
+            found = Some(buf);
+            Ok(false)
+        })
+        .map_err(|e| ArchiveError::from(e).in_entry(name, None))?;
+
+    found.ok_or_else(|| ArchiveError::EntryNotFound(name.to_string()))
+}
+
+/// 一次顺序解码读取多个文件条目，按 `names` 的顺序返回内容
+///
+/// 固实压缩包只能从头解码，逐个调用 `read_7z_entry` 会反复解码前面的数据。
+pub fn read_7z_entries<P: AsRef<Path>>(
+    path: P,
+    names: &[&str],
+) -> Result<Vec<Vec<u8>>, ArchiveError> {
+    let mut reader = sevenz_rust::SevenZReader::open(path, sevenz_rust::Password::empty())?;
+    let wanted: HashMap<&str, usize> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.trim_end_matches('/'), i))
+        .collect();
+    for entry in reader.archive().files.iter().filter(|e| !e.is_directory) {
+        let name = entry.name.trim_end_matches('/');
+        if wanted.contains_key(name) {
+            check_read_size(name, entry.size)?;
+        }
+    }
+
+    let mut found: Vec<Option<Vec<u8>>> = vec![None; names.len()];
+    let mut remaining = wanted.len();
+    reader.for_each_entries(|entry, data| {
+        let index = match wanted.get(entry.name.trim_end_matches('/')) {
+            Some(&index) if !entry.is_directory && found[index].is_none() => index,
+            _ => {
+                io::copy(data, &mut io::sink())?;
+                return Ok(true);
+            },
+        };
+        let mut buf = Vec::with_capacity(entry.size as usize);
+        data.read_to_end(&mut buf)?;
+        found[index] = Some(buf);
+        remaining -= 1;
+        Ok(remaining > 0)
+    })?;
+
+    collect_found(names, found)
+}
+
+/// 将 7Z 解压到目标目录，返回解压的条目数
+///
+/// 包含绝对路径或 `..` 的条目会被拒绝，不会写到目标目录之外。
+pub fn extract_7z<P: AsRef<Path>, Q: AsRef<Path>>(path: P, dest: Q) -> Result<usize, ArchiveError> {
+    let dest = dest.as_ref();
+    std::fs::create_dir_all(dest)?;
+
+    let mut reader = sevenz_rust::SevenZReader::open(path, sevenz_rust::Password::empty())?;
+    // 先校验全部条目，避免解压到一半才发现非法路径
+    for entry in reader.archive().files.iter() {
+        safe_join(dest, &entry.name)?;
+    }
+
+    let mut count = 0;
+    reader.for_each_entries(|entry, data| {
+        let target =
+            safe_join(dest, &entry.name).map_err(|e| sevenz_rust::Error::other(e.to_string()))?;
+        if entry.is_directory {
+            std::fs::create_dir_all(&target)?;
+        } else {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut out = File::create(&target)?;
+            io::copy(data, &mut out)?;
+        }
+        count += 1;
+        Ok(true)
+    })?;
+
+    Ok(count)
+}
+
+/// 校验 7Z 文件：完整解压每个条目并校验 CRC，返回校验的条目数
+pub fn test_7z<P: AsRef<Path>>(path: P) -> Result<usize, ArchiveError> {
+    let mut reader = sevenz_rust::SevenZReader::open(path, sevenz_rust::Password::empty())?;
+    let mut count = 0;
+
+    reader.for_each_entries(|_, data| {
+        io::copy(data, &mut io::sink())?;
+        count += 1;
+        Ok(true)
+    })?;
+
+    Ok(count)
+}
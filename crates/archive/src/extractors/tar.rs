@@ -1,14 +1,45 @@
+use super::{check_read_size, collect_found, safe_join};
 use crate::{ArchiveError, Extract};
 use std::{
+    collections::HashMap,
     fs::File,
+    io::{self, BufReader, Read},
     path::Path,
     time::{Duration, UNIX_EPOCH},
 };
 
-/// 列举 TAR 文件条目
-pub fn list_tar_entries<P: AsRef<Path>>(path: P) -> Result<Vec<Extract>, ArchiveError> {
-    let file = File::open(path)?;
-    let mut archive = tar::Archive::new(file);
+/// TAR 外层的压缩方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TarCompression {
+    None,
+    Gz,
+    Bz2,
+    Xz,
+    Zst,
+}
+
+/// 打开 TAR 文件并按压缩方式套上对应的解码器
+fn open_tar<P: AsRef<Path>>(
+    path: P,
+    compression: TarCompression,
+) -> Result<tar::Archive<Box<dyn Read>>, ArchiveError> {
+    let file = BufReader::new(File::open(path)?);
+    let reader: Box<dyn Read> = match compression {
+        TarCompression::None => Box::new(file),
+        TarCompression::Gz => Box::new(flate2::read::GzDecoder::new(file)),
+        TarCompression::Bz2 => Box::new(bzip2::read::BzDecoder::new(file)),
+        TarCompression::Xz => Box::new(xz2::read::XzDecoder::new(file)),
+        TarCompression::Zst => Box::new(zstd::stream::read::Decoder::with_buffer(file)?),
+    };
+    Ok(tar::Archive::new(reader))
+}
+
+/// 列举 TAR 文件条目（可指定外层压缩方式）
+pub fn list_tar_entries_with<P: AsRef<Path>>(
+    path: P,
+    compression: TarCompression,
+) -> Result<Vec<Extract>, ArchiveError> {
+    let mut archive = open_tar(path, compression)?;
     let mut entries = Vec::new();
 
     for entry in archive.entries()? {
@@ -21,10 +52,7 @@ pub fn list_tar_entries<P: AsRef<Path>>(path: P) -> Result<Vec<Extract>, Archive
         let is_dir = header.entry_type().is_dir();
 
         let dt = UNIX_EPOCH + Duration::from_secs(mtime);
-        let last_modified = format!(
-            "{}",
-            chrono::DateTime::<chrono::Local>::from(dt).to_rfc3339()
-        );
+        let last_modified = chrono::DateTime::<chrono::Local>::from(dt).to_rfc3339();
 
         entries.push(Extract::new(name, size, last_modified, is_dir));
     }
@@ -32,86 +60,173 @@ pub fn list_tar_entries<P: AsRef<Path>>(path: P) -> Result<Vec<Extract>, Archive
     Ok(entries)
 }
 
+/// 列举 TAR 文件条目
+pub fn list_tar_entries<P: AsRef<Path>>(path: P) -> Result<Vec<Extract>, ArchiveError> {
+    list_tar_entries_with(path, TarCompression::None)
+}
+
 /// 列举 TAR.GZ 文件条目
 pub fn list_tar_gz_entries<P: AsRef<Path>>(path: P) -> Result<Vec<Extract>, ArchiveError> {
-    let file = File::open(path)?;
-    let gz = flate2::read::GzDecoder::new(file);
-    let mut archive = tar::Archive::new(gz);
-    let mut entries = Vec::new();
-
-    for entry in archive.entries()? {
-        let entry = entry?;
-        let header = entry.header();
-        let path = entry.path()?;
-        let name = path.to_string_lossy().to_string();
-        let size = header.size()?;
-        let mtime = header.mtime()?;
-        let is_dir = header.entry_type().is_dir();
+    list_tar_entries_with(path, TarCompression::Gz)
+}
 
-        let dt = UNIX_EPOCH + Duration::from_secs(mtime);
-        let last_modified = format!(
-            "{}",
-            chrono::DateTime::<chrono::Local>::from(dt).to_rfc3339()
-        );
+/// 列举 TAR.BZ2 文件条目
+pub fn list_tar_bz2_entries<P: AsRef<Path>>(path: P) -> Result<Vec<Extract>, ArchiveError> {
+    list_tar_entries_with(path, TarCompression::Bz2)
+}
 
-        entries.push(Extract::new(name, size, last_modified, is_dir));
-    }
+/// 列举 TAR.XZ 文件条目
+pub fn list_tar_xz_entries<P: AsRef<Path>>(path: P) -> Result<Vec<Extract>, ArchiveError> {
+    list_tar_entries_with(path, TarCompression::Xz)
+}
 
-    Ok(entries)
+/// 列举 TAR.ZST 文件条目
+pub fn list_tar_zst_entries<P: AsRef<Path>>(path: P) -> Result<Vec<Extract>, ArchiveError> {
+    list_tar_entries_with(path, TarCompression::Zst)
 }
 
-/// 列举 TAR.BZ2 文件条目
-pub fn list_tar_bz2_entries<P: AsRef<Path>>(path: P) -> Result<Vec<Extract>, ArchiveError> {
-    let file = File::open(path)?;
-    let bz2 = bzip2::read::BzDecoder::new(file);
-    let mut archive = tar::Archive::new(bz2);
-    let mut entries = Vec::new();
+/// 从条目头中取得 TAR 中单个文件的大小，找到后即停止扫描
+pub fn tar_entry_size<P: AsRef<Path>>(
+    path: P,
+    compression: TarCompression,
+    name: &str,
+) -> Result<u64, ArchiveError> {
+    let mut archive = open_tar(path, compression)?;
+    let name = name.trim_end_matches('/');
 
     for entry in archive.entries()? {
         let entry = entry?;
-        let header = entry.header();
-        let path = entry.path()?;
-        let name = path.to_string_lossy().to_string();
-        let size = header.size()?;
-        let mtime = header.mtime()?;
-        let is_dir = header.entry_type().is_dir();
+        if entry.header().entry_type().is_dir() {
+            continue;
+        }
+        if entry.path()?.to_string_lossy().trim_end_matches('/') == name {
+            return Ok(entry.size());
+        }
+    }
 
-        let dt = UNIX_EPOCH + Duration::from_secs(mtime);
-        let last_modified = format!(
-            "{}",
-            chrono::DateTime::<chrono::Local>::from(dt).to_rfc3339()
-        );
+    Err(ArchiveError::EntryNotFound(name.to_string()))
+}
 
-        entries.push(Extract::new(name, size, last_modified, is_dir));
+/// 读取 TAR 中单个文件的内容
+pub fn read_tar_entry<P: AsRef<Path>>(
+    path: P,
+    compression: TarCompression,
+    name: &str,
+) -> Result<Vec<u8>, ArchiveError> {
+    let mut archive = open_tar(path, compression)?;
+    let name = name.trim_end_matches('/');
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.header().entry_type().is_dir() {
+            continue;
+        }
+        if entry.path()?.to_string_lossy().trim_end_matches('/') == name {
+            check_read_size(name, entry.size())?;
+            let offset = entry.raw_file_position();
+            let mut data = Vec::with_capacity(entry.size() as usize);
+            entry
+                .read_to_end(&mut data)
+                .map_err(|e| ArchiveError::from(e).in_entry(name, Some(offset)))?;
+            return Ok(data);
+        }
     }
 
-    Ok(entries)
+    Err(ArchiveError::EntryNotFound(name.to_string()))
 }
 
-/// 列举 TAR.XZ 文件条目
-pub fn list_tar_xz_entries<P: AsRef<Path>>(path: P) -> Result<Vec<Extract>, ArchiveError> {
-    let file = File::open(path)?;
-    let xz = xz2::read::XzDecoder::new(file);
-    let mut archive = tar::Archive::new(xz);
-    let mut entries = Vec::new();
+/// 一次顺序解码读取多个文件条目，按 `names` 的顺序返回内容
+///
+/// 带外层压缩的 TAR 只能从头解码，逐个调用 `read_tar_entry` 会反复解码前面的数据。
+pub fn read_tar_entries<P: AsRef<Path>>(
+    path: P,
+    compression: TarCompression,
+    names: &[&str],
+) -> Result<Vec<Vec<u8>>, ArchiveError> {
+    let mut archive = open_tar(path, compression)?;
+    let wanted: HashMap<&str, usize> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.trim_end_matches('/'), i))
+        .collect();
+
+    let mut found: Vec<Option<Vec<u8>>> = vec![None; names.len()];
+    let mut remaining = wanted.len();
+    for entry in archive.entries()? {
+        if remaining == 0 {
+            break;
+        }
+        let mut entry = entry?;
+        if entry.header().entry_type().is_dir() {
+            continue;
+        }
+        let path = entry.path()?.to_string_lossy().to_string();
+        let name = path.trim_end_matches('/');
+        let Some(&index) = wanted.get(name).filter(|&&i| found[i].is_none()) else {
+            continue;
+        };
+        check_read_size(name, entry.size())?;
+        let offset = entry.raw_file_position();
+        let mut data = Vec::with_capacity(entry.size() as usize);
+        entry
+            .read_to_end(&mut data)
+            .map_err(|e| ArchiveError::from(e).in_entry(name, Some(offset)))?;
+        found[index] = Some(data);
+        remaining -= 1;
+    }
+
+    collect_found(names, found)
+}
 
+/// 将 TAR 解压到目标目录，返回解压的条目数
+///
+/// 包含绝对路径或 `..` 的条目会被拒绝，不会写到目标目录之外。
+pub fn extract_tar<P: AsRef<Path>, Q: AsRef<Path>>(
+    path: P,
+    compression: TarCompression,
+    dest: Q,
+) -> Result<usize, ArchiveError> {
+    let dest = dest.as_ref();
+    std::fs::create_dir_all(dest)?;
+
+    let mut archive = open_tar(path, compression)?;
+    archive.set_preserve_permissions(false);
+    archive.set_preserve_ownerships(false);
+
+    let mut count = 0;
     for entry in archive.entries()? {
-        let entry = entry?;
-        let header = entry.header();
-        let path = entry.path()?;
-        let name = path.to_string_lossy().to_string();
-        let size = header.size()?;
-        let mtime = header.mtime()?;
-        let is_dir = header.entry_type().is_dir();
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().to_string();
+        let offset = entry.raw_file_position();
+        safe_join(dest, &name)?;
+        let unpacked = entry
+            .unpack_in(dest)
+            .map_err(|e| ArchiveError::from(e).in_entry(&name, Some(offset)))?;
+        if !unpacked {
+            return Err(ArchiveError::InvalidPath(name));
+        }
+        count += 1;
+    }
 
-        let dt = UNIX_EPOCH + Duration::from_secs(mtime);
-        let last_modified = format!(
-            "{}",
-            chrono::DateTime::<chrono::Local>::from(dt).to_rfc3339()
-        );
+    Ok(count)
+}
 
-        entries.push(Extract::new(name, size, last_modified, is_dir));
+/// 校验 TAR 文件：完整解码每个条目，返回校验的条目数
+pub fn test_tar<P: AsRef<Path>>(
+    path: P,
+    compression: TarCompression,
+) -> Result<usize, ArchiveError> {
+    let mut archive = open_tar(path, compression)?;
+    let mut count = 0;
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().to_string();
+        let offset = entry.raw_file_position();
+        io::copy(&mut entry, &mut io::sink())
+            .map_err(|e| ArchiveError::from(e).in_entry(&name, Some(offset)))?;
+        count += 1;
     }
 
-    Ok(entries)
+    Ok(count)
 }
@@ -1,5 +1,10 @@
+use super::{check_read_size, safe_join};
 use crate::{ArchiveError, Extract};
-use std::{fs::File, path::Path};
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
 use zip::{DateTime, ZipArchive};
 
 /// 列举 ZIP 文件条目
@@ -26,22 +31,84 @@ pub fn list_zip_entries<P: AsRef<Path>>(path: P) -> Result<Vec<Extract>, Archive
 
 /// 处理zip格式的压缩文件（兼容旧接口）
 pub fn zip_extract(zip_path: &str) -> Result<Vec<Extract>, ArchiveError> {
-    let file = File::open(zip_path)?;
+    list_zip_entries(zip_path)
+}
+
+/// 读取 ZIP 中单个文件的内容
+pub fn read_zip_entry<P: AsRef<Path>>(path: P, name: &str) -> Result<Vec<u8>, ArchiveError> {
+    let file = File::open(path)?;
+    let mut archive = ZipArchive::new(file)?;
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => {
+            return Err(ArchiveError::EntryNotFound(name.to_string()))
+        },
+        Err(err) => return Err(ArchiveError::from(err).in_entry(name, None)),
+    };
+    if entry.is_dir() {
+        return Err(ArchiveError::EntryNotFound(name.to_string()));
+    }
+    check_read_size(name, entry.size())?;
+
+    let offset = entry.data_start();
+    let mut data = Vec::with_capacity(entry.size() as usize);
+    entry
+        .read_to_end(&mut data)
+        .map_err(|e| ArchiveError::from(e).in_entry(name, Some(offset)))?;
+    Ok(data)
+}
+
+/// 将 ZIP 解压到目标目录，返回解压的条目数
+///
+/// 包含绝对路径或 `..` 的条目会被拒绝，符号链接条目会被跳过。
+pub fn extract_zip<P: AsRef<Path>, Q: AsRef<Path>>(
+    path: P,
+    dest: Q,
+) -> Result<usize, ArchiveError> {
+    let dest = dest.as_ref();
+    std::fs::create_dir_all(dest)?;
+
+    let file = File::open(path)?;
     let mut archive = ZipArchive::new(file)?;
-    let mut target = Vec::new();
+    let mut count = 0;
 
     for i in 0..archive.len() {
-        let file = archive.by_index(i)?;
-        let dir = file.is_dir();
-        let file_name = file.name().to_string();
-        let size = file.size();
-        let last_modified = file
-            .last_modified()
-            .unwrap_or(DateTime::default())
-            .to_string();
+        let mut entry = archive.by_index(i)?;
+        let name = entry.name().to_string();
+        let offset = entry.data_start();
+        let target = safe_join(dest, &name)?;
+
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target)?;
+        } else if entry.is_symlink() {
+            log::warn!("跳过符号链接条目: {}", entry.name());
+            continue;
+        } else {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut out = File::create(&target)?;
+            io::copy(&mut entry, &mut out)
+                .map_err(|e| ArchiveError::from(e).in_entry(&name, Some(offset)))?;
+        }
+        count += 1;
+    }
 
-        target.push(Extract::new(file_name, size, last_modified, dir));
+    Ok(count)
+}
+
+/// 校验 ZIP 文件：完整解压每个条目并校验 CRC，返回校验的条目数
+pub fn test_zip<P: AsRef<Path>>(path: P) -> Result<usize, ArchiveError> {
+    let file = File::open(path)?;
+    let mut archive = ZipArchive::new(file)?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let name = entry.name().to_string();
+        let offset = entry.data_start();
+        io::copy(&mut entry, &mut io::sink())
+            .map_err(|e| ArchiveError::from(e).in_entry(&name, Some(offset)))?;
     }
 
-    Ok(target)
+    Ok(archive.len())
 }
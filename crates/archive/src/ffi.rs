@@ -0,0 +1,464 @@
+//! C ABI 导出
+//!
+//! 所有函数返回 `ARCHIVE_OK`（0）表示成功，负数表示错误码；
+//! 失败时可通过 `archive_last_error` 取得当前线程最近一次的错误信息。
+//! 对应的 C 头文件位于 `include/quicklook_archive.h`，由 cbindgen 生成。
+
+use crate::{extractors::MAX_READ_SIZE, ArchiveError, ArchiveFormat, ArchiveInfo, Extract};
+use std::{
+    cell::RefCell,
+    ffi::{CStr, CString},
+    os::raw::c_char,
+    ptr,
+};
+
+/// 成功
+pub const ARCHIVE_OK: i32 = 0;
+/// 参数无效（空指针、非 UTF-8 字符串等）
+pub const ARCHIVE_ERR_INVALID_ARGUMENT: i32 = -1;
+/// IO 错误
+pub const ARCHIVE_ERR_IO: i32 = -2;
+/// ZIP 解析错误
+pub const ARCHIVE_ERR_ZIP: i32 = -3;
+/// 7Z 解析错误
+pub const ARCHIVE_ERR_SEVENZ: i32 = -4;
+/// 不支持的格式
+pub const ARCHIVE_ERR_UNSUPPORTED_FORMAT: i32 = -5;
+/// 非法路径（如条目试图解压到目标目录之外）
+pub const ARCHIVE_ERR_INVALID_PATH: i32 = -6;
+/// 条目不存在
+pub const ARCHIVE_ERR_ENTRY_NOT_FOUND: i32 = -7;
+/// 调用方提供的缓冲区太小
+pub const ARCHIVE_ERR_BUFFER_TOO_SMALL: i32 = -8;
+/// 数据损坏
+pub const ARCHIVE_ERR_CORRUPT: i32 = -9;
+/// 数据被截断
+pub const ARCHIVE_ERR_TRUNCATED: i32 = -10;
+/// 需要密码
+pub const ARCHIVE_ERR_PASSWORD_REQUIRED: i32 = -11;
+/// 超出大小/数量等限制
+pub const ARCHIVE_ERR_LIMIT_EXCEEDED: i32 = -12;
+/// 不支持的压缩方法
+pub const ARCHIVE_ERR_UNSUPPORTED_COMPRESSION: i32 = -13;
+/// 其他错误
+pub const ARCHIVE_ERR_OTHER: i32 = -99;
+
+/// 读取单个条目到内存时允许的最大大小
+pub const ARCHIVE_MAX_READ_SIZE: u64 = 1 << 30;
+
+const _: () = assert!(ARCHIVE_MAX_READ_SIZE == MAX_READ_SIZE);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// `ArchiveError` 到 C 错误码的映射
+pub fn error_code(err: &ArchiveError) -> i32 {
+    match err {
+        ArchiveError::IoError(_) => ARCHIVE_ERR_IO,
+        ArchiveError::ZipError(_) => ARCHIVE_ERR_ZIP,
+        ArchiveError::SevenZError(_) => ARCHIVE_ERR_SEVENZ,
+        ArchiveError::UnsupportedFormat(_) => ARCHIVE_ERR_UNSUPPORTED_FORMAT,
+        ArchiveError::InvalidPath(_) => ARCHIVE_ERR_INVALID_PATH,
+        ArchiveError::EntryNotFound(_) => ARCHIVE_ERR_ENTRY_NOT_FOUND,
+        ArchiveError::Corrupt { .. } => ARCHIVE_ERR_CORRUPT,
+        ArchiveError::Truncated { .. } => ARCHIVE_ERR_TRUNCATED,
+        ArchiveError::PasswordRequired { .. } => ARCHIVE_ERR_PASSWORD_REQUIRED,
+        ArchiveError::LimitExceeded { .. } => ARCHIVE_ERR_LIMIT_EXCEEDED,
+        ArchiveError::UnsupportedCompression { .. } => ARCHIVE_ERR_UNSUPPORTED_COMPRESSION,
+        ArchiveError::Other(_) => ARCHIVE_ERR_OTHER,
+    }
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', ""))
+        .unwrap_or_else(|_| CString::new("unknown error").unwrap());
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+fn clear_last_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+}
+
+/// 记录错误信息并返回对应的错误码
+fn fail(code: i32, message: String) -> i32 {
+    set_last_error(message);
+    code
+}
+
+fn fail_with(err: ArchiveError) -> i32 {
+    fail(error_code(&err), err.to_string())
+}
+
+/// 将 C 字符串参数转换为 `&str`
+unsafe fn arg_str<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, i32> {
+    if ptr.is_null() {
+        return Err(fail(
+            ARCHIVE_ERR_INVALID_ARGUMENT,
+            format!("{} is null", name),
+        ));
+    }
+    CStr::from_ptr(ptr).to_str().map_err(|_| {
+        fail(
+            ARCHIVE_ERR_INVALID_ARGUMENT,
+            format!("{} is not valid UTF-8", name),
+        )
+    })
+}
+
+/// 将 JSON 字符串写入输出参数
+unsafe fn write_json<T: serde::Serialize>(value: &T, result: *mut *mut c_char) -> i32 {
+    let json = match serde_json::to_string(value) {
+        Ok(json) => json,
+        Err(err) => return fail(ARCHIVE_ERR_OTHER, err.to_string()),
+    };
+    match CString::new(json) {
+        Ok(c_string) => {
+            *result = c_string.into_raw();
+            ARCHIVE_OK
+        },
+        Err(err) => fail(ARCHIVE_ERR_OTHER, err.to_string()),
+    }
+}
+
+/// 列举压缩文件条目，结果为树状结构的 JSON 字符串，需使用 `archive_free_string` 释放
+///
+/// # Safety
+///
+/// `path` 必须是有效的 NUL 结尾字符串，`result` 必须是可写的指针。
+#[no_mangle]
+pub unsafe extern "C" fn archive_list_entries(
+    path: *const c_char,
+    result: *mut *mut c_char,
+) -> i32 {
+    clear_last_error();
+    if result.is_null() {
+        return fail(ARCHIVE_ERR_INVALID_ARGUMENT, "result is null".to_string());
+    }
+    let path = match arg_str(path, "path") {
+        Ok(path) => path,
+        Err(code) => return code,
+    };
+
+    match Extract::list_archive_tree(path) {
+        Ok(entries) => write_json(&entries, result),
+        Err(err) => fail_with(err),
+    }
+}
+
+/// 识别压缩文件格式，结果写入 `format`（取值见 `ArchiveFormat`）
+///
+/// # Safety
+///
+/// `path` 必须是有效的 NUL 结尾字符串，`format` 必须是可写的指针。
+#[no_mangle]
+pub unsafe extern "C" fn archive_detect_format(
+    path: *const c_char,
+    format: *mut ArchiveFormat,
+) -> i32 {
+    clear_last_error();
+    if format.is_null() {
+        return fail(ARCHIVE_ERR_INVALID_ARGUMENT, "format is null".to_string());
+    }
+    let path = match arg_str(path, "path") {
+        Ok(path) => path,
+        Err(code) => return code,
+    };
+
+    match ArchiveFormat::detect(path) {
+        Ok(detected) => {
+            *format = detected;
+            ARCHIVE_OK
+        },
+        Err(err) => fail_with(err),
+    }
+}
+
+/// 读取压缩文件元信息，结果为 JSON 字符串，需使用 `archive_free_string` 释放
+///
+/// # Safety
+///
+/// `path` 必须是有效的 NUL 结尾字符串，`result` 必须是可写的指针。
+#[no_mangle]
+pub unsafe extern "C" fn archive_info(path: *const c_char, result: *mut *mut c_char) -> i32 {
+    clear_last_error();
+    if result.is_null() {
+        return fail(ARCHIVE_ERR_INVALID_ARGUMENT, "result is null".to_string());
+    }
+    let path = match arg_str(path, "path") {
+        Ok(path) => path,
+        Err(code) => return code,
+    };
+
+    match ArchiveInfo::read(path) {
+        Ok(info) => write_json(&info, result),
+        Err(err) => fail_with(err),
+    }
+}
+
+/// 将单个条目的内容读取到调用方提供的缓冲区
+///
+/// `written` 总是写入条目的实际大小；`buffer` 为空或 `buffer_len` 不足时返回
+/// `ARCHIVE_ERR_BUFFER_TOO_SMALL`，调用方可据此分配缓冲区后重试。
+/// 查询大小时只读取条目元数据，不会解压内容。
+///
+/// # Safety
+///
+/// `path`、`entry` 必须是有效的 NUL 结尾字符串，`written` 必须是可写的指针，
+/// `buffer` 为空或指向至少 `buffer_len` 字节的可写内存。
+#[no_mangle]
+pub unsafe extern "C" fn archive_read_entry(
+    path: *const c_char,
+    entry: *const c_char,
+    buffer: *mut u8,
+    buffer_len: usize,
+    written: *mut usize,
+) -> i32 {
+    clear_last_error();
+    if written.is_null() {
+        return fail(ARCHIVE_ERR_INVALID_ARGUMENT, "written is null".to_string());
+    }
+    let path = match arg_str(path, "path") {
+        Ok(path) => path,
+        Err(code) => return code,
+    };
+    let entry = match arg_str(entry, "entry") {
+        Ok(entry) => entry,
+        Err(code) => return code,
+    };
+
+    let format = match ArchiveFormat::detect(path) {
+        Ok(format) => format,
+        Err(err) => return fail_with(err),
+    };
+
+    // 先按元数据回答大小，缓冲区不足时无需解压
+    let size = match format.entry_size(path, entry) {
+        Ok(size) => size as usize,
+        Err(err) => return fail_with(err),
+    };
+    if buffer.is_null() || buffer_len < size {
+        *written = size;
+        return fail(
+            ARCHIVE_ERR_BUFFER_TOO_SMALL,
+            format!("buffer too small: need {} bytes", size),
+        );
+    }
+
+    let data = match format.read_entry(path, entry) {
+        Ok(data) => data,
+        Err(err) => return fail_with(err),
+    };
+
+    *written = data.len();
+    if buffer_len < data.len() {
+        return fail(
+            ARCHIVE_ERR_BUFFER_TOO_SMALL,
+            format!("buffer too small: need {} bytes", data.len()),
+        );
+    }
+    ptr::copy_nonoverlapping(data.as_ptr(), buffer, data.len());
+    ARCHIVE_OK
+}
+
+/// 将压缩文件安全地解压到目标目录，拒绝会逃出目标目录的条目
+///
+/// `count` 不为空时写入解压的条目数。
+///
+/// # Safety
+///
+/// `path`、`dest` 必须是有效的 NUL 结尾字符串，`count` 为空或是可写的指针。
+#[no_mangle]
+pub unsafe extern "C" fn archive_extract(
+    path: *const c_char,
+    dest: *const c_char,
+    count: *mut usize,
+) -> i32 {
+    clear_last_error();
+    let path = match arg_str(path, "path") {
+        Ok(path) => path,
+        Err(code) => return code,
+    };
+    let dest = match arg_str(dest, "dest") {
+        Ok(dest) => dest,
+        Err(code) => return code,
+    };
+
+    match ArchiveFormat::detect(path).and_then(|format| format.extract(path, dest)) {
+        Ok(extracted) => {
+            if !count.is_null() {
+                *count = extracted;
+            }
+            ARCHIVE_OK
+        },
+        Err(err) => fail_with(err),
+    }
+}
+
+/// 当前线程最近一次失败的错误信息，没有错误时返回空指针
+///
+/// 返回的字符串由库持有，在当前线程下一次调用本库函数前有效，不要释放。
+#[no_mangle]
+pub extern "C" fn archive_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// 释放本库返回的字符串
+///
+/// # Safety
+///
+/// `s` 必须是由本库返回的字符串指针，且只能释放一次。
+#[no_mangle]
+pub unsafe extern "C" fn archive_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writers::{create_zip, CompressOptions};
+    use std::{fs, path::PathBuf};
+
+    /// 含 `hello.txt`（5 字节）的 ZIP 文件
+    fn fixture(name: &str) -> (PathBuf, CString) {
+        let dir = std::env::temp_dir().join(format!("ffi-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("hello.txt"), "hello").unwrap();
+        let zip = dir.join("fixture.zip");
+        create_zip(
+            &[dir.join("hello.txt")],
+            &zip,
+            &CompressOptions::default(),
+            |_| {},
+        )
+        .unwrap();
+        let path = CString::new(zip.to_str().unwrap()).unwrap();
+        (dir, path)
+    }
+
+    fn last_error() -> Option<String> {
+        let message = archive_last_error();
+        (!message.is_null()).then(|| {
+            unsafe { CStr::from_ptr(message) }
+                .to_string_lossy()
+                .into_owned()
+        })
+    }
+
+    #[test]
+    fn error_codes() {
+        let (dir, path) = fixture("codes");
+        let mut result = ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                archive_list_entries(ptr::null(), &mut result),
+                ARCHIVE_ERR_INVALID_ARGUMENT
+            );
+            assert_eq!(last_error().as_deref(), Some("path is null"));
+            assert_eq!(
+                archive_list_entries(path.as_ptr(), ptr::null_mut()),
+                ARCHIVE_ERR_INVALID_ARGUMENT
+            );
+
+            let missing = CString::new(dir.join("missing.zip").to_str().unwrap()).unwrap();
+            assert_eq!(archive_info(missing.as_ptr(), &mut result), ARCHIVE_ERR_IO);
+            assert!(last_error().is_some());
+
+            let invalid = [0xFFu8, 0];
+            assert_eq!(
+                archive_info(invalid.as_ptr() as *const c_char, &mut result),
+                ARCHIVE_ERR_INVALID_ARGUMENT
+            );
+
+            let mut format = ArchiveFormat::Tar;
+            assert_eq!(
+                archive_detect_format(path.as_ptr(), &mut format),
+                ARCHIVE_OK
+            );
+            assert_eq!(format, ArchiveFormat::Zip);
+            assert!(last_error().is_none());
+
+            assert_eq!(archive_list_entries(path.as_ptr(), &mut result), ARCHIVE_OK);
+            let json = CStr::from_ptr(result).to_str().unwrap().to_string();
+            archive_free_string(result);
+            assert!(json.contains("hello.txt"));
+
+            let entry = CString::new("missing.txt").unwrap();
+            let mut written = 0;
+            assert_eq!(
+                archive_read_entry(
+                    path.as_ptr(),
+                    entry.as_ptr(),
+                    ptr::null_mut(),
+                    0,
+                    &mut written
+                ),
+                ARCHIVE_ERR_ENTRY_NOT_FOUND
+            );
+
+            let out = CString::new(dir.join("out").to_str().unwrap()).unwrap();
+            let mut count = 0;
+            assert_eq!(
+                archive_extract(path.as_ptr(), out.as_ptr(), &mut count),
+                ARCHIVE_OK
+            );
+            assert_eq!(count, 1);
+            assert_eq!(fs::read(dir.join("out/hello.txt")).unwrap(), b"hello");
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn buffer_too_small() {
+        let (dir, path) = fixture("buffer");
+        let entry = CString::new("hello.txt").unwrap();
+        let mut written = 0;
+        let mut buffer = [0u8; 8];
+        unsafe {
+            let read = |buffer: *mut u8, len: usize, written: &mut usize| {
+                archive_read_entry(path.as_ptr(), entry.as_ptr(), buffer, len, written)
+            };
+            // 查询大小
+            assert_eq!(
+                read(ptr::null_mut(), 0, &mut written),
+                ARCHIVE_ERR_BUFFER_TOO_SMALL
+            );
+            assert_eq!(written, 5);
+            assert_eq!(
+                last_error().as_deref(),
+                Some("buffer too small: need 5 bytes")
+            );
+
+            written = 0;
+            assert_eq!(
+                read(buffer.as_mut_ptr(), 4, &mut written),
+                ARCHIVE_ERR_BUFFER_TOO_SMALL
+            );
+            assert_eq!((written, buffer), (5, [0; 8]));
+
+            assert_eq!(
+                read(buffer.as_mut_ptr(), buffer.len(), &mut written),
+                ARCHIVE_OK
+            );
+            assert_eq!(&buffer[..written], b"hello");
+            assert!(last_error().is_none());
+
+            let code = archive_read_entry(
+                path.as_ptr(),
+                entry.as_ptr(),
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                ptr::null_mut(),
+            );
+            assert_eq!(code, ARCHIVE_ERR_INVALID_ARGUMENT);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{extractors, ArchiveError, Extract, TarCompression};
+use serde::Serialize;
+use std::{fs::File, io::Read, path::Path};
+
+/// 压缩文件格式
+///
+/// 取值与 C ABI 中的格式编号一一对应，不要修改已有的数值。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[repr(i32)]
+pub enum ArchiveFormat {
+    #[serde(rename = "zip")]
+    Zip = 1,
+    #[serde(rename = "tar")]
+    Tar = 2,
+    #[serde(rename = "tar.gz")]
+    TarGz = 3,
+    #[serde(rename = "tar.bz2")]
+    TarBz2 = 4,
+    #[serde(rename = "tar.xz")]
+    TarXz = 5,
+    #[serde(rename = "tar.zst")]
+    TarZst = 6,
+    #[serde(rename = "7z")]
+    SevenZ = 7,
+}
+
+/// 压缩文件元信息
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveInfo {
+    /// 格式
+    pub format: ArchiveFormat,
+    /// 文件数
+    pub files: usize,
+    /// 目录数
+    pub dirs: usize,
+    /// 解压后的总大小
+    pub total_size: u64,
+    /// 压缩文件本身的大小
+    pub archive_size: u64,
+}
+
+impl ArchiveFormat {
+    /// 根据扩展名推断格式
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "zip" | "cbz" => Some(ArchiveFormat::Zip),
+            "tar" | "cbt" => Some(ArchiveFormat::Tar),
+            "gz" | "tgz" => Some(ArchiveFormat::TarGz),
+            "bz2" | "tbz2" => Some(ArchiveFormat::TarBz2),
+            "xz" | "txz" => Some(ArchiveFormat::TarXz),
+            "zst" | "tzst" => Some(ArchiveFormat::TarZst),
+            "7z" | "cb7" => Some(ArchiveFormat::SevenZ),
+            _ => None,
+        }
+    }
+
+    /// 根据文件头的魔数推断格式
+    pub fn from_magic(header: &[u8]) -> Option<Self> {
+        if header.starts_with(b"PK\x03\x04")
+            || header.starts_with(b"PK\x05\x06")
+            || header.starts_with(b"PK\x07\x08")
+        {
+            Some(ArchiveFormat::Zip)
+        } else if header.starts_with(&[0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C]) {
+            Some(ArchiveFormat::SevenZ)
+        } else if header.starts_with(&[0x1F, 0x8B]) {
+            Some(ArchiveFormat::TarGz)
+        } else if header.starts_with(b"BZh") {
+            Some(ArchiveFormat::TarBz2)
+        } else if header.starts_with(&[0xFD, b'7', b'z', b'X', b'Z', 0x00]) {
+            Some(ArchiveFormat::TarXz)
+        } else if header.starts_with(&[0x28, 0xB5, 0x2F, 0xFD]) {
+            Some(ArchiveFormat::TarZst)
+        } else if header.len() >= 262 && &header[257..262] == b"ustar" {
+            Some(ArchiveFormat::Tar)
+        } else {
+            None
+        }
+    }
+
+    /// 识别压缩文件格式，优先使用文件头，无法识别时回退到扩展名
+    pub fn detect<P: AsRef<Path>>(path: P) -> Result<Self, ArchiveError> {
+        let path = path.as_ref();
+        let mut header = Vec::with_capacity(512);
+        File::open(path)?.take(512).read_to_end(&mut header)?;
+
+        // RAR（含 .cbr）暂不支持，直接给出明确的格式名
+        if header.starts_with(b"Rar!\x1a\x07") {
+            return Err(ArchiveError::UnsupportedFormat("rar".to_string()));
+        }
+
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        Self::from_magic(&header)
+            .or_else(|| Self::from_extension(&extension))
+            .ok_or(ArchiveError::UnsupportedFormat(extension))
+    }
+
+    /// TAR 系列格式的外层压缩方式，非 TAR 格式返回 `TarCompression::None`
+    fn tar_compression(&self) -> TarCompression {
+        match self {
+            ArchiveFormat::TarGz => TarCompression::Gz,
+            ArchiveFormat::TarBz2 => TarCompression::Bz2,
+            ArchiveFormat::TarXz => TarCompression::Xz,
+            ArchiveFormat::TarZst => TarCompression::Zst,
+            ArchiveFormat::Tar | ArchiveFormat::Zip | ArchiveFormat::SevenZ => TarCompression::None,
+        }
+    }
+
+    /// 列举条目（扁平列表）
+    pub fn list_entries<P: AsRef<Path>>(&self, path: P) -> Result<Vec<Extract>, ArchiveError> {
+        match self {
+            ArchiveFormat::Zip => extractors::zip::list_zip_entries(path),
+            ArchiveFormat::SevenZ => extractors::sevenz::list_7z_entries(path),
+            _ => extractors::tar::list_tar_entries_with(path, self.tar_compression()),
+        }
+    }
+
+    /// 从条目元数据中取得单个文件条目的大小，不解压内容
+    pub fn entry_size<P: AsRef<Path>>(&self, path: P, name: &str) -> Result<u64, ArchiveError> {
+        let find = |entries: Vec<Extract>| {
+            let name = name.trim_end_matches('/');
+            entries
+                .into_iter()
+                .find(|e| !e.dir && e.name.trim_end_matches('/') == name)
+                .map(|e| e.size)
+                .ok_or_else(|| ArchiveError::EntryNotFound(name.to_string()))
+        };
+        match self {
+            ArchiveFormat::Zip => find(extractors::zip::list_zip_entries(path)?),
+            ArchiveFormat::SevenZ => find(extractors::sevenz::list_7z_entries(path)?),
+            _ => extractors::tar::tar_entry_size(path, self.tar_compression(), name),
+        }
+    }
+
+    /// 读取单个文件条目的内容
+    pub fn read_entry<P: AsRef<Path>>(&self, path: P, name: &str) -> Result<Vec<u8>, ArchiveError> {
+        match self {
+            ArchiveFormat::Zip => extractors::zip::read_zip_entry(path, name),
+            ArchiveFormat::SevenZ => extractors::sevenz::read_7z_entry(path, name),
+            _ => extractors::tar::read_tar_entry(path, self.tar_compression(), name),
+        }
+    }
+
+    /// 能否按条目随机读取；7Z 与带外层压缩的 TAR 只能从头顺序解码
+    pub fn random_access(&self) -> bool {
+        matches!(self, ArchiveFormat::Zip | ArchiveFormat::Tar)
+    }
+
+    /// 一次顺序解码读取多个文件条目，按 `names` 的顺序返回内容
+    pub fn read_entries<P: AsRef<Path>>(
+        &self,
+        path: P,
+        names: &[&str],
+    ) -> Result<Vec<Vec<u8>>, ArchiveError> {
+        match self {
+            ArchiveFormat::Zip => {
+                let path = path.as_ref();
+                names
+                    .iter()
+                    .map(|name| extractors::zip::read_zip_entry(path, name))
+                    .collect()
+            },
+            ArchiveFormat::SevenZ => extractors::sevenz::read_7z_entries(path, names),
+            _ => extractors::tar::read_tar_entries(path, self.tar_compression(), names),
+        }
+    }
+
+    /// 安全解压到目标目录，返回解压的条目数
+    pub fn extract<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        path: P,
+        dest: Q,
+    ) -> Result<usize, ArchiveError> {
+        match self {
+            ArchiveFormat::Zip => extractors::zip::extract_zip(path, dest),
+            ArchiveFormat::SevenZ => extractors::sevenz::extract_7z(path, dest),
+            _ => extractors::tar::extract_tar(path, self.tar_compression(), dest),
+        }
+    }
+
+    /// 完整解码所有条目以校验压缩文件，返回校验的条目数
+    pub fn test<P: AsRef<Path>>(&self, path: P) -> Result<usize, ArchiveError> {
+        match self {
+            ArchiveFormat::Zip => extractors::zip::test_zip(path),
+            ArchiveFormat::SevenZ => extractors::sevenz::test_7z(path),
+            _ => extractors::tar::test_tar(path, self.tar_compression()),
+        }
+    }
+}
+
+impl ArchiveInfo {
+    /// 读取压缩文件的元信息
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, ArchiveError> {
+        let path = path.as_ref();
+        let format = ArchiveFormat::detect(path)?;
+        let entries = format.list_entries(path)?;
+
+        let dirs = entries.iter().filter(|e| e.dir).count();
+        Ok(ArchiveInfo {
+            format,
+            files: entries.len() - dirs,
+            dirs,
+            total_size: entries.iter().map(|e| e.size).sum(),
+            archive_size: std::fs::metadata(path)?.len(),
+        })
+    }
+}
@@ -1,11 +1,18 @@
 use serde::Serialize;
 use std::path::Path;
 
+pub mod comic;
 pub mod error;
 pub mod extractors;
+pub mod ffi;
+pub mod format;
+pub mod writers;
 
+pub use comic::{Comic, ComicInfo, ComicPage};
 pub use error::ArchiveError;
 pub use extractors::*;
+pub use format::{ArchiveFormat, ArchiveInfo};
+pub use writers::{compress, CompressFormat, CompressOptions, CompressProgress};
 
 /// 压缩文件条目信息
 #[derive(Debug, Clone, Serialize)]
@@ -39,22 +46,8 @@ impl Extract {
         archive_path: P,
     ) -> Result<Vec<Extract>, ArchiveError> {
         let path = archive_path.as_ref();
-        let extension = path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("")
-            .to_lowercase();
-
-        let entries = match extension.as_str() {
-            "zip" => extractors::zip::list_zip_entries(path)?,
-            "tar" => extractors::tar::list_tar_entries(path)?,
-            "gz" | "tgz" => extractors::tar::list_tar_gz_entries(path)?,
-            "bz2" | "tbz2" => extractors::tar::list_tar_bz2_entries(path)?,
-            "xz" | "txz" => extractors::tar::list_tar_xz_entries(path)?,
-            "7z" => extractors::sevenz::list_7z_entries(path)?,
-            // 对于其他格式，返回错误
-            _ => return Err(ArchiveError::UnsupportedFormat(extension)),
-        };
+        let format = ArchiveFormat::detect(path)?;
+        let entries = format.list_entries(path)?;
 
         let tree = Self::build_tree(entries);
         Ok(tree)
@@ -90,7 +83,7 @@ impl Extract {
     }
 
     fn build_tree_recursive(
-        items: &mut Vec<Extract>,
+        items: &mut [Extract],
         dirs_map: &std::collections::HashMap<String, Vec<Extract>>,
     ) {
         for item in items.iter_mut() {
@@ -104,48 +97,3 @@ impl Extract {
         }
     }
 }
-
-// 导出 C ABI 兼容的函数
-#[no_mangle]
-pub extern "C" fn archive_list_entries(
-    path: *const std::os::raw::c_char,
-    result: *mut *mut std::os::raw::c_char,
-) -> i32 {
-    use std::ffi::{CStr, CString};
-
-    if path.is_null() || result.is_null() {
-        return -1;
-    }
-
-    let path_str = unsafe {
-        match CStr::from_ptr(path).to_str() {
-            Ok(s) => s,
-            Err(_) => return -1,
-        }
-    };
-
-    match Extract::list_archive_tree(path_str) {
-        Ok(entries) => match serde_json::to_string(&entries) {
-            Ok(json) => match CString::new(json) {
-                Ok(c_string) => {
-                    unsafe {
-                        *result = c_string.into_raw();
-                    }
-                    0
-                },
-                Err(_) => -1,
-            },
-            Err(_) => -1,
-        },
-        Err(_) => -1,
-    }
-}
-
-#[no_mangle]
-pub extern "C" fn archive_free_string(s: *mut std::os::raw::c_char) {
-    if !s.is_null() {
-        unsafe {
-            drop(std::ffi::CString::from_raw(s));
-        }
-    }
-}
@@ -0,0 +1,214 @@
+use quicklook_archive::{ffi, ArchiveError, ArchiveFormat, ArchiveInfo, Extract};
+use std::{
+    io::{self, Write},
+    process::ExitCode,
+};
+
+const USAGE: &str = "\
+用法: quicklook-archive <命令> [选项] <压缩文件>
+
+命令:
+    list [--flat] [--json] <压缩文件>    列举条目（默认树状表格）
+    info [--json] <压缩文件>             显示压缩文件信息
+    cat <压缩文件> <条目>                将条目内容输出到标准输出
+    test <压缩文件>                      完整解码所有条目以校验压缩文件
+    extract <压缩文件> [目标目录]        安全解压到目标目录（默认当前目录）
+
+退出码与 C ABI 错误码的绝对值一致，例如 2 表示 IO 错误，5 表示不支持的格式。";
+
+/// 命令行错误：参数错误或压缩文件处理错误
+enum CliError {
+    Usage(String),
+    Archive(ArchiveError),
+}
+
+impl From<ArchiveError> for CliError {
+    fn from(err: ArchiveError) -> Self {
+        CliError::Archive(err)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(err: io::Error) -> Self {
+        CliError::Archive(ArchiveError::IoError(err))
+    }
+}
+
+/// 拆分出 `--xxx` 形式的开关和位置参数
+fn split_args(args: &[String]) -> (Vec<&str>, Vec<&str>) {
+    args.iter()
+        .map(String::as_str)
+        .partition(|arg| arg.starts_with("--"))
+}
+
+fn check_flags(flags: &[&str], allowed: &[&str]) -> Result<(), CliError> {
+    match flags.iter().find(|flag| !allowed.contains(flag)) {
+        Some(flag) => Err(CliError::Usage(format!("未知选项: {}", flag))),
+        None => Ok(()),
+    }
+}
+
+fn positional<'a>(args: &[&'a str], index: usize, name: &str) -> Result<&'a str, CliError> {
+    args.get(index)
+        .copied()
+        .ok_or_else(|| CliError::Usage(format!("缺少参数: {}", name)))
+}
+
+fn print_json<T: serde::Serialize>(value: &T) -> Result<(), CliError> {
+    let mut out = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut out, value).map_err(io::Error::from)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+fn print_row(out: &mut impl Write, entry: &Extract, name: &str) -> io::Result<()> {
+    let size = if entry.dir {
+        "-".to_string()
+    } else {
+        entry.size.to_string()
+    };
+    writeln!(out, "{:>12}  {:<25}  {}", size, entry.last_modified, name)
+}
+
+fn print_tree(out: &mut impl Write, entries: &[Extract], depth: usize) -> io::Result<()> {
+    for entry in entries {
+        let trimmed = entry.name.trim_end_matches('/');
+        let base = trimmed.rsplit('/').next().unwrap_or(trimmed);
+        let suffix = if entry.dir { "/" } else { "" };
+        let name = format!("{}{}{}", "  ".repeat(depth), base, suffix);
+        print_row(out, entry, &name)?;
+        if let Some(children) = &entry.children {
+            print_tree(out, children, depth + 1)?;
+        }
+    }
+    Ok(())
+}
+
+fn list(args: &[String]) -> Result<(), CliError> {
+    let (flags, rest) = split_args(args);
+    check_flags(&flags, &["--flat", "--json"])?;
+    let path = positional(&rest, 0, "压缩文件")?;
+    let flat = flags.contains(&"--flat");
+    let json = flags.contains(&"--json");
+
+    let entries = if flat {
+        let mut entries = ArchiveFormat::detect(path)?.list_entries(path)?;
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        entries
+    } else {
+        Extract::list_archive_tree(path)?
+    };
+
+    if json {
+        return print_json(&entries);
+    }
+
+    let mut out = io::stdout().lock();
+    writeln!(out, "{:>12}  {:<25}  名称", "大小", "修改时间")?;
+    if flat {
+        for entry in &entries {
+            print_row(&mut out, entry, &entry.name)?;
+        }
+    } else {
+        print_tree(&mut out, &entries, 0)?;
+    }
+    Ok(())
+}
+
+fn info(args: &[String]) -> Result<(), CliError> {
+    let (flags, rest) = split_args(args);
+    check_flags(&flags, &["--json"])?;
+    let path = positional(&rest, 0, "压缩文件")?;
+    let info = ArchiveInfo::read(path)?;
+
+    if flags.contains(&"--json") {
+        return print_json(&info);
+    }
+
+    let format = serde_json::to_value(info.format)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+    println!("格式:       {}", format);
+    println!("文件数:     {}", info.files);
+    println!("目录数:     {}", info.dirs);
+    println!("解压后大小: {}", info.total_size);
+    println!("文件大小:   {}", info.archive_size);
+    Ok(())
+}
+
+fn cat(args: &[String]) -> Result<(), CliError> {
+    let (flags, rest) = split_args(args);
+    check_flags(&flags, &[])?;
+    let path = positional(&rest, 0, "压缩文件")?;
+    let entry = positional(&rest, 1, "条目")?;
+
+    let data = ArchiveFormat::detect(path)?.read_entry(path, entry)?;
+    let mut out = io::stdout().lock();
+    out.write_all(&data)?;
+    out.flush()?;
+    Ok(())
+}
+
+fn test(args: &[String]) -> Result<(), CliError> {
+    let (flags, rest) = split_args(args);
+    check_flags(&flags, &[])?;
+    let path = positional(&rest, 0, "压缩文件")?;
+
+    let count = ArchiveFormat::detect(path)?.test(path)?;
+    println!("校验通过，共 {} 个条目", count);
+    Ok(())
+}
+
+fn extract(args: &[String]) -> Result<(), CliError> {
+    let (flags, rest) = split_args(args);
+    check_flags(&flags, &[])?;
+    let path = positional(&rest, 0, "压缩文件")?;
+    let dest = rest.get(1).copied().unwrap_or(".");
+
+    let count = ArchiveFormat::detect(path)?.extract(path, dest)?;
+    println!("已解压 {} 个条目到 {}", count, dest);
+    Ok(())
+}
+
+fn run(args: &[String]) -> Result<(), CliError> {
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => return Err(CliError::Usage("缺少命令".to_string())),
+    };
+
+    match command {
+        "list" => list(rest),
+        "info" => info(rest),
+        "cat" => cat(rest),
+        "test" => test(rest),
+        "extract" => extract(rest),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        },
+        _ => Err(CliError::Usage(format!("未知命令: {}", command))),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(CliError::Usage(message)) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            ExitCode::from(ffi::ARCHIVE_ERR_INVALID_ARGUMENT.unsigned_abs() as u8)
+        },
+        // 管道被关闭（如 `cat | head`）时不视为错误
+        Err(CliError::Archive(ArchiveError::IoError(err)))
+            if err.kind() == io::ErrorKind::BrokenPipe =>
+        {
+            ExitCode::SUCCESS
+        },
+        Err(CliError::Archive(err)) => {
+            eprintln!("{}", err);
+            ExitCode::from(ffi::error_code(&err).unsigned_abs() as u8)
+        },
+    }
+}
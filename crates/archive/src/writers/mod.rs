@@ -0,0 +1,352 @@
+pub mod tar;
+pub mod zip;
+
+pub use self::tar::*;
+pub use self::zip::*;
+
+use crate::ArchiveError;
+use serde::Serialize;
+use std::{
+    collections::HashSet,
+    ffi::OsString,
+    fs::{self, File, Metadata},
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+/// 确定性模式下统一使用的时间戳（1980-01-01T00:00:00Z，ZIP 可表示的最早时间）
+pub const DETERMINISTIC_MTIME: u64 = 315_532_800;
+
+/// 压缩输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressFormat {
+    Zip,
+    TarZst,
+}
+
+impl CompressFormat {
+    /// 根据输出文件名推断压缩格式
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let name = path.as_ref().file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".zip") {
+            Some(CompressFormat::Zip)
+        } else if name.ends_with(".tar.zst") || name.ends_with(".tzst") {
+            Some(CompressFormat::TarZst)
+        } else {
+            None
+        }
+    }
+}
+
+/// 压缩选项
+#[derive(Debug, Clone, Default)]
+pub struct CompressOptions {
+    /// 压缩级别，`None` 表示使用格式的默认级别
+    pub level: Option<i64>,
+    /// 确定性输出：固定时间戳并清除属主等与环境相关的元数据
+    pub deterministic: bool,
+}
+
+/// 压缩进度
+#[derive(Debug, Clone, Serialize)]
+pub struct CompressProgress {
+    /// 当前处理的条目名
+    pub current: String,
+    /// 已处理的条目数
+    pub processed_entries: usize,
+    /// 条目总数
+    pub total_entries: usize,
+    /// 已写入的原始字节数
+    pub processed_bytes: u64,
+    /// 原始字节总数
+    pub total_bytes: u64,
+}
+
+/// 待写入压缩包的源条目
+#[derive(Debug, Clone)]
+pub struct SourceEntry {
+    /// 源文件路径
+    pub path: PathBuf,
+    /// 压缩包内的名称（使用 `/` 分隔，目录不带结尾 `/`）
+    pub name: String,
+    /// 是否是目录
+    pub dir: bool,
+    /// 文件大小
+    pub size: u64,
+    /// 最后修改时间（秒级 Unix 时间戳）
+    pub mtime: u64,
+}
+
+/// 将一组文件/目录压缩为指定格式
+pub fn compress<P, Q, F>(
+    sources: &[P],
+    output: Q,
+    format: CompressFormat,
+    options: &CompressOptions,
+    progress: F,
+) -> Result<(), ArchiveError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+    F: FnMut(&CompressProgress),
+{
+    match format {
+        CompressFormat::Zip => create_zip(sources, output, options, progress),
+        CompressFormat::TarZst => create_tar_zst(sources, output, options, progress),
+    }
+}
+
+/// 收集源路径下的所有条目，目录会被递归展开
+///
+/// 条目按压缩包内名称排序，保证相同输入得到相同的写入顺序。
+/// 目录中的符号链接会被跳过，以免链接成环时无限递归；直接选中的源路径仍按链接目标处理。
+/// 管道、套接字与设备文件无法作为普通文件读取，同样会被跳过。
+pub fn collect_entries<P: AsRef<Path>>(
+    sources: &[P],
+    options: &CompressOptions,
+) -> Result<Vec<SourceEntry>, ArchiveError> {
+    let mut entries = Vec::new();
+    for source in sources {
+        let path = source.as_ref();
+        let name = entry_name(path.file_name().map(Path::new).unwrap_or(path))?;
+        collect_recursive(path, name, fs::metadata(path)?, options, &mut entries)?;
+    }
+
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut seen = HashSet::new();
+    for entry in &entries {
+        if !seen.insert(entry.name.as_str()) {
+            return Err(ArchiveError::InvalidPath(format!(
+                "duplicate entry: {}",
+                entry.name
+            )));
+        }
+    }
+
+    Ok(entries)
+}
+
+fn collect_recursive(
+    path: &Path,
+    name: String,
+    metadata: Metadata,
+    options: &CompressOptions,
+    entries: &mut Vec<SourceEntry>,
+) -> Result<(), ArchiveError> {
+    // 目录中的符号链接取的是链接本身的元数据，也在这里跳过
+    if !metadata.is_file() && !metadata.is_dir() {
+        log::warn!("跳过符号链接或特殊文件: {}", path.display());
+        return Ok(());
+    }
+    let mtime = if options.deterministic {
+        DETERMINISTIC_MTIME
+    } else {
+        metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(DETERMINISTIC_MTIME)
+    };
+
+    if metadata.is_dir() {
+        for child in fs::read_dir(path)? {
+            let child = child?;
+            let child_path = child.path();
+            let child_metadata = fs::symlink_metadata(&child_path)?;
+            let child_name = format!("{}/{}", name, entry_name(Path::new(&child.file_name()))?);
+            collect_recursive(&child_path, child_name, child_metadata, options, entries)?;
+        }
+        entries.push(SourceEntry {
+            path: path.to_path_buf(),
+            name,
+            dir: true,
+            size: 0,
+            mtime,
+        });
+    } else {
+        entries.push(SourceEntry {
+            path: path.to_path_buf(),
+            name,
+            dir: false,
+            size: metadata.len(),
+            mtime,
+        });
+    }
+
+    Ok(())
+}
+
+/// 先写入输出目录下的临时文件，成功后重命名为输出文件；失败时删除临时文件，不留下不完整的压缩包
+fn write_output<F>(output: &Path, write: F) -> Result<(), ArchiveError>
+where
+    F: FnOnce(File) -> Result<(), ArchiveError>,
+{
+    let name = output
+        .file_name()
+        .ok_or_else(|| ArchiveError::InvalidPath(output.to_string_lossy().to_string()))?;
+    let mut temp_name = OsString::from(".");
+    temp_name.push(name);
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp = output.with_file_name(temp_name);
+
+    let result = File::create(&temp)
+        .map_err(ArchiveError::from)
+        .and_then(write)
+        .and_then(|()| fs::rename(&temp, output).map_err(ArchiveError::from));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+/// 将路径转换为 UTF-8 的条目名
+fn entry_name(path: &Path) -> Result<String, ArchiveError> {
+    let name = path
+        .to_str()
+        .ok_or_else(|| ArchiveError::InvalidPath(path.to_string_lossy().to_string()))?;
+    if name.is_empty() || name == "." || name == ".." {
+        return Err(ArchiveError::InvalidPath(name.to_string()));
+    }
+    Ok(name.replace('\\', "/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractors::{tar::*, zip::*};
+
+    /// 每个测试独立的临时目录
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("writers-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn names(entries: &[SourceEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    /// 含子目录的源目录：src/b.txt、src/a/c.txt
+    fn source(dir: &Path) -> PathBuf {
+        let src = dir.join("src");
+        fs::create_dir_all(src.join("a")).unwrap();
+        fs::write(src.join("b.txt"), "bravo").unwrap();
+        fs::write(src.join("a/c.txt"), "charlie").unwrap();
+        src
+    }
+
+    #[test]
+    fn round_trip() {
+        let dir = temp_dir("round-trip");
+        let src = source(&dir);
+        let zip = dir.join("out.zip");
+        let tzst = dir.join("out.tar.zst");
+        for output in [&zip, &tzst] {
+            let format = CompressFormat::from_path(output).unwrap();
+            let mut last = None;
+            compress(&[&src], output, format, &CompressOptions::default(), |p| {
+                last = Some(p.clone())
+            })
+            .unwrap();
+            let last = last.unwrap();
+            assert_eq!((last.processed_entries, last.total_entries), (4, 4));
+            assert_eq!((last.processed_bytes, last.total_bytes), (12, 12));
+        }
+
+        assert_eq!(read_zip_entry(&zip, "src/a/c.txt").unwrap(), b"charlie");
+        assert_eq!(read_zip_entry(&zip, "src/b.txt").unwrap(), b"bravo");
+        assert_eq!(
+            read_tar_entry(&tzst, TarCompression::Zst, "src/a/c.txt").unwrap(),
+            b"charlie"
+        );
+        let files = |entries: Vec<crate::Extract>| -> Vec<String> {
+            entries
+                .into_iter()
+                .filter(|e| !e.dir)
+                .map(|e| e.name)
+                .collect()
+        };
+        assert_eq!(
+            files(list_zip_entries(&zip).unwrap()),
+            ["src/a/c.txt", "src/b.txt"]
+        );
+        assert_eq!(
+            files(list_tar_zst_entries(&tzst).unwrap()),
+            ["src/a/c.txt", "src/b.txt"]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn duplicate_names_are_rejected() {
+        let dir = temp_dir("duplicate");
+        for sub in ["x", "y"] {
+            fs::create_dir(dir.join(sub)).unwrap();
+            fs::write(dir.join(sub).join("same.txt"), sub).unwrap();
+        }
+        let sources = [dir.join("x/same.txt"), dir.join("y/same.txt")];
+        let output = dir.join("out.zip");
+        let result = compress(
+            &sources,
+            &output,
+            CompressFormat::Zip,
+            &CompressOptions::default(),
+            |_| {},
+        );
+        assert!(matches!(result, Err(ArchiveError::InvalidPath(_))));
+        // 失败时不留下不完整的输出
+        assert!(!output.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn deterministic_output() {
+        let dir = temp_dir("deterministic");
+        let src = source(&dir);
+        let options = CompressOptions { level: None, deterministic: true };
+        let entries = collect_entries(&[&src], &options).unwrap();
+        assert_eq!(
+            names(&entries),
+            ["src", "src/a", "src/a/c.txt", "src/b.txt"]
+        );
+        assert!(entries.iter().all(|e| e.mtime == DETERMINISTIC_MTIME));
+
+        for name in ["out.zip", "out.tar.zst"] {
+            let format = CompressFormat::from_path(name).unwrap();
+            let first = dir.join(format!("1-{}", name));
+            let second = dir.join(format!("2-{}", name));
+            compress(&[&src], &first, format, &options, |_| {}).unwrap();
+            // 修改时间不同也应得到相同的字节
+            File::options()
+                .append(true)
+                .open(src.join("b.txt"))
+                .unwrap()
+                .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(3600))
+                .unwrap();
+            compress(&[&src], &second, format, &options, |_| {}).unwrap();
+            assert_eq!(fs::read(&first).unwrap(), fs::read(&second).unwrap());
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn special_files_are_skipped() {
+        let dir = temp_dir("special");
+        let src = dir.join("src");
+        fs::create_dir(&src).unwrap();
+        fs::write(src.join("a.txt"), "a").unwrap();
+        std::os::unix::fs::symlink(src.join("a.txt"), src.join("link")).unwrap();
+        let _socket = std::os::unix::net::UnixListener::bind(src.join("socket")).unwrap();
+
+        let entries = collect_entries(&[&src], &CompressOptions::default()).unwrap();
+        assert_eq!(names(&entries), ["src", "src/a.txt"]);
+        let socket = collect_entries(&[src.join("socket")], &CompressOptions::default()).unwrap();
+        assert!(socket.is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
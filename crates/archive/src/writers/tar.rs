@@ -0,0 +1,83 @@
+use super::{collect_entries, write_output, CompressOptions, CompressProgress};
+use crate::ArchiveError;
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+};
+
+/// 将一组文件/目录压缩为 TAR.ZST 文件
+pub fn create_tar_zst<P, Q, F>(
+    sources: &[P],
+    output: Q,
+    options: &CompressOptions,
+    mut progress: F,
+) -> Result<(), ArchiveError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+    F: FnMut(&CompressProgress),
+{
+    let entries = collect_entries(sources, options)?;
+    let total_entries = entries.len();
+    let total_bytes = entries.iter().map(|e| e.size).sum();
+
+    write_output(output.as_ref(), |file| {
+        // zstd 的级别为 i32，0 表示使用默认级别
+        let level = options.level.unwrap_or(0).clamp(0, 22) as i32;
+        let encoder = zstd::stream::write::Encoder::new(BufWriter::new(file), level)?;
+        let mut builder = tar::Builder::new(encoder);
+
+        let mut processed_bytes = 0;
+        for (index, entry) in entries.iter().enumerate() {
+            let mut header = tar::Header::new_gnu();
+            header.set_mtime(entry.mtime);
+            if options.deterministic {
+                header.set_uid(0);
+                header.set_gid(0);
+            }
+
+            if entry.dir {
+                header.set_entry_type(tar::EntryType::Directory);
+                header.set_mode(0o755);
+                header.set_size(0);
+                builder.append_data(&mut header, format!("{}/", entry.name), std::io::empty())?;
+            } else {
+                let file = File::open(&entry.path)?;
+                header.set_entry_type(tar::EntryType::Regular);
+                header.set_mode(file_mode(&file, options.deterministic));
+                header.set_size(entry.size);
+                builder.append_data(&mut header, &entry.name, BufReader::new(file))?;
+                processed_bytes += entry.size;
+            }
+
+            progress(&CompressProgress {
+                current: entry.name.clone(),
+                processed_entries: index + 1,
+                total_entries,
+                processed_bytes,
+                total_bytes,
+            });
+        }
+
+        let encoder = builder.into_inner()?;
+        encoder.finish()?.flush()?;
+        Ok(())
+    })
+}
+
+/// 文件权限，确定性模式或非 Unix 平台下使用固定值
+#[cfg(unix)]
+fn file_mode(file: &File, deterministic: bool) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+
+    match file.metadata() {
+        Ok(metadata) if !deterministic => metadata.permissions().mode() & 0o7777,
+        _ => 0o644,
+    }
+}
+
+#[cfg(not(unix))]
+fn file_mode(_file: &File, _deterministic: bool) -> u32 {
+    0o644
+}
@@ -0,0 +1,87 @@
+use super::{collect_entries, write_output, CompressOptions, CompressProgress};
+use crate::ArchiveError;
+use chrono::{Datelike, Timelike};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+    time::{Duration, UNIX_EPOCH},
+};
+use zip::{write::SimpleFileOptions, CompressionMethod, DateTime, ZipWriter};
+
+/// 将一组文件/目录压缩为 ZIP 文件
+///
+/// 条目名统一使用 UTF-8，压缩级别为 0 时仅存储不压缩。
+pub fn create_zip<P, Q, F>(
+    sources: &[P],
+    output: Q,
+    options: &CompressOptions,
+    mut progress: F,
+) -> Result<(), ArchiveError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+    F: FnMut(&CompressProgress),
+{
+    let entries = collect_entries(sources, options)?;
+    let total_entries = entries.len();
+    let total_bytes = entries.iter().map(|e| e.size).sum();
+
+    write_output(output.as_ref(), |file| {
+        let mut writer = ZipWriter::new(BufWriter::new(file));
+
+        let method = match options.level {
+            Some(0) => CompressionMethod::Stored,
+            _ => CompressionMethod::Deflated,
+        };
+        let base = SimpleFileOptions::default().compression_method(method);
+        let base = match options.level {
+            Some(level) if level > 0 => base.compression_level(Some(level)),
+            _ => base,
+        };
+
+        let mut processed_bytes = 0;
+        for (index, entry) in entries.iter().enumerate() {
+            let modified = if options.deterministic {
+                DateTime::default()
+            } else {
+                zip_time(entry.mtime)
+            };
+            let file_options = base.last_modified_time(modified);
+
+            if entry.dir {
+                writer.add_directory(format!("{}/", entry.name), file_options)?;
+            } else {
+                let file_options = file_options.large_file(entry.size >= u32::MAX as u64);
+                writer.start_file(entry.name.as_str(), file_options)?;
+                let mut reader = BufReader::new(File::open(&entry.path)?);
+                processed_bytes += io::copy(&mut reader, &mut writer)?;
+            }
+
+            progress(&CompressProgress {
+                current: entry.name.clone(),
+                processed_entries: index + 1,
+                total_entries,
+                processed_bytes,
+                total_bytes,
+            });
+        }
+
+        writer.finish()?.flush()?;
+        Ok(())
+    })
+}
+
+/// 将 Unix 时间戳转换为 ZIP 使用的本地 DOS 时间
+fn zip_time(mtime: u64) -> DateTime {
+    let local = chrono::DateTime::<chrono::Local>::from(UNIX_EPOCH + Duration::from_secs(mtime));
+    DateTime::from_date_and_time(
+        local.year().clamp(1980, 2107) as u16,
+        local.month() as u8,
+        local.day() as u8,
+        local.hour() as u8,
+        local.minute() as u8,
+        local.second() as u8,
+    )
+    .unwrap_or_default()
+}
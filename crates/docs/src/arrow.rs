@@ -0,0 +1,410 @@
+//! Arrow IPC：文件格式（Feather v2）与流格式，消息通过 arrow-ipc 解码
+//!
+//! 打开时只读取各消息的元数据；读取行时才载入覆盖这些行的记录批次及其之前的字典批次。
+
+use crate::{
+    columnar::{
+        type_name, value, ColumnChunkInfo, ColumnSchema, ColumnarInfo, RowGroupInfo, Value,
+    },
+    error::DocsError,
+    format::DocsFormat,
+    ooxml::MAX_PART_SIZE,
+};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_buffer::Buffer;
+use arrow_ipc::{
+    convert::fb_to_schema,
+    reader::{read_dictionary, read_record_batch},
+    root_as_message, CompressionType, Endianness, MessageHeader,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    sync::Arc,
+};
+
+/// 文件格式的开头与结尾
+pub const ARROW_MAGIC: &[u8; 6] = b"ARROW1";
+/// 流格式中消息的前缀
+const CONTINUATION: u32 = 0xFFFF_FFFF;
+/// 单条消息元数据的最大字节数
+const MAX_METADATA: usize = 64 << 20;
+
+/// 字典批次或记录批次在文件中的位置
+#[derive(Debug, Clone, Copy)]
+struct Message {
+    kind: MessageHeader,
+    /// 元数据的位置与长度，消息体紧随其后
+    offset: u64,
+    metadata_len: usize,
+    body_len: u64,
+}
+
+/// 记录批次
+#[derive(Debug, Clone, Copy)]
+struct Batch {
+    /// 在 `messages` 中的下标
+    message: usize,
+    first_row: u64,
+    row_count: u64,
+}
+
+/// 已读取结构的 Arrow IPC 文件
+pub(crate) struct ArrowFile {
+    file: File,
+    info: ColumnarInfo,
+    schema: SchemaRef,
+    /// 字典批次与记录批次，按生效顺序排列
+    messages: Vec<Message>,
+    batches: Vec<Batch>,
+    /// 字典 ID -> 字典值
+    dictionaries: HashMap<i64, ArrayRef>,
+    /// 已应用到 `dictionaries` 的消息数
+    applied: usize,
+}
+
+impl ArrowFile {
+    pub fn open(path: &str) -> Result<Self, DocsError> {
+        let mut file = File::open(path)?;
+        let size = file.metadata()?.len();
+        let head = read_at(&mut file, 0, size.min(8) as usize)?;
+        let file_format = head.starts_with(ARROW_MAGIC);
+
+        // 文件格式由消息流与文件尾组成，只扫描文件尾之前的消息
+        let mut end = size;
+        if file_format && size >= 18 {
+            let tail = read_at(&mut file, size - 10, 10)?;
+            let footer_len = i32::from_le_bytes(tail[..4].try_into().unwrap());
+            if &tail[4..] == ARROW_MAGIC {
+                if let Some(len) = u64::try_from(footer_len).ok().filter(|&l| l <= size - 18) {
+                    end = size - 10 - len;
+                }
+            }
+        }
+
+        let mut schema: Option<SchemaRef> = None;
+        let mut messages = Vec::new();
+        let mut batches = Vec::new();
+        let mut row_groups = Vec::new();
+        let mut first_row = 0u64;
+        let mut pos = if file_format { 8 } else { 0 };
+        while let Some((metadata, offset)) = read_metadata(&mut file, end, pos)? {
+            let message = root_as_message(&metadata)
+                .map_err(|e| DocsError::corrupt(format!("Arrow 消息无效: {}", e)))?;
+            let body_len = u64::try_from(message.bodyLength())
+                .ok()
+                .filter(|&len| offset + metadata.len() as u64 + len <= end)
+                .ok_or_else(|| DocsError::corrupt("Arrow 消息体长度无效"))?;
+            match message.header_type() {
+                MessageHeader::Schema if schema.is_none() => {
+                    let header = message
+                        .header_as_schema()
+                        .filter(|s| s.fields().is_some())
+                        .ok_or_else(|| DocsError::corrupt("Arrow 模式无效"))?;
+                    if header.endianness() == Endianness::Big {
+                        return Err(DocsError::Unsupported(
+                            "大端字节序的 Arrow 文件".to_string(),
+                        ));
+                    }
+                    schema = Some(Arc::new(fb_to_schema(header)));
+                },
+                MessageHeader::DictionaryBatch => messages.push(Message {
+                    kind: MessageHeader::DictionaryBatch,
+                    offset,
+                    metadata_len: metadata.len(),
+                    body_len,
+                }),
+                MessageHeader::RecordBatch => {
+                    let fields = schema
+                        .as_ref()
+                        .ok_or_else(|| DocsError::corrupt("Arrow 记录批次出现在模式之前"))?
+                        .fields();
+                    let header = message
+                        .header_as_record_batch()
+                        .ok_or_else(|| DocsError::corrupt("Arrow 记录批次缺失"))?;
+                    let row_count = u64::try_from(header.length())
+                        .map_err(|_| DocsError::corrupt("Arrow 记录批次的行数无效"))?;
+                    let codec = match header.compression().map(|c| c.codec()) {
+                        None => "uncompressed",
+                        Some(CompressionType::LZ4_FRAME) => "lz4_frame",
+                        Some(CompressionType::ZSTD) => "zstd",
+                        Some(_) => "unknown",
+                    };
+                    let nodes = header.nodes();
+                    let mut node = 0;
+                    let columns = fields
+                        .iter()
+                        .map(|field| {
+                            let null_count = nodes
+                                .filter(|nodes| node < nodes.len())
+                                .map(|nodes| nodes.get(node).null_count().max(0) as u64);
+                            node += node_count(field.data_type());
+                            ColumnChunkInfo {
+                                column: field.name().clone(),
+                                codec: Some(codec.to_string()),
+                                null_count,
+                                ..Default::default()
+                            }
+                        })
+                        .collect();
+                    row_groups.push(RowGroupInfo {
+                        row_count,
+                        compressed_size: Some(body_len),
+                        uncompressed_size: None,
+                        columns,
+                    });
+                    batches.push(Batch {
+                        message: messages.len(),
+                        first_row,
+                        row_count,
+                    });
+                    messages.push(Message {
+                        kind: MessageHeader::RecordBatch,
+                        offset,
+                        metadata_len: metadata.len(),
+                        body_len,
+                    });
+                    first_row = first_row.saturating_add(row_count);
+                },
+                _ => {},
+            }
+            pos = offset + metadata.len() as u64 + body_len;
+        }
+        let schema = schema.ok_or_else(|| DocsError::corrupt("缺少 Arrow 模式"))?;
+
+        let info = ColumnarInfo {
+            format: DocsFormat::Arrow,
+            row_count: first_row,
+            columns: schema
+                .fields()
+                .iter()
+                .map(|field| ColumnSchema {
+                    name: field.name().clone(),
+                    data_type: type_name(field.data_type()),
+                    physical_type: None,
+                    nullable: field.is_nullable(),
+                })
+                .collect(),
+            row_groups,
+            metadata: schema
+                .metadata()
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect::<BTreeMap<_, _>>(),
+            created_by: None,
+        };
+        Ok(ArrowFile {
+            file,
+            info,
+            schema,
+            messages,
+            batches,
+            dictionaries: HashMap::new(),
+            applied: 0,
+        })
+    }
+
+    pub fn info(&self) -> &ColumnarInfo {
+        &self.info
+    }
+
+    /// 按列读取从 `offset` 开始的 `count` 行
+    pub fn read(&mut self, offset: u64, count: usize) -> Result<Vec<Vec<Value>>, DocsError> {
+        let end = offset + count as u64;
+        let mut columns: Vec<Vec<Value>> =
+            vec![Vec::with_capacity(count); self.schema.fields().len()];
+        for batch in self.batches.clone() {
+            let batch_end = batch.first_row + batch.row_count;
+            if batch_end <= offset || batch.first_row >= end {
+                continue;
+            }
+            let start = offset.saturating_sub(batch.first_row) as usize;
+            let stop = (end.min(batch_end) - batch.first_row) as usize;
+            self.apply_dictionaries(batch.message)?;
+            let record_batch = self.record_batch(batch.message)?;
+            for (column, array) in columns.iter_mut().zip(record_batch.columns()) {
+                let mut values: Vec<Value> = (start..stop.min(array.len()))
+                    .map(|i| value(array.as_ref(), i))
+                    .collect();
+                values.resize(stop - start, Value::Null);
+                column.extend(values);
+            }
+        }
+        Ok(columns)
+    }
+
+    /// 应用第 `until` 条消息之前的全部字典批次
+    fn apply_dictionaries(&mut self, until: usize) -> Result<(), DocsError> {
+        if self.applied > until {
+            self.dictionaries.clear();
+            self.applied = 0;
+        }
+        for index in self.applied..until {
+            let message = self.messages[index];
+            if message.kind != MessageHeader::DictionaryBatch {
+                continue;
+            }
+            let (metadata, body) = self.load(message)?;
+            let message = root_as_message(&metadata)
+                .map_err(|e| DocsError::corrupt(format!("Arrow 消息无效: {}", e)))?;
+            let batch = message
+                .header_as_dictionary_batch()
+                .ok_or_else(|| DocsError::corrupt("Arrow 字典批次缺失"))?;
+            let data = batch
+                .data()
+                .ok_or_else(|| DocsError::corrupt("Arrow 字典批次缺失"))?;
+            check_buffers(data, &body)?;
+            if !batch.isDelta() {
+                read_dictionary(
+                    &body,
+                    batch,
+                    &self.schema,
+                    &mut self.dictionaries,
+                    &message.version(),
+                )?;
+                continue;
+            }
+            // arrow-ipc 不支持增量字典，按值类型解码后追加到已有字典
+            let existing = self
+                .dictionaries
+                .get(&batch.id())
+                .ok_or_else(|| DocsError::corrupt("Arrow 增量字典缺少初始字典"))?;
+            let schema = Schema::new(vec![Field::new("", existing.data_type().clone(), true)]);
+            let delta = read_record_batch(
+                &body,
+                data,
+                Arc::new(schema),
+                &self.dictionaries,
+                None,
+                &message.version(),
+            )?;
+            let merged = arrow_select::concat::concat(&[existing.as_ref(), delta.column(0)])?;
+            self.dictionaries.insert(batch.id(), merged);
+        }
+        self.applied = until;
+        Ok(())
+    }
+
+    fn record_batch(&mut self, index: usize) -> Result<RecordBatch, DocsError> {
+        let (metadata, body) = self.load(self.messages[index])?;
+        let message = root_as_message(&metadata)
+            .map_err(|e| DocsError::corrupt(format!("Arrow 消息无效: {}", e)))?;
+        let batch = message
+            .header_as_record_batch()
+            .ok_or_else(|| DocsError::corrupt("Arrow 记录批次缺失"))?;
+        check_buffers(batch, &body)?;
+        Ok(read_record_batch(
+            &body,
+            batch,
+            self.schema.clone(),
+            &self.dictionaries,
+            None,
+            &message.version(),
+        )?)
+    }
+
+    /// 读取消息的元数据与消息体
+    fn load(&mut self, message: Message) -> Result<(Vec<u8>, Buffer), DocsError> {
+        if message.body_len > MAX_PART_SIZE {
+            return Err(DocsError::LimitExceeded {
+                limit: format!("记录批次 {} 字节", message.body_len),
+                part: None,
+            });
+        }
+        let metadata = read_at(&mut self.file, message.offset, message.metadata_len)?;
+        let body = read_at(
+            &mut self.file,
+            message.offset + message.metadata_len as u64,
+            message.body_len as usize,
+        )?;
+        Ok((metadata, Buffer::from_vec(body)))
+    }
+}
+
+/// 压缩缓冲区以解压后的长度开头，arrow-ipc 按该长度分配内存，先检查以免过大
+fn check_buffers(batch: arrow_ipc::RecordBatch, body: &[u8]) -> Result<(), DocsError> {
+    if batch.compression().is_none() {
+        return Ok(());
+    }
+    for buffer in batch.buffers().into_iter().flatten() {
+        let Some(prefix) = usize::try_from(buffer.offset())
+            .ok()
+            .filter(|_| buffer.length() >= 8)
+            .and_then(|start| body.get(start..start.checked_add(8)?))
+        else {
+            continue;
+        };
+        let len = i64::from_le_bytes(prefix.try_into().unwrap());
+        if len > MAX_PART_SIZE as i64 {
+            return Err(DocsError::LimitExceeded {
+                limit: format!("记录批次 {} 字节", len),
+                part: None,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// 字段（含子字段）在记录批次中占用的节点数，字典编码的字段只有索引一个节点
+fn node_count(data_type: &DataType) -> usize {
+    match data_type {
+        DataType::List(child)
+        | DataType::LargeList(child)
+        | DataType::ListView(child)
+        | DataType::LargeListView(child)
+        | DataType::FixedSizeList(child, _)
+        | DataType::Map(child, _) => 1 + node_count(child.data_type()),
+        DataType::Struct(fields) => {
+            1 + fields
+                .iter()
+                .map(|f| node_count(f.data_type()))
+                .sum::<usize>()
+        },
+        DataType::Union(fields, _) => {
+            1 + fields
+                .iter()
+                .map(|(_, f)| node_count(f.data_type()))
+                .sum::<usize>()
+        },
+        DataType::RunEndEncoded(run_ends, values) => {
+            1 + node_count(run_ends.data_type()) + node_count(values.data_type())
+        },
+        _ => 1,
+    }
+}
+
+/// 读取 `pos` 处消息的元数据，返回元数据与其起始位置；遇到结束标记或到达 `end` 时返回 `None`
+fn read_metadata(file: &mut File, end: u64, pos: u64) -> Result<Option<(Vec<u8>, u64)>, DocsError> {
+    if pos + 4 > end {
+        return Ok(None);
+    }
+    let prefix = read_at(file, pos, 4)?;
+    let mut len = u32::from_le_bytes(prefix[..].try_into().unwrap());
+    let mut start = pos + 4;
+    // 0.15 之前的格式没有前缀，直接是长度
+    if len == CONTINUATION {
+        if pos + 8 > end {
+            return Ok(None);
+        }
+        let prefix = read_at(file, pos + 4, 4)?;
+        len = u32::from_le_bytes(prefix[..].try_into().unwrap());
+        start = pos + 8;
+    }
+    if len == 0 {
+        return Ok(None);
+    }
+    let len = len as usize;
+    if len > MAX_METADATA || start + len as u64 > end {
+        return Err(DocsError::corrupt("Arrow 消息元数据长度无效"));
+    }
+    Ok(Some((read_at(file, start, len)?, start)))
+}
+
+fn read_at(file: &mut File, pos: u64, len: usize) -> Result<Vec<u8>, DocsError> {
+    let mut buf = vec![0; len];
+    file.seek(SeekFrom::Start(pos))?;
+    file.read_exact(&mut buf)?;
+    Ok(buf)
+}
@@ -0,0 +1,603 @@
+//! 列式数据文件（Parquet、Arrow IPC / Feather v2）：结构、行组统计、键值元数据与按需读取的行
+//!
+//! 行中的值按列的逻辑类型转换为与 Excel、CSV 相同的单元格；列表、结构体与映射显示为 JSON 风格的文本。
+
+use crate::{
+    arrow::ArrowFile,
+    error::DocsError,
+    format::DocsFormat,
+    metadata::DocumentMetadata,
+    numfmt,
+    parquet::ParquetFile,
+    sheet::{Cell, CellValue},
+};
+use arrow_array::{cast::AsArray, types::*, Array};
+use arrow_cast::display::{ArrayFormatter, FormatOptions};
+use arrow_schema::DataType;
+use chrono::{DateTime, NaiveDate, NaiveTime};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    panic::{self, AssertUnwindSafe},
+};
+
+/// 二进制值只显示开头的字节
+const BINARY_PREVIEW: usize = 32;
+
+/// 列式文件概要
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnarInfo {
+    /// `parquet` 或 `arrow`
+    pub format: DocsFormat,
+    pub row_count: u64,
+    /// 与表格中的列一一对应，即顶层字段；嵌套结构显示为一列
+    pub columns: Vec<ColumnSchema>,
+    /// Parquet 的行组，或 Arrow 的记录批次
+    pub row_groups: Vec<RowGroupInfo>,
+    /// 文件级键值元数据
+    pub metadata: BTreeMap<String, String>,
+    /// 生成文件的程序，如 `parquet-cpp-arrow version 15.0.0`
+    pub created_by: Option<String>,
+}
+
+/// 列结构
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnSchema {
+    pub name: String,
+    /// 逻辑类型，如 `int64`、`timestamp[ms, UTC]`、`decimal(10, 2)`、`list<string>`
+    pub data_type: String,
+    /// Parquet 顶层基本类型列的物理类型，如 `INT64`、`BYTE_ARRAY`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub physical_type: Option<String>,
+    pub nullable: bool,
+}
+
+/// 行组（记录批次）概要
+#[derive(Debug, Clone, Serialize)]
+pub struct RowGroupInfo {
+    pub row_count: u64,
+    /// 压缩后的字节数
+    pub compressed_size: Option<u64>,
+    pub uncompressed_size: Option<u64>,
+    pub columns: Vec<ColumnChunkInfo>,
+}
+
+/// 行组中一列的存储信息与统计
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ColumnChunkInfo {
+    pub column: String,
+    /// 压缩算法，如 `snappy`、`zstd`
+    pub codec: Option<String>,
+    /// Parquet 的编码，如 `PLAIN`、`RLE_DICTIONARY`
+    pub encodings: Vec<String>,
+    pub compressed_size: Option<u64>,
+    pub uncompressed_size: Option<u64>,
+    pub null_count: Option<u64>,
+    pub distinct_count: Option<u64>,
+    /// 最小值的显示文本
+    pub min: Option<String>,
+    pub max: Option<String>,
+}
+
+/// 按需读取行的列式文件
+pub struct Columnar {
+    reader: Reader,
+}
+
+enum Reader {
+    Parquet(Box<ParquetFile>),
+    Arrow(Box<ArrowFile>),
+}
+
+impl Columnar {
+    /// 按内容识别格式并读取文件结构，不读取数据
+    pub fn open(path: &str) -> Result<Self, DocsError> {
+        let reader = match DocsFormat::detect(path)? {
+            DocsFormat::Parquet => Reader::Parquet(Box::new(guard(|| ParquetFile::open(path))?)),
+            DocsFormat::Arrow => Reader::Arrow(Box::new(guard(|| ArrowFile::open(path))?)),
+            other => return Err(DocsError::Unsupported(other.name().to_string())),
+        };
+        Ok(Columnar { reader })
+    }
+
+    pub fn info(&self) -> &ColumnarInfo {
+        match &self.reader {
+            Reader::Parquet(file) => file.info(),
+            Reader::Arrow(file) => file.info(),
+        }
+    }
+
+    /// 读取从 `offset` 开始的至多 `count` 行，只读取覆盖这些行的行组
+    pub fn rows(&mut self, offset: u64, count: usize) -> Result<Vec<Vec<Cell>>, DocsError> {
+        let count = (count as u64).min(self.info().row_count.saturating_sub(offset)) as usize;
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        let columns = match &mut self.reader {
+            Reader::Parquet(file) => guard(|| file.read(offset, count))?,
+            Reader::Arrow(file) => guard(|| file.read(offset, count))?,
+        };
+        // 按列读取，转置为行
+        let mut rows: Vec<Vec<Cell>> = (0..count)
+            .map(|_| Vec::with_capacity(columns.len()))
+            .collect();
+        for column in columns {
+            let mut values = column.into_iter();
+            for row in &mut rows {
+                row.push(values.next().map_or_else(Cell::empty, Value::into_cell));
+            }
+        }
+        Ok(rows)
+    }
+}
+
+/// parquet 与 arrow 库遇到部分损坏的数据时会 panic，转换为损坏错误
+fn guard<T>(read: impl FnOnce() -> Result<T, DocsError>) -> Result<T, DocsError> {
+    panic::catch_unwind(AssertUnwindSafe(read))
+        .unwrap_or_else(|_| Err(DocsError::corrupt("列式文件的数据无效")))
+}
+
+/// 读取列式文件的概要
+pub fn parse(path: &str) -> Result<ColumnarInfo, DocsError> {
+    let columnar = Columnar::open(path)?;
+    log::info!(
+        "读取列式文件: {}, {} 列, {} 行",
+        path,
+        columnar.info().columns.len(),
+        columnar.info().row_count
+    );
+    Ok(columnar.info().clone())
+}
+
+impl From<&ColumnarInfo> for DocumentMetadata {
+    fn from(info: &ColumnarInfo) -> Self {
+        DocumentMetadata {
+            application: info.created_by.clone(),
+            custom: info.metadata.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// 时间单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TimeUnit {
+    Second,
+    Milli,
+    Micro,
+    Nano,
+}
+
+impl TimeUnit {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimeUnit::Second => "s",
+            TimeUnit::Milli => "ms",
+            TimeUnit::Micro => "us",
+            TimeUnit::Nano => "ns",
+        }
+    }
+
+    fn per_second(&self) -> i64 {
+        match self {
+            TimeUnit::Second => 1,
+            TimeUnit::Milli => 1_000,
+            TimeUnit::Micro => 1_000_000,
+            TimeUnit::Nano => 1_000_000_000,
+        }
+    }
+
+    /// 拆分为秒与纳秒
+    fn split(&self, value: i64) -> (i64, u32) {
+        let per_second = self.per_second();
+        let nanos = value.rem_euclid(per_second) * (1_000_000_000 / per_second);
+        (value.div_euclid(per_second), nanos as u32)
+    }
+}
+
+impl From<arrow_schema::TimeUnit> for TimeUnit {
+    fn from(unit: arrow_schema::TimeUnit) -> Self {
+        match unit {
+            arrow_schema::TimeUnit::Second => TimeUnit::Second,
+            arrow_schema::TimeUnit::Millisecond => TimeUnit::Milli,
+            arrow_schema::TimeUnit::Microsecond => TimeUnit::Micro,
+            arrow_schema::TimeUnit::Nanosecond => TimeUnit::Nano,
+        }
+    }
+}
+
+/// 解码出的值，与文件格式无关
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    /// 单精度（含半精度），按单精度显示以免出现多余的位数
+    Float32(f32),
+    Float(f64),
+    /// 十进制整数文本与小数位数
+    Decimal {
+        digits: String,
+        scale: i32,
+    },
+    Text(String),
+    Binary(Vec<u8>),
+    /// 自 1970-01-01 起的天数
+    Date(i64),
+    Timestamp {
+        value: i64,
+        unit: TimeUnit,
+    },
+    /// 自午夜起的时间
+    Time {
+        value: i64,
+        unit: TimeUnit,
+    },
+    Duration {
+        value: i64,
+        unit: TimeUnit,
+    },
+    List(Vec<Value>),
+    Struct(Vec<(String, Value)>),
+    Map(Vec<(Value, Value)>),
+}
+
+impl Value {
+    /// 转换为单元格
+    pub fn into_cell(self) -> Cell {
+        let display = self.to_string();
+        let value = match self {
+            Value::Null => return Cell::empty(),
+            Value::Bool(b) => CellValue::Bool(b),
+            Value::Int(i) => CellValue::Int(i),
+            Value::UInt(u) => match i64::try_from(u) {
+                Ok(i) => CellValue::Int(i),
+                Err(_) => CellValue::Float(u as f64),
+            },
+            Value::Float32(f) => CellValue::Float(display.parse().unwrap_or(f as f64)),
+            Value::Float(f) => CellValue::Float(f),
+            Value::Decimal { .. } => match display.parse() {
+                Ok(f) => CellValue::Float(f),
+                Err(_) => CellValue::String(display.clone()),
+            },
+            Value::Date(_) | Value::Timestamp { .. } => match self.iso() {
+                Some(iso) => CellValue::DateTime(iso),
+                None => CellValue::String(display.clone()),
+            },
+            Value::Time { value, unit } | Value::Duration { value, unit } => {
+                CellValue::Duration(value as f64 / unit.per_second() as f64)
+            },
+            Value::Text(_)
+            | Value::Binary(_)
+            | Value::List(_)
+            | Value::Struct(_)
+            | Value::Map(_) => CellValue::String(display.clone()),
+        };
+        Cell { value, display }
+    }
+
+    /// 日期与时间戳的 ISO 8601 文本
+    fn iso(&self) -> Option<String> {
+        match self {
+            Value::Date(days) => Some(date(*days)?.format("%Y-%m-%d").to_string()),
+            Value::Timestamp { value, unit } => {
+                let (seconds, nanos) = unit.split(*value);
+                let datetime = DateTime::from_timestamp(seconds, nanos)?.naive_utc();
+                Some(datetime.format("%Y-%m-%dT%H:%M:%S%.f").to_string())
+            },
+            _ => None,
+        }
+    }
+
+    /// 显示文本，嵌套值中的字符串加引号
+    fn render(&self, out: &mut String, nested: bool) {
+        match self {
+            Value::Null if nested => out.push_str("null"),
+            Value::Null => {},
+            Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+            Value::Int(i) => out.push_str(&i.to_string()),
+            Value::UInt(u) => out.push_str(&u.to_string()),
+            Value::Float32(f) => out.push_str(&f.to_string()),
+            Value::Float(f) => out.push_str(&f.to_string()),
+            Value::Decimal { digits, scale } => out.push_str(&decimal(digits, *scale)),
+            Value::Text(text) if nested => {
+                out.push_str(&serde_json::to_string(text).unwrap_or_default())
+            },
+            Value::Text(text) => out.push_str(text),
+            Value::Binary(bytes) => {
+                out.push_str("0x");
+                for b in bytes.iter().take(BINARY_PREVIEW) {
+                    out.push_str(&format!("{:02x}", b));
+                }
+                if bytes.len() > BINARY_PREVIEW {
+                    out.push('…');
+                }
+            },
+            Value::Date(_) | Value::Timestamp { .. } => {
+                let text = match self.iso() {
+                    Some(iso) => iso.replacen('T', " ", 1),
+                    None => format!("{:?}", self),
+                };
+                if nested {
+                    out.push_str(&serde_json::to_string(&text).unwrap_or_default());
+                } else {
+                    out.push_str(&text);
+                }
+            },
+            Value::Time { value, unit } => {
+                let (seconds, nanos) = unit.split(*value);
+                let text = u32::try_from(seconds)
+                    .ok()
+                    .and_then(|s| NaiveTime::from_num_seconds_from_midnight_opt(s, nanos))
+                    .map_or_else(
+                        || numfmt::format_duration(seconds as f64),
+                        |time| time.format("%H:%M:%S%.f").to_string(),
+                    );
+                if nested {
+                    out.push_str(&serde_json::to_string(&text).unwrap_or_default());
+                } else {
+                    out.push_str(&text);
+                }
+            },
+            Value::Duration { value, unit } => {
+                out.push_str(&value.to_string());
+                out.push_str(unit.name());
+            },
+            Value::List(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    item.render(out, true);
+                }
+                out.push(']');
+            },
+            Value::Struct(fields) => {
+                out.push('{');
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    out.push_str(&serde_json::to_string(name).unwrap_or_default());
+                    out.push_str(": ");
+                    value.render(out, true);
+                }
+                out.push('}');
+            },
+            Value::Map(entries) => {
+                out.push('{');
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    key.render(out, true);
+                    out.push_str(": ");
+                    value.render(out, true);
+                }
+                out.push('}');
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut out = String::new();
+        self.render(&mut out, false);
+        f.write_str(&out)
+    }
+}
+
+/// 显示用的 Arrow 类型名
+pub(crate) fn type_name(data_type: &DataType) -> String {
+    let child = |field: &arrow_schema::Field| type_name(field.data_type());
+    match data_type {
+        DataType::Null => "null".to_string(),
+        DataType::Boolean => "bool".to_string(),
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64 => data_type.to_string().to_lowercase(),
+        DataType::Float16 => "float16".to_string(),
+        DataType::Float32 => "float".to_string(),
+        DataType::Float64 => "double".to_string(),
+        DataType::Binary => "binary".to_string(),
+        DataType::LargeBinary => "large_binary".to_string(),
+        DataType::BinaryView => "binary_view".to_string(),
+        DataType::Utf8 => "string".to_string(),
+        DataType::LargeUtf8 => "large_string".to_string(),
+        DataType::Utf8View => "string_view".to_string(),
+        DataType::FixedSizeBinary(width) => format!("fixed_size_binary[{}]", width),
+        DataType::Decimal128(precision, scale) | DataType::Decimal256(precision, scale) => {
+            format!("decimal({}, {})", precision, scale)
+        },
+        DataType::Date32 => "date32".to_string(),
+        DataType::Date64 => "date64".to_string(),
+        DataType::Time32(unit) | DataType::Time64(unit) => {
+            format!("time[{}]", TimeUnit::from(*unit).name())
+        },
+        DataType::Timestamp(unit, Some(tz)) => {
+            format!("timestamp[{}, {}]", TimeUnit::from(*unit).name(), tz)
+        },
+        DataType::Timestamp(unit, None) => format!("timestamp[{}]", TimeUnit::from(*unit).name()),
+        DataType::Duration(unit) => format!("duration[{}]", TimeUnit::from(*unit).name()),
+        DataType::Interval(_) => "interval".to_string(),
+        DataType::List(field) => format!("list<{}>", child(field)),
+        DataType::LargeList(field) => format!("large_list<{}>", child(field)),
+        DataType::ListView(field) => format!("list_view<{}>", child(field)),
+        DataType::LargeListView(field) => format!("large_list_view<{}>", child(field)),
+        DataType::FixedSizeList(field, size) => {
+            format!("fixed_size_list<{}>[{}]", child(field), size)
+        },
+        DataType::Map(entries, _) => match entries.data_type() {
+            DataType::Struct(fields) if fields.len() == 2 => {
+                format!("map<{}, {}>", child(&fields[0]), child(&fields[1]))
+            },
+            _ => "map<?, ?>".to_string(),
+        },
+        DataType::Struct(fields) => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|f| format!("{}: {}", f.name(), child(f)))
+                .collect();
+            format!("struct<{}>", fields.join(", "))
+        },
+        DataType::Union(fields, _) => {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|(_, f)| format!("{}: {}", f.name(), child(f)))
+                .collect();
+            format!("union<{}>", fields.join(", "))
+        },
+        DataType::Dictionary(_, values) => format!("dictionary<{}>", type_name(values)),
+        DataType::RunEndEncoded(_, values) => format!("run_end_encoded<{}>", child(values)),
+    }
+}
+
+/// 读取数组中第 `i` 个值，不常见的类型使用 Arrow 的显示文本
+pub(crate) fn value(array: &dyn Array, i: usize) -> Value {
+    if array.is_null(i) {
+        return Value::Null;
+    }
+    match array.data_type() {
+        DataType::Null => Value::Null,
+        DataType::Boolean => Value::Bool(array.as_boolean().value(i)),
+        DataType::Int8 => Value::Int(array.as_primitive::<Int8Type>().value(i) as i64),
+        DataType::Int16 => Value::Int(array.as_primitive::<Int16Type>().value(i) as i64),
+        DataType::Int32 => Value::Int(array.as_primitive::<Int32Type>().value(i) as i64),
+        DataType::Int64 => Value::Int(array.as_primitive::<Int64Type>().value(i)),
+        DataType::UInt8 => Value::UInt(array.as_primitive::<UInt8Type>().value(i) as u64),
+        DataType::UInt16 => Value::UInt(array.as_primitive::<UInt16Type>().value(i) as u64),
+        DataType::UInt32 => Value::UInt(array.as_primitive::<UInt32Type>().value(i) as u64),
+        DataType::UInt64 => Value::UInt(array.as_primitive::<UInt64Type>().value(i)),
+        DataType::Float16 => Value::Float32(array.as_primitive::<Float16Type>().value(i).to_f32()),
+        DataType::Float32 => Value::Float32(array.as_primitive::<Float32Type>().value(i)),
+        DataType::Float64 => Value::Float(array.as_primitive::<Float64Type>().value(i)),
+        DataType::Decimal128(_, scale) => Value::Decimal {
+            digits: array.as_primitive::<Decimal128Type>().value(i).to_string(),
+            scale: *scale as i32,
+        },
+        DataType::Decimal256(_, scale) => Value::Decimal {
+            digits: array.as_primitive::<Decimal256Type>().value(i).to_string(),
+            scale: *scale as i32,
+        },
+        DataType::Utf8 => Value::Text(array.as_string::<i32>().value(i).to_string()),
+        DataType::LargeUtf8 => Value::Text(array.as_string::<i64>().value(i).to_string()),
+        DataType::Utf8View => Value::Text(array.as_string_view().value(i).to_string()),
+        DataType::Binary => Value::Binary(array.as_binary::<i32>().value(i).to_vec()),
+        DataType::LargeBinary => Value::Binary(array.as_binary::<i64>().value(i).to_vec()),
+        DataType::BinaryView => Value::Binary(array.as_binary_view().value(i).to_vec()),
+        DataType::FixedSizeBinary(_) => {
+            Value::Binary(array.as_fixed_size_binary().value(i).to_vec())
+        },
+        DataType::Date32 => Value::Date(array.as_primitive::<Date32Type>().value(i) as i64),
+        DataType::Date64 => Value::Date(
+            array
+                .as_primitive::<Date64Type>()
+                .value(i)
+                .div_euclid(86_400_000),
+        ),
+        DataType::Timestamp(unit, _) => Value::Timestamp {
+            value: ticks(array, i),
+            unit: (*unit).into(),
+        },
+        DataType::Time32(unit) | DataType::Time64(unit) => Value::Time {
+            value: ticks(array, i),
+            unit: (*unit).into(),
+        },
+        DataType::Duration(unit) => Value::Duration {
+            value: ticks(array, i),
+            unit: (*unit).into(),
+        },
+        DataType::List(_) => list(array.as_list::<i32>().value(i).as_ref()),
+        DataType::LargeList(_) => list(array.as_list::<i64>().value(i).as_ref()),
+        DataType::FixedSizeList(..) => list(array.as_fixed_size_list().value(i).as_ref()),
+        DataType::Struct(fields) => Value::Struct(
+            fields
+                .iter()
+                .zip(array.as_struct().columns())
+                .map(|(field, column)| (field.name().clone(), value(column.as_ref(), i)))
+                .collect(),
+        ),
+        DataType::Map(..) => {
+            let entries = array.as_map().value(i);
+            let (keys, values) = (entries.column(0), entries.column(1));
+            Value::Map(
+                (0..entries.len())
+                    .map(|j| (value(keys.as_ref(), j), value(values.as_ref(), j)))
+                    .collect(),
+            )
+        },
+        DataType::Dictionary(..) => {
+            let dictionary = array.as_any_dictionary();
+            let key = match value(dictionary.keys(), i) {
+                Value::Int(key) => usize::try_from(key).ok(),
+                Value::UInt(key) => usize::try_from(key).ok(),
+                _ => None,
+            };
+            key.filter(|&key| key < dictionary.values().len())
+                .map_or(Value::Null, |key| value(dictionary.values().as_ref(), key))
+        },
+        _ => ArrayFormatter::try_new(array, &FormatOptions::default())
+            .map_or(Value::Null, |formatter| {
+                Value::Text(formatter.value(i).to_string())
+            }),
+    }
+}
+
+fn list(items: &dyn Array) -> Value {
+    Value::List((0..items.len()).map(|i| value(items, i)).collect())
+}
+
+/// 时间戳、时间与时长的整数值
+fn ticks(array: &dyn Array, i: usize) -> i64 {
+    use arrow_schema::TimeUnit::*;
+
+    match array.data_type() {
+        DataType::Timestamp(Second, _) => array.as_primitive::<TimestampSecondType>().value(i),
+        DataType::Timestamp(Millisecond, _) => {
+            array.as_primitive::<TimestampMillisecondType>().value(i)
+        },
+        DataType::Timestamp(Microsecond, _) => {
+            array.as_primitive::<TimestampMicrosecondType>().value(i)
+        },
+        DataType::Timestamp(Nanosecond, _) => {
+            array.as_primitive::<TimestampNanosecondType>().value(i)
+        },
+        DataType::Time32(Second) => array.as_primitive::<Time32SecondType>().value(i) as i64,
+        DataType::Time32(_) => array.as_primitive::<Time32MillisecondType>().value(i) as i64,
+        DataType::Time64(Microsecond) => array.as_primitive::<Time64MicrosecondType>().value(i),
+        DataType::Time64(_) => array.as_primitive::<Time64NanosecondType>().value(i),
+        DataType::Duration(Second) => array.as_primitive::<DurationSecondType>().value(i),
+        DataType::Duration(Millisecond) => array.as_primitive::<DurationMillisecondType>().value(i),
+        DataType::Duration(Microsecond) => array.as_primitive::<DurationMicrosecondType>().value(i),
+        DataType::Duration(Nanosecond) => array.as_primitive::<DurationNanosecondType>().value(i),
+        _ => 0,
+    }
+}
+
+fn date(days: i64) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(1970, 1, 1)?.checked_add_signed(chrono::Duration::try_days(days)?)
+}
+
+/// 在整数文本中插入小数点
+fn decimal(digits: &str, scale: i32) -> String {
+    let (sign, digits) = match digits.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", digits),
+    };
+    if scale <= 0 {
+        let zeros = if digits == "0" { 0 } else { (-scale) as usize };
+        return format!("{}{}{}", sign, digits, "0".repeat(zeros));
+    }
+    let scale = scale as usize;
+    let padded = format!("{:0>width$}", digits, width = scale + 1);
+    let (int, frac) = padded.split_at(padded.len() - scale);
+    format!("{}{}.{}", sign, int, frac)
+}
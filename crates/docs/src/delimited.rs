@@ -0,0 +1,400 @@
+//! 分隔符文本表格（csv/tsv/psv/tab）：分隔符与引号探测、编码识别、表头判断
+
+use crate::error::DocsError;
+use csv::{ByteRecord, ReaderBuilder};
+use encoding_rs::{Encoding, GBK, SHIFT_JIS, UTF_8, WINDOWS_1252};
+use std::{
+    fs::File,
+    io::{Cursor, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+/// 分隔符文本的扩展名
+pub const EXTENSIONS: [&str; 4] = ["csv", "tsv", "psv", "tab"];
+
+/// 用于探测的样本大小
+const SAMPLE_SIZE: usize = 64 * 1024;
+/// 探测分隔符时最多分析的行数
+const SAMPLE_LINES: usize = 50;
+const DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+pub trait ReadSeek: Read + Seek + Send {}
+impl<T: Read + Seek + Send> ReadSeek for T {}
+
+/// 按扩展名判断是否为分隔符文本
+pub fn is_delimited(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| EXTENSIONS.iter().any(|x| e.eq_ignore_ascii_case(x)))
+}
+
+/// 分隔符文本的格式
+#[derive(Debug, Clone, Copy)]
+pub struct Dialect {
+    pub delimiter: u8,
+    pub quote: u8,
+    pub encoding: &'static Encoding,
+    /// BOM 的字节数
+    pub bom_len: usize,
+    /// 首行是否为表头
+    pub has_header: bool,
+}
+
+impl Dialect {
+    /// 读取文件开头的样本进行探测
+    pub fn sniff(path: &str) -> Result<Self, DocsError> {
+        let mut sample = Vec::with_capacity(SAMPLE_SIZE);
+        File::open(path)?
+            .take(SAMPLE_SIZE as u64)
+            .read_to_end(&mut sample)?;
+        let dialect = Self::from_sample(&sample, sample.len() == SAMPLE_SIZE, path);
+        log::info!(
+            "分隔符文本格式: {}, 分隔符 {:?}, 引号 {:?}, 编码 {}, 表头 {}",
+            path,
+            dialect.delimiter as char,
+            dialect.quote as char,
+            dialect.encoding.name(),
+            dialect.has_header
+        );
+        Ok(dialect)
+    }
+
+    fn from_sample(sample: &[u8], truncated: bool, path: &str) -> Self {
+        let (encoding, bom_len) = match Encoding::for_bom(sample) {
+            Some((encoding, len)) => (encoding, len),
+            None => (detect_encoding(sample, truncated), 0),
+        };
+        let mut body = &sample[bom_len..];
+        // 样本被截断时只分析完整的行
+        if truncated {
+            if let Some(end) = body.iter().rposition(|&b| b == b'\n') {
+                body = &body[..end];
+            }
+        }
+        let (text, _) = encoding.decode_without_bom_handling(body);
+
+        let quote = detect_quote(&text);
+        let delimiter = detect_delimiter(&text, quote).unwrap_or_else(|| {
+            // 无法判断时按扩展名
+            let extension = Path::new(path).extension().and_then(|e| e.to_str());
+            match extension.map(|e| e.to_ascii_lowercase()).as_deref() {
+                Some("tsv" | "tab") => b'\t',
+                Some("psv") => b'|',
+                _ => b',',
+            }
+        });
+        let has_header = detect_header(&text, delimiter, quote);
+        Dialect {
+            delimiter,
+            quote,
+            encoding,
+            bom_len,
+            has_header,
+        }
+    }
+
+    /// 按探测结果配置的 csv 读取器，表头由调用方处理
+    pub fn reader_builder(&self) -> ReaderBuilder {
+        let mut builder = ReaderBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .has_headers(false)
+            .flexible(true);
+        builder
+    }
+
+    /// 打开文件并定位到 BOM 之后；其他编码先整体转为 UTF-8，避免多字节字符的尾字节被当作分隔符
+    pub fn open(&self, path: &str) -> Result<Box<dyn ReadSeek>, DocsError> {
+        let mut file = File::open(path)?;
+        if self.encoding == UTF_8 {
+            file.seek(SeekFrom::Start(self.bom_len as u64))?;
+            return Ok(Box::new(file));
+        }
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        let (text, _) = self
+            .encoding
+            .decode_without_bom_handling(&data[self.bom_len..]);
+        Ok(Box::new(Cursor::new(text.into_owned().into_bytes())))
+    }
+
+    /// 解码一条记录的各字段，`open` 返回的内容总是 UTF-8
+    pub fn fields(&self, record: &ByteRecord) -> Vec<String> {
+        record
+            .iter()
+            .map(|field| String::from_utf8_lossy(field).into_owned())
+            .collect()
+    }
+}
+
+/// 没有 BOM 时识别编码：合法的 UTF-8 优先，其次是最像中日文的 GBK/Shift-JIS，最后是 Windows-1252
+pub(crate) fn detect_encoding(sample: &[u8], truncated: bool) -> &'static Encoding {
+    match std::str::from_utf8(sample) {
+        Ok(_) => return UTF_8,
+        // 只是末尾的多字节字符被截断
+        Err(e) if truncated && e.error_len().is_none() => return UTF_8,
+        Err(_) => {},
+    }
+    let sample = match truncated {
+        true => sample
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(sample, |end| &sample[..end]),
+        false => sample,
+    };
+
+    [GBK, SHIFT_JIS]
+        .into_iter()
+        .filter_map(|encoding| {
+            let text = encoding.decode_without_bom_handling_and_without_replacement(sample)?;
+            Some((cjk_score(&text), encoding))
+        })
+        .filter(|(score, _)| *score > 0)
+        .max_by_key(|(score, _)| *score)
+        .map_or(WINDOWS_1252, |(_, encoding)| encoding)
+}
+
+fn cjk_score(text: &str) -> i64 {
+    text.chars()
+        .map(|c| match c as u32 {
+            // 平假名、片假名
+            0x3040..=0x30FF => 2,
+            // 汉字、CJK 标点、全角字符
+            0x4E00..=0x9FFF | 0x3000..=0x303F | 0xFF01..=0xFF5E => 1,
+            // 半角片假名：GBK 文本按 Shift-JIS 解码时会大量出现
+            0xFF61..=0xFF9F => -1,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// 出现在字段开头更多的引号字符，默认 `"`
+fn detect_quote(text: &str) -> u8 {
+    let (mut double, mut single) = (0, 0);
+    let mut previous = '\n';
+    for c in text.chars() {
+        let field_start =
+            previous == '\n' || (previous.is_ascii() && DELIMITERS.contains(&(previous as u8)));
+        if field_start {
+            match c {
+                '"' => double += 1,
+                '\'' => single += 1,
+                _ => {},
+            }
+        }
+        previous = c;
+    }
+    if single > double {
+        b'\''
+    } else {
+        b'"'
+    }
+}
+
+/// 选择各行出现次数最一致的分隔符
+fn detect_delimiter(text: &str, quote: u8) -> Option<u8> {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .take(SAMPLE_LINES)
+        .collect();
+
+    DELIMITERS
+        .into_iter()
+        .filter_map(|delimiter| {
+            let counts: Vec<usize> = lines
+                .iter()
+                .map(|line| count_outside_quotes(line, delimiter, quote))
+                .collect();
+            // 出现最多的非零次数及其行数
+            let (count, frequency) = counts
+                .iter()
+                .filter(|&&c| c > 0)
+                .map(|&c| (c, counts.iter().filter(|&&x| x == c).count()))
+                .max_by_key(|&(c, f)| (f, c))?;
+            Some(((frequency, count), delimiter))
+        })
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, delimiter)| delimiter)
+}
+
+fn count_outside_quotes(line: &str, delimiter: u8, quote: u8) -> usize {
+    let mut quoted = false;
+    let mut count = 0;
+    for b in line.bytes() {
+        if b == quote {
+            quoted = !quoted;
+        } else if b == delimiter && !quoted {
+            count += 1;
+        }
+    }
+    count
+}
+
+/// 首行各列与其下数据的类型或长度明显不同时视为表头
+fn detect_header(text: &str, delimiter: u8, quote: u8) -> bool {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .quote(quote)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let rows: Vec<Vec<String>> = reader
+        .records()
+        .take(SAMPLE_LINES)
+        .filter_map(|r| r.ok())
+        .map(|r| r.iter().map(|f| f.trim().to_string()).collect())
+        .collect();
+    let Some((header, data)) = rows.split_first() else {
+        return false;
+    };
+    if data.is_empty() || header.iter().any(|h| h.is_empty() || is_number(h)) {
+        return false;
+    }
+
+    let mut votes = 0;
+    for (col, name) in header.iter().enumerate() {
+        let values: Vec<&str> = data
+            .iter()
+            .filter_map(|row| row.get(col).map(String::as_str))
+            .filter(|v| !v.is_empty())
+            .collect();
+        if values.is_empty() {
+            continue;
+        }
+        let numeric = values.iter().filter(|v| is_number(v)).count();
+        let length = values[0].chars().count();
+        // 数值列上方的文字是最明显的表头特征
+        if numeric * 2 > values.len() {
+            votes += 2;
+        } else if values.iter().all(|v| v.chars().count() == length) {
+            votes += if name.chars().count() != length {
+                1
+            } else {
+                -1
+            };
+        }
+    }
+    votes > 0
+}
+
+fn is_number(text: &str) -> bool {
+    let text = text.trim().trim_end_matches('%');
+    !text.is_empty()
+        && text
+            .replace(',', "")
+            .parse::<f64>()
+            .is_ok_and(|n| n.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 没有扩展名时使用 `.csv`
+    fn temp_file(name: &str, data: &[u8]) -> String {
+        let path = std::env::temp_dir().join(format!("{}-{}", std::process::id(), name));
+        let path = match path.extension() {
+            Some(_) => path,
+            None => path.with_extension("csv"),
+        };
+        std::fs::write(&path, data).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    /// 按探测结果读取全部记录
+    fn read(path: &str) -> (Dialect, Vec<Vec<String>>) {
+        let dialect = Dialect::sniff(path).unwrap();
+        let mut reader = dialect
+            .reader_builder()
+            .from_reader(dialect.open(path).unwrap());
+        let rows = reader
+            .byte_records()
+            .map(|r| dialect.fields(&r.unwrap()))
+            .collect();
+        (dialect, rows)
+    }
+
+    #[test]
+    fn multibyte_trail_byte_is_not_a_delimiter() {
+        // “億”“剕”的 GBK 编码与“ポ”“倒”的 Shift-JIS 编码的第二个字节都是 `|`
+        for (name, encoding, text) in [
+            ("gbk", GBK, "名称|数量\n億元|12\n剕|3\n"),
+            ("sjis", SHIFT_JIS, "品名|数\nポイント|1\n倒れる|2\n"),
+        ] {
+            let (bytes, _, _) = encoding.encode(text);
+            let path = temp_file(name, &bytes);
+            let (dialect, rows) = read(&path);
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(dialect.encoding, encoding);
+            assert_eq!(dialect.delimiter, b'|');
+            let expected: Vec<Vec<String>> = text
+                .lines()
+                .map(|l| l.split('|').map(str::to_string).collect())
+                .collect();
+            assert_eq!(rows, expected);
+        }
+    }
+
+    fn sniff(name: &str, data: &[u8]) -> Dialect {
+        let path = temp_file(name, data);
+        let dialect = Dialect::sniff(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        dialect
+    }
+
+    #[test]
+    fn delimiter_detection() {
+        let semicolon = sniff("semicolon", b"name;price\napple;1,5\npear;2,25\n");
+        assert_eq!(semicolon.delimiter, b';');
+        let tab = sniff("tab", b"a\tb\tc\n1\t2\t3\n4\t5\t6\n");
+        assert_eq!(tab.delimiter, b'\t');
+        // 引号内的逗号不计入
+        let quoted = sniff("quoted", b"\"a,b\"|c\n\"d,e\"|f\n\"g,h\"|i\n");
+        assert_eq!((quoted.delimiter, quoted.quote), (b'|', b'"'));
+        let single = sniff("single", b"'x;y',1\n'z;w',2\n");
+        assert_eq!((single.delimiter, single.quote), (b',', b'\''));
+    }
+
+    #[test]
+    fn delimiter_falls_back_to_extension() {
+        assert_eq!(sniff("fallback.tsv", b"one\ntwo\n").delimiter, b'\t');
+        assert_eq!(sniff("fallback.psv", b"one\ntwo\n").delimiter, b'|');
+        assert_eq!(sniff("fallback", b"one\ntwo\n").delimiter, b',');
+    }
+
+    #[test]
+    fn encoding_detection() {
+        let bom = sniff("bom", "\u{feff}名称,数量\n苹果,3\n".as_bytes());
+        assert_eq!((bom.encoding, bom.bom_len), (UTF_8, 3));
+        // encoding_rs 不能编码为 UTF-16，手工写入
+        let mut data = vec![0xFF, 0xFE];
+        for unit in "a,b\n1,2\n".encode_utf16() {
+            data.extend_from_slice(&unit.to_le_bytes());
+        }
+        let utf16 = sniff("utf16", &data);
+        assert_eq!((utf16.encoding, utf16.bom_len), (encoding_rs::UTF_16LE, 2));
+
+        let (gbk, _, _) = GBK.encode("名称,数量\n苹果,3\n香蕉,5\n");
+        assert_eq!(sniff("gbk", &gbk).encoding, GBK);
+        let (sjis, _, _) = SHIFT_JIS.encode("名前,数\nりんご,3\nみかん,5\n");
+        assert_eq!(sniff("sjis", &sjis).encoding, SHIFT_JIS);
+        assert_eq!(
+            sniff("latin1", b"caf\xe9,cr\xe8me\n1,2\n").encoding,
+            WINDOWS_1252
+        );
+    }
+
+    #[test]
+    fn header_detection() {
+        assert!(sniff("numeric", b"name,age\nalice,30\nbob,41\n").has_header);
+        assert!(!sniff("data", b"alice,30\nbob,41\ncarol,29\n").has_header);
+        // 首行含数字时不是表头
+        assert!(!sniff("numbers", b"1,2\n3,4\n5,6\n").has_header);
+        // 定长代码列上方长度不同的文字
+        assert!(sniff("codes", b"id,city\nAB12,Paris\nCD34,Rome\n").has_header);
+        assert!(!sniff("single", b"name,age\n").has_header);
+    }
+}
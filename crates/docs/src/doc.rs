@@ -0,0 +1,461 @@
+//! Word 97-2003（.doc）：按分段表（piece table）还原正文文本，按段落属性识别表格
+
+use crate::{
+    document::{Block, Document, Inline, Paragraph, Run, Table, TableCell, TableRow},
+    error::DocsError,
+    metadata::DocumentMetadata,
+    ole::{self, u16_at, u32_at},
+};
+use encoding_rs::WINDOWS_1252;
+
+const WORD_STREAM: &str = "WordDocument";
+/// FIB 中的魔数
+const WORD_IDENT: u16 = 0xA5EC;
+/// Word 97 的 nFib，更早的版本没有分段表
+const MIN_NFIB: u16 = 0x00C0;
+/// FKP 页大小
+const PAGE_SIZE: usize = 512;
+/// 一页 PapxFkp 最多容纳的段落数：crun + 1 个 FC 与 crun 个 13 字节的 BxPap 需放在末字节之前
+const MAX_CRUN: usize = (PAGE_SIZE - 1 - 4) / 17;
+
+/// 文本中的控制字符
+const PARAGRAPH_END: char = '\r';
+const CELL_END: char = '\u{7}';
+const LINE_BREAK: char = '\u{b}';
+const PAGE_BREAK: char = '\u{c}';
+const FIELD_BEGIN: char = '\u{13}';
+const FIELD_SEPARATOR: char = '\u{14}';
+const FIELD_END: char = '\u{15}';
+
+/// 分段表中的一段文本
+struct Piece {
+    cp_start: u32,
+    cp_end: u32,
+    /// 在 WordDocument 流中的字节偏移
+    fc: u32,
+    /// 单字节（Windows-1252）存储
+    compressed: bool,
+}
+
+/// 一段连续字节范围内段落共有的属性
+struct ParagraphProps {
+    fc_start: u32,
+    fc_end: u32,
+    /// 样式索引，1-9 是内置的标题 1-9
+    istd: u16,
+    in_table: bool,
+    /// 表格行结束标记所在的段落
+    row_end: bool,
+}
+
+/// 解析 doc 文件
+pub fn parse(path: &str) -> Result<Document, DocsError> {
+    let mut cfb = ole::open(path)?;
+    let word = ole::read_stream(&mut cfb, WORD_STREAM)?
+        .ok_or_else(|| DocsError::missing_part(WORD_STREAM))?;
+    let fib = Fib::parse(&word)?;
+    let table_name = if fib.table_one { "1Table" } else { "0Table" };
+    let table = ole::read_stream(&mut cfb, table_name)?
+        .ok_or_else(|| DocsError::missing_part(table_name))?;
+
+    let clx = slice(&table, fib.clx).ok_or_else(|| corrupt("分段表越界"))?;
+    let pieces = parse_pieces(clx).ok_or_else(|| corrupt("分段表无效"))?;
+    let text = read_text(&word, &pieces, fib.ccp_text);
+    let props = match slice(&table, fib.plcf_bte_papx) {
+        Some(plcf) => paragraph_props(&word, plcf),
+        None => Vec::new(),
+    };
+
+    let mut builder = Builder::default();
+    for (c, fc) in text {
+        builder.push(c, || props_at(&props, fc));
+    }
+    let mut document = Document {
+        blocks: builder.finish(),
+        ..Default::default()
+    };
+    document.metadata = DocumentMetadata::from_ole(&mut cfb)
+        .map_err(|e| log::error!("读取文档属性失败: {}", e))
+        .ok()
+        .flatten();
+    Ok(document)
+}
+
+fn corrupt(reason: &str) -> DocsError {
+    DocsError::corrupt(reason).in_part(WORD_STREAM)
+}
+
+fn slice(data: &[u8], (offset, len): (u32, u32)) -> Option<&[u8]> {
+    if len == 0 {
+        return None;
+    }
+    let start = offset as usize;
+    data.get(start..start.checked_add(len as usize)?)
+}
+
+/// 文件信息块中用到的字段
+struct Fib {
+    table_one: bool,
+    ccp_text: u32,
+    /// (偏移, 长度)
+    clx: (u32, u32),
+    plcf_bte_papx: (u32, u32),
+}
+
+impl Fib {
+    fn parse(data: &[u8]) -> Result<Self, DocsError> {
+        let truncated = || corrupt("FIB 不完整");
+        if u16_at(data, 0) != Some(WORD_IDENT) {
+            return Err(corrupt("不是 Word 文档"));
+        }
+        let nfib = u16_at(data, 2).ok_or_else(truncated)?;
+        if nfib < MIN_NFIB {
+            return Err(DocsError::Unsupported("Word 95 及更早版本".to_string()));
+        }
+        let flags = u16_at(data, 0x0A).ok_or_else(truncated)?;
+        if flags & 0x0100 != 0 {
+            return Err(DocsError::Encrypted);
+        }
+
+        // FibBase 之后依次是 fibRgW、fibRgLw 与 fibRgFcLcb，各自以长度开头
+        let csw = u16_at(data, 32).ok_or_else(truncated)? as usize;
+        let rg_lw = 34 + csw * 2 + 2;
+        let cslw = u16_at(data, rg_lw - 2).ok_or_else(truncated)? as usize;
+        let rg_fc_lcb = rg_lw + cslw * 4 + 2;
+        let pair = |index: usize| -> Option<(u32, u32)> {
+            let offset = rg_fc_lcb + index * 8;
+            Some((u32_at(data, offset)?, u32_at(data, offset + 4)?))
+        };
+        Ok(Fib {
+            table_one: flags & 0x0200 != 0,
+            ccp_text: u32_at(data, rg_lw + 3 * 4).ok_or_else(truncated)?,
+            clx: pair(33).ok_or_else(truncated)?,
+            plcf_bte_papx: pair(13).ok_or_else(truncated)?,
+        })
+    }
+}
+
+/// 跳过 Clx 开头的 Prc，解析其后的 Pcdt
+fn parse_pieces(clx: &[u8]) -> Option<Vec<Piece>> {
+    let mut offset = 0;
+    while *clx.get(offset)? == 0x01 {
+        offset += 3 + u16_at(clx, offset + 1)? as usize;
+    }
+    if *clx.get(offset)? != 0x02 {
+        return None;
+    }
+    let len = u32_at(clx, offset + 1)? as usize;
+    let plc = clx.get(offset + 5..(offset + 5).checked_add(len)?)?;
+    // n + 1 个 CP 与 n 个 8 字节的 Pcd
+    let count = (len.checked_sub(4)?) / 12;
+    let mut pieces = Vec::with_capacity(count);
+    for i in 0..count {
+        let fc = u32_at(plc, (count + 1) * 4 + i * 8 + 2)?;
+        pieces.push(Piece {
+            cp_start: u32_at(plc, i * 4)?,
+            cp_end: u32_at(plc, (i + 1) * 4)?,
+            fc: if fc & 0x4000_0000 != 0 {
+                (fc & 0x3FFF_FFFF) / 2
+            } else {
+                fc
+            },
+            compressed: fc & 0x4000_0000 != 0,
+        });
+    }
+    Some(pieces)
+}
+
+/// 读取正文的 `ccp` 个字符及其在流中的字节偏移
+fn read_text(word: &[u8], pieces: &[Piece], ccp: u32) -> Vec<(char, u32)> {
+    let mut text = Vec::new();
+    for piece in pieces {
+        if piece.cp_start >= ccp {
+            break;
+        }
+        let count = (piece.cp_end.min(ccp).saturating_sub(piece.cp_start)) as usize;
+        let start = piece.fc as usize;
+        if piece.compressed {
+            let Some(bytes) = word.get(start..start.saturating_add(count)) else {
+                continue;
+            };
+            let (decoded, _) = WINDOWS_1252.decode_without_bom_handling(bytes);
+            for (i, c) in decoded.chars().enumerate() {
+                text.push((c, piece.fc + i as u32));
+            }
+        } else {
+            let Some(bytes) = word.get(start..start.saturating_add(count * 2)) else {
+                continue;
+            };
+            let units = bytes
+                .chunks_exact(2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]));
+            let mut fc = piece.fc;
+            for c in char::decode_utf16(units) {
+                let c = c.unwrap_or(char::REPLACEMENT_CHARACTER);
+                text.push((c, fc));
+                fc += c.len_utf16() as u32 * 2;
+            }
+        }
+    }
+    text
+}
+
+/// 读取 PlcBtePapx 引用的各 FKP 页中的段落属性
+fn paragraph_props(word: &[u8], plcf: &[u8]) -> Vec<ParagraphProps> {
+    let count = plcf.len().saturating_sub(4) / 8;
+    let mut props = Vec::new();
+    for i in 0..count {
+        let Some(pn) = u32_at(plcf, (count + 1) * 4 + i * 4) else {
+            break;
+        };
+        let start = (pn & 0x003F_FFFF) as usize * PAGE_SIZE;
+        let Some(page) = word.get(start..start + PAGE_SIZE) else {
+            continue;
+        };
+        let crun = page.last().map_or(0, |&crun| crun as usize).min(MAX_CRUN);
+        for run in 0..crun {
+            let (Some(fc_start), Some(fc_end)) = (u32_at(page, run * 4), u32_at(page, run * 4 + 4))
+            else {
+                break;
+            };
+            let mut prop = ParagraphProps {
+                fc_start,
+                fc_end,
+                istd: 0,
+                in_table: false,
+                row_end: false,
+            };
+            let b_offset = page.get((crun + 1) * 4 + run * 13).copied().unwrap_or(0) as usize * 2;
+            if b_offset != 0 {
+                if let Some(grpprl) = papx_grpprl(page, b_offset) {
+                    apply_sprms(grpprl, &mut prop);
+                }
+            }
+            props.push(prop);
+        }
+    }
+    props.sort_by_key(|p| p.fc_start);
+    props
+}
+
+/// PapxInFkp 中的 istd 与 sprm 序列
+fn papx_grpprl(page: &[u8], offset: usize) -> Option<&[u8]> {
+    let cb = *page.get(offset)? as usize;
+    let (start, len) = if cb == 0 {
+        (offset + 2, *page.get(offset + 1)? as usize * 2)
+    } else {
+        (offset + 1, cb * 2 - 1)
+    };
+    page.get(start..start + len)
+}
+
+fn apply_sprms(grpprl: &[u8], prop: &mut ParagraphProps) {
+    let Some(istd) = u16_at(grpprl, 0) else {
+        return;
+    };
+    prop.istd = istd;
+    let mut offset = 2;
+    while let Some(sprm) = u16_at(grpprl, offset) {
+        offset += 2;
+        let operand = grpprl.get(offset).copied().unwrap_or(0);
+        // 操作数长度由 sprm 的 spra 位决定
+        let size = match sprm >> 13 {
+            0 | 1 => 1,
+            2 | 4 | 5 => 2,
+            3 => 4,
+            7 => 3,
+            // sprmTDefTable 的长度是两个字节，sprmPChgTabs 另有规则，均不影响这里的判断
+            _ if sprm == 0xD608 => u16_at(grpprl, offset).unwrap_or(0) as usize + 1,
+            _ => operand as usize + 1,
+        };
+        match sprm {
+            // sprmPFInTable
+            0x2416 => prop.in_table = operand != 0,
+            // sprmPFTtp、sprmPFInnerTtp
+            0x2417 | 0x244C => prop.row_end = operand != 0,
+            _ => {},
+        }
+        offset += size;
+    }
+}
+
+fn props_at(props: &[ParagraphProps], fc: u32) -> Option<&ParagraphProps> {
+    let index = props.partition_point(|p| p.fc_end <= fc);
+    props.get(index).filter(|p| p.fc_start <= fc)
+}
+
+/// 按控制字符把文本组装为段落与表格
+#[derive(Default)]
+struct Builder {
+    blocks: Vec<Block>,
+    inlines: Vec<Inline>,
+    text: String,
+    /// 当前表格已完成的行、当前行已完成的单元格、当前单元格已完成的段落
+    rows: Vec<TableRow>,
+    cells: Vec<TableCell>,
+    cell_blocks: Vec<Block>,
+    /// 域的嵌套状态，true 表示已到达域结果部分
+    fields: Vec<bool>,
+}
+
+impl Builder {
+    fn push<'a>(&mut self, c: char, props: impl Fn() -> Option<&'a ParagraphProps>) {
+        match c {
+            FIELD_BEGIN => return self.fields.push(false),
+            FIELD_SEPARATOR => {
+                if let Some(result) = self.fields.last_mut() {
+                    *result = true;
+                }
+                return;
+            },
+            FIELD_END => {
+                self.fields.pop();
+                return;
+            },
+            _ => {},
+        }
+        // 域代码本身不显示，只显示域结果
+        if self.fields.iter().any(|result| !result) {
+            return;
+        }
+
+        match c {
+            PARAGRAPH_END | PAGE_BREAK => {
+                let prop = props();
+                let paragraph = self.paragraph(prop);
+                if prop.is_some_and(|p| p.in_table) {
+                    self.cell_blocks.push(paragraph);
+                } else {
+                    self.end_table();
+                    self.blocks.push(paragraph);
+                }
+            },
+            CELL_END => {
+                let prop = props();
+                let paragraph = self.paragraph(prop);
+                if prop.is_some_and(|p| p.row_end) {
+                    self.cell_blocks.clear();
+                    let cells = std::mem::take(&mut self.cells);
+                    self.rows.push(TableRow { cells });
+                } else {
+                    self.cell_blocks.push(paragraph);
+                    let blocks = std::mem::take(&mut self.cell_blocks);
+                    self.cells.push(TableCell { blocks, ..Default::default() });
+                }
+            },
+            LINE_BREAK => {
+                self.flush_text();
+                self.inlines.push(Inline::Break);
+            },
+            '\t' => self.text.push('\t'),
+            // 不间断连字符
+            '\u{1e}' => self.text.push('-'),
+            // 可选连字符、图片与脚注等对象的占位符
+            c if c.is_control() => {},
+            c => self.text.push(c),
+        }
+    }
+
+    fn flush_text(&mut self) {
+        if !self.text.is_empty() {
+            let text = std::mem::take(&mut self.text);
+            self.inlines
+                .push(Inline::Text(Run { text, ..Default::default() }));
+        }
+    }
+
+    fn paragraph(&mut self, prop: Option<&ParagraphProps>) -> Block {
+        self.flush_text();
+        let heading = prop
+            .map(|p| p.istd)
+            .filter(|istd| (1..=9).contains(istd))
+            .map(|istd| istd.min(6) as u8);
+        Block::Paragraph(Paragraph {
+            heading,
+            inlines: std::mem::take(&mut self.inlines),
+            ..Default::default()
+        })
+    }
+
+    fn end_table(&mut self) {
+        if !self.cells.is_empty() || !self.cell_blocks.is_empty() {
+            let mut blocks = std::mem::take(&mut self.cell_blocks);
+            if !blocks.is_empty() {
+                self.cells.push(TableCell {
+                    blocks: std::mem::take(&mut blocks),
+                    ..Default::default()
+                });
+            }
+            let cells = std::mem::take(&mut self.cells);
+            self.rows.push(TableRow { cells });
+        }
+        if !self.rows.is_empty() {
+            let rows = std::mem::take(&mut self.rows);
+            self.blocks.push(Block::Table(Table { rows }));
+        }
+    }
+
+    fn finish(mut self) -> Vec<Block> {
+        if !self.text.is_empty() || !self.inlines.is_empty() {
+            let paragraph = self.paragraph(None);
+            self.end_table();
+            self.blocks.push(paragraph);
+        }
+        self.end_table();
+        self.blocks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 在第 `pn` 页写入 FKP，返回 WordDocument 流与指向该页的 PlcBtePapx
+    fn fkp(pn: u32, page: [u8; PAGE_SIZE]) -> (Vec<u8>, Vec<u8>) {
+        let mut word = vec![0u8; pn as usize * PAGE_SIZE];
+        word.extend_from_slice(&page);
+        let mut plcf = Vec::new();
+        for v in [0u32, u32::MAX, pn] {
+            plcf.extend_from_slice(&v.to_le_bytes());
+        }
+        (word, plcf)
+    }
+
+    #[test]
+    fn oversized_crun_is_clamped() {
+        let mut page = [0xFFu8; PAGE_SIZE];
+        page[PAGE_SIZE - 1] = 255;
+        let (word, plcf) = fkp(1, page);
+        assert_eq!(paragraph_props(&word, &plcf).len(), MAX_CRUN);
+    }
+
+    #[test]
+    fn table_paragraph_props() {
+        let mut page = [0u8; PAGE_SIZE];
+        page[..4].copy_from_slice(&100u32.to_le_bytes());
+        page[4..8].copy_from_slice(&200u32.to_le_bytes());
+        // BxPap 指向 0x100 处的 PapxInFkp：istd 2 与 sprmPFInTable
+        page[8] = 0x80;
+        page[0x100..0x108].copy_from_slice(&[4, 2, 0, 0x16, 0x24, 1, 0, 0]);
+        page[PAGE_SIZE - 1] = 1;
+        let (word, plcf) = fkp(0, page);
+        let props = paragraph_props(&word, &plcf);
+        let prop = props_at(&props, 150).unwrap();
+        assert_eq!((prop.istd, prop.in_table, prop.row_end), (2, true, false));
+        assert!(props_at(&props, 200).is_none());
+    }
+
+    #[test]
+    fn out_of_range_offsets() {
+        assert!(slice(&[0; 8], (u32::MAX, u32::MAX)).is_none());
+        let clx = [0x02, 0xFF, 0xFF, 0xFF, 0xFF];
+        assert!(parse_pieces(&clx).is_none());
+        let piece = Piece {
+            cp_start: 0,
+            cp_end: 10,
+            fc: u32::MAX,
+            compressed: false,
+        };
+        assert!(read_text(&[0; 16], &[piece], 10).is_empty());
+    }
+}
@@ -0,0 +1,175 @@
+use crate::metadata::DocumentMetadata;
+use serde::Serialize;
+
+/// 文字处理类文档（docx/odt/rtf/doc）的统一结构化模型
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Document {
+    /// 正文
+    pub blocks: Vec<Block>,
+    /// 页眉，每个元素对应一个页眉部件
+    pub headers: Vec<Vec<Block>>,
+    /// 页脚，每个元素对应一个页脚部件
+    pub footers: Vec<Vec<Block>>,
+    /// 脚注
+    pub footnotes: Vec<Note>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<DocumentMetadata>,
+}
+
+/// 块级元素
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Block {
+    Paragraph(Paragraph),
+    Table(Table),
+}
+
+/// 段落
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Paragraph {
+    /// 样式名
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    /// 标题级别（1-6）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading: Option<u8>,
+    /// 列表信息
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub list: Option<ListInfo>,
+    /// 对齐方式：left/center/right/justify
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub align: Option<String>,
+    pub inlines: Vec<Inline>,
+}
+
+/// 列表项信息
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListInfo {
+    /// 嵌套层级（从 0 开始）
+    pub level: u8,
+    /// 是否为有序列表
+    pub ordered: bool,
+}
+
+/// 行内元素
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Inline {
+    Text(Run),
+    Image(Image),
+    Break,
+    /// 脚注引用
+    Note {
+        id: String,
+    },
+}
+
+/// 具有相同格式的一段文字
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Run {
+    pub text: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub bold: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub italic: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub underline: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub strike: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub superscript: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub subscript: bool,
+    /// 颜色，`#RRGGBB`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// 字号（pt）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font: Option<String>,
+    /// 超链接地址
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub href: Option<String>,
+}
+
+/// 图片，`src` 为 data URI
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Image {
+    pub src: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt: Option<String>,
+    /// 宽度（px）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<f32>,
+    /// 高度（px）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<f32>,
+}
+
+/// 表格
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Table {
+    pub rows: Vec<TableRow>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TableRow {
+    pub cells: Vec<TableCell>,
+}
+
+/// 单元格，被合并覆盖的单元格不会出现在行中
+#[derive(Debug, Clone, Serialize)]
+pub struct TableCell {
+    pub blocks: Vec<Block>,
+    pub col_span: u32,
+    pub row_span: u32,
+}
+
+impl Default for TableCell {
+    fn default() -> Self {
+        Self {
+            blocks: Vec::new(),
+            col_span: 1,
+            row_span: 1,
+        }
+    }
+}
+
+/// 脚注/尾注
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Note {
+    pub id: String,
+    pub blocks: Vec<Block>,
+}
+
+impl Paragraph {
+    /// 段落的纯文本
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for inline in &self.inlines {
+            match inline {
+                Inline::Text(run) => text.push_str(&run.text),
+                Inline::Break => text.push('\n'),
+                Inline::Image(_) | Inline::Note { .. } => {},
+            }
+        }
+        text
+    }
+}
+
+/// 块的纯文本，段落之间以换行分隔，表格按单元格逐个输出
+pub fn plain_text(blocks: &[Block]) -> String {
+    let mut lines = Vec::new();
+    for block in blocks {
+        match block {
+            Block::Paragraph(p) => lines.push(p.text()),
+            Block::Table(table) => {
+                for cell in table.rows.iter().flat_map(|r| &r.cells) {
+                    lines.push(plain_text(&cell.blocks));
+                }
+            },
+        }
+    }
+    lines.join("\n").trim().to_string()
+}
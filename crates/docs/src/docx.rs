@@ -0,0 +1,517 @@
+use crate::{
+    document::{
+        Block, Document, Image, Inline, ListInfo, Note, Paragraph, Run, Table, TableCell, TableRow,
+    },
+    error::DocsError,
+    metadata::DocumentMetadata,
+    ooxml::{attr, child, emu_to_px, is, rel_attr, Package, Relationship},
+};
+use roxmltree::Node;
+use std::collections::HashMap;
+
+const DOCUMENT_PART: &str = "word/document.xml";
+
+/// 样式表中的段落样式
+#[derive(Debug, Default)]
+struct Style {
+    name: String,
+    based_on: Option<String>,
+    outline_level: Option<u8>,
+    num: Option<(String, u8)>,
+}
+
+struct Parser {
+    package: Package,
+    styles: HashMap<String, Style>,
+    /// numId -> 每个层级的编号格式
+    numbering: HashMap<String, HashMap<u8, String>>,
+}
+
+/// 解析 docx 文件
+pub fn parse(path: &str) -> Result<Document, DocsError> {
+    let mut package = Package::open(path)?;
+    if !package.contains(DOCUMENT_PART) {
+        return Err(DocsError::missing_part(DOCUMENT_PART));
+    }
+
+    let styles = match package.read_string("word/styles.xml")? {
+        Some(xml) => parse_styles(&roxmltree::Document::parse(&xml)?),
+        None => HashMap::new(),
+    };
+    let numbering = match package.read_string("word/numbering.xml")? {
+        Some(xml) => parse_numbering(&roxmltree::Document::parse(&xml)?),
+        None => HashMap::new(),
+    };
+    let mut parser = Parser { package, styles, numbering };
+
+    let rels = parser.package.relationships(DOCUMENT_PART)?;
+    let xml = parser
+        .package
+        .read_string(DOCUMENT_PART)?
+        .unwrap_or_default();
+    let doc = roxmltree::Document::parse(&xml)?;
+    let body = doc
+        .root_element()
+        .children()
+        .find(|n| is(n, "body"))
+        .ok_or_else(|| DocsError::corrupt("缺少 body 元素").in_part(DOCUMENT_PART))?;
+
+    let mut document = Document {
+        blocks: parser.blocks(body, &rels),
+        ..Default::default()
+    };
+
+    // 页眉页脚按 sectPr 中出现的顺序读取，同一部件只读取一次
+    let mut seen = Vec::new();
+    for reference in body
+        .descendants()
+        .filter(|n| is(n, "headerReference") || is(n, "footerReference"))
+    {
+        let Some(rel) = rel_attr(&reference, "id").and_then(|id| rels.get(id)) else {
+            continue;
+        };
+        if rel.external || seen.contains(&rel.target) {
+            continue;
+        }
+        seen.push(rel.target.clone());
+        let blocks = parser.part_blocks(&rel.target)?;
+        if blocks.is_empty() {
+            continue;
+        }
+        if is(&reference, "headerReference") {
+            document.headers.push(blocks);
+        } else {
+            document.footers.push(blocks);
+        }
+    }
+
+    for kind in ["footnote", "endnote"] {
+        let Some(target) = rels
+            .values()
+            .find(|r| r.kind == format!("{}s", kind))
+            .map(|r| r.target.clone())
+        else {
+            continue;
+        };
+        document.footnotes.extend(parser.notes(&target, kind)?);
+    }
+
+    document.metadata = DocumentMetadata::from_ooxml(&mut parser.package)
+        .map_err(|e| log::error!("读取文档属性失败: {}", e))
+        .ok()
+        .flatten();
+    Ok(document)
+}
+
+impl Parser {
+    /// 读取页眉/页脚部件
+    fn part_blocks(&mut self, part: &str) -> Result<Vec<Block>, DocsError> {
+        let Some(xml) = self.package.read_string(part)? else {
+            return Ok(Vec::new());
+        };
+        let rels = self.package.relationships(part)?;
+        let doc = roxmltree::Document::parse(&xml)?;
+        Ok(self.blocks(doc.root_element(), &rels))
+    }
+
+    /// 读取脚注/尾注部件，跳过分隔符等特殊注释
+    fn notes(&mut self, part: &str, kind: &str) -> Result<Vec<Note>, DocsError> {
+        let Some(xml) = self.package.read_string(part)? else {
+            return Ok(Vec::new());
+        };
+        let rels = self.package.relationships(part)?;
+        let doc = roxmltree::Document::parse(&xml)?;
+        let notes = doc
+            .root_element()
+            .children()
+            .filter(|n| is(n, kind))
+            .filter(|n| attr(n, "type").map_or(true, |t| t == "normal"))
+            .filter_map(|n| {
+                let id = attr(&n, "id")?;
+                Some(Note {
+                    id: note_id(kind, id),
+                    blocks: self.blocks(n, &rels),
+                })
+            })
+            .collect();
+        Ok(notes)
+    }
+
+    fn blocks(&mut self, parent: Node, rels: &HashMap<String, Relationship>) -> Vec<Block> {
+        let mut blocks = Vec::new();
+        for child in parent.children().filter(Node::is_element) {
+            match child.tag_name().name() {
+                "p" => blocks.push(Block::Paragraph(self.paragraph(child, rels))),
+                "tbl" => blocks.push(Block::Table(self.table(child, rels))),
+                // 内容控件、修订插入内容等容器
+                "sdt" | "sdtContent" | "ins" | "customXml" => {
+                    blocks.extend(self.blocks(child, rels))
+                },
+                _ => {},
+            }
+        }
+        blocks
+    }
+
+    fn paragraph(&mut self, node: Node, rels: &HashMap<String, Relationship>) -> Paragraph {
+        let mut paragraph = Paragraph::default();
+        let mut num = None;
+        let mut outline_level = None;
+
+        if let Some(ppr) = child(&node, "pPr") {
+            if let Some(style_id) = child(&ppr, "pStyle").and_then(|n| attr(&n, "val")) {
+                paragraph.style = Some(
+                    self.styles
+                        .get(style_id)
+                        .map(|s| s.name.clone())
+                        .unwrap_or_else(|| style_id.to_string()),
+                );
+                outline_level = self.style_outline_level(style_id);
+                num = self.style_num(style_id);
+            }
+            if let Some(level) = child(&ppr, "outlineLvl")
+                .and_then(|n| attr(&n, "val"))
+                .and_then(|v| v.parse().ok())
+            {
+                outline_level = Some(level);
+            }
+            if let Some(num_pr) = child(&ppr, "numPr") {
+                let num_id = child(&num_pr, "numId").and_then(|n| attr(&n, "val"));
+                let level = child(&num_pr, "ilvl")
+                    .and_then(|n| attr(&n, "val"))
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(0);
+                num = num_id.map(|id| (id.to_string(), level));
+            }
+            paragraph.align = child(&ppr, "jc").and_then(|n| attr(&n, "val")).map(|v| {
+                match v {
+                    "both" | "distribute" => "justify",
+                    "start" => "left",
+                    "end" => "right",
+                    v => v,
+                }
+                .to_string()
+            });
+        }
+
+        // outlineLvl 从 0 开始，9 表示正文
+        paragraph.heading = outline_level.filter(|l| *l < 6).map(|l| l + 1);
+        // numId 为 0 表示取消编号
+        paragraph.list = num.filter(|(id, _)| id != "0").map(|(id, level)| {
+            let format = self
+                .numbering
+                .get(&id)
+                .and_then(|levels| levels.get(&level))
+                .map(String::as_str);
+            ListInfo {
+                level,
+                ordered: !matches!(format, Some("bullet" | "none") | None),
+            }
+        });
+
+        self.inlines(node, rels, None, &mut paragraph.inlines);
+        paragraph
+    }
+
+    fn inlines(
+        &mut self,
+        parent: Node,
+        rels: &HashMap<String, Relationship>,
+        href: Option<&str>,
+        inlines: &mut Vec<Inline>,
+    ) {
+        for node in parent.children().filter(Node::is_element) {
+            match node.tag_name().name() {
+                "r" => self.run(node, rels, href, inlines),
+                "hyperlink" => {
+                    let target = rel_attr(&node, "id")
+                        .and_then(|id| rels.get(id))
+                        .filter(|r| r.external)
+                        .map(|r| r.target.clone());
+                    self.inlines(node, rels, target.as_deref().or(href), inlines);
+                },
+                "ins" | "smartTag" | "sdt" | "sdtContent" | "fldSimple" | "customXml" => {
+                    self.inlines(node, rels, href, inlines)
+                },
+                _ => {},
+            }
+        }
+    }
+
+    fn run(
+        &mut self,
+        node: Node,
+        rels: &HashMap<String, Relationship>,
+        href: Option<&str>,
+        inlines: &mut Vec<Inline>,
+    ) {
+        let mut template = Run {
+            href: href.map(str::to_string),
+            ..Default::default()
+        };
+        if let Some(rpr) = child(&node, "rPr") {
+            run_properties(&rpr, &mut template);
+        }
+
+        let mut text = String::new();
+        let flush = |text: &mut String, inlines: &mut Vec<Inline>| {
+            if !text.is_empty() {
+                inlines.push(Inline::Text(Run {
+                    text: std::mem::take(text),
+                    ..template.clone()
+                }));
+            }
+        };
+
+        for item in node.children().filter(Node::is_element) {
+            match item.tag_name().name() {
+                "t" => text.push_str(item.text().unwrap_or_default()),
+                "tab" => text.push('\t'),
+                "noBreakHyphen" => text.push('\u{2011}'),
+                "softHyphen" => text.push('\u{00AD}'),
+                "sym" => {
+                    if let Some(c) = attr(&item, "char")
+                        .and_then(|c| u32::from_str_radix(c, 16).ok())
+                        .and_then(char::from_u32)
+                    {
+                        text.push(c);
+                    }
+                },
+                "br" | "cr" => {
+                    flush(&mut text, inlines);
+                    inlines.push(Inline::Break);
+                },
+                "drawing" | "pict" | "object" | "AlternateContent" => {
+                    flush(&mut text, inlines);
+                    if let Some(image) = self.image(item, rels) {
+                        inlines.push(Inline::Image(image));
+                    }
+                },
+                "footnoteReference" | "endnoteReference" => {
+                    flush(&mut text, inlines);
+                    if let Some(id) = attr(&item, "id") {
+                        let kind = item.tag_name().name().trim_end_matches("Reference");
+                        inlines.push(Inline::Note { id: note_id(kind, id) });
+                    }
+                },
+                _ => {},
+            }
+        }
+        flush(&mut text, inlines);
+    }
+
+    /// 解析 DrawingML（`a:blip`）或 VML（`v:imagedata`）图片
+    fn image(&mut self, node: Node, rels: &HashMap<String, Relationship>) -> Option<Image> {
+        // mc:AlternateContent 只取第一个 Choice，避免与 Fallback 重复
+        let node = if is(&node, "AlternateContent") {
+            child(&node, "Choice").or_else(|| child(&node, "Fallback"))?
+        } else {
+            node
+        };
+
+        let reference = node
+            .descendants()
+            .find(|n| is(n, "blip") || is(n, "imagedata"))?;
+        let rel_id = rel_attr(&reference, "embed").or_else(|| rel_attr(&reference, "id"))?;
+        let rel = rels.get(rel_id).filter(|r| !r.external)?;
+        let src = self.package.image_data_uri(&rel.target)?;
+
+        let extent = node.descendants().find(|n| is(n, "extent"));
+        let doc_pr = node.descendants().find(|n| is(n, "docPr"));
+        Some(Image {
+            src,
+            alt: doc_pr
+                .and_then(|n| attr(&n, "descr").or_else(|| attr(&n, "name")))
+                .filter(|s| !s.is_empty())
+                .map(str::to_string),
+            width: extent.and_then(|n| attr(&n, "cx")).and_then(emu_to_px),
+            height: extent.and_then(|n| attr(&n, "cy")).and_then(emu_to_px),
+        })
+    }
+
+    fn table(&mut self, node: Node, rels: &HashMap<String, Relationship>) -> Table {
+        let mut table = Table::default();
+        // 网格列 -> 正在纵向合并的起始单元格 (行, 单元格)
+        let mut merges: HashMap<u32, (usize, usize)> = HashMap::new();
+
+        for tr in node.children().filter(|n| is(n, "tr")) {
+            let row_index = table.rows.len();
+            let mut row = TableRow::default();
+            let mut column = 0;
+
+            for tc in tr.children().filter(|n| is(n, "tc")) {
+                let tc_pr = child(&tc, "tcPr");
+                let col_span = tc_pr
+                    .and_then(|p| child(&p, "gridSpan"))
+                    .and_then(|n| attr(&n, "val"))
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(1u32)
+                    .max(1);
+                let v_merge = tc_pr.and_then(|p| child(&p, "vMerge"));
+
+                match v_merge.map(|n| attr(&n, "val").unwrap_or("continue")) {
+                    Some("continue") => {
+                        if let Some(&(r, c)) = merges.get(&column) {
+                            table.rows[r].cells[c].row_span += 1;
+                            column += col_span;
+                            continue;
+                        }
+                    },
+                    Some(_) => {
+                        merges.insert(column, (row_index, row.cells.len()));
+                    },
+                    None => {
+                        merges.remove(&column);
+                    },
+                }
+
+                row.cells.push(TableCell {
+                    blocks: self.blocks(tc, rels),
+                    col_span,
+                    row_span: 1,
+                });
+                column += col_span;
+            }
+            table.rows.push(row);
+        }
+        table
+    }
+
+    /// 沿 basedOn 链查找样式的大纲级别
+    fn style_outline_level(&self, style_id: &str) -> Option<u8> {
+        self.style_chain(style_id).find_map(|s| s.outline_level)
+    }
+
+    /// 沿 basedOn 链查找样式自带的编号
+    fn style_num(&self, style_id: &str) -> Option<(String, u8)> {
+        self.style_chain(style_id).find_map(|s| s.num.clone())
+    }
+
+    fn style_chain<'a>(&'a self, style_id: &str) -> impl Iterator<Item = &'a Style> {
+        let mut next = self.styles.get(style_id);
+        // 限制深度，防止循环引用
+        std::iter::from_fn(move || {
+            let current = next?;
+            next = current
+                .based_on
+                .as_deref()
+                .and_then(|id| self.styles.get(id));
+            Some(current)
+        })
+        .take(16)
+    }
+}
+
+fn run_properties(rpr: &Node, run: &mut Run) {
+    run.bold = toggle(rpr, "b");
+    run.italic = toggle(rpr, "i");
+    run.strike = toggle(rpr, "strike") || toggle(rpr, "dstrike");
+    run.underline = child(rpr, "u").is_some_and(|n| attr(&n, "val") != Some("none"));
+    match child(rpr, "vertAlign").and_then(|n| attr(&n, "val")) {
+        Some("superscript") => run.superscript = true,
+        Some("subscript") => run.subscript = true,
+        _ => {},
+    }
+    run.color = child(rpr, "color")
+        .and_then(|n| attr(&n, "val"))
+        .filter(|v| v.len() == 6 && v.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(|v| format!("#{}", v.to_ascii_uppercase()));
+    // sz 以半磅为单位
+    run.size = child(rpr, "sz")
+        .and_then(|n| attr(&n, "val"))
+        .and_then(|v| v.parse::<f32>().ok())
+        .map(|v| v / 2.0);
+    run.font = child(rpr, "rFonts")
+        .and_then(|n| attr(&n, "ascii").or_else(|| attr(&n, "eastAsia")))
+        .map(str::to_string);
+}
+
+/// 开关属性：`<w:b/>` 或 `<w:b w:val="true"/>` 为真，`w:val="0"/"false"` 为假
+fn toggle(rpr: &Node, name: &str) -> bool {
+    child(rpr, name).is_some_and(|n| !matches!(attr(&n, "val"), Some("0" | "false" | "off")))
+}
+
+fn parse_styles(doc: &roxmltree::Document) -> HashMap<String, Style> {
+    let mut styles = HashMap::new();
+    for node in doc.root_element().children().filter(|n| is(n, "style")) {
+        let Some(id) = attr(&node, "styleId") else {
+            continue;
+        };
+        let name = child(&node, "name")
+            .and_then(|n| attr(&n, "val"))
+            .unwrap_or(id)
+            .to_string();
+        let ppr = child(&node, "pPr");
+        let outline_level = ppr
+            .and_then(|p| child(&p, "outlineLvl"))
+            .and_then(|n| attr(&n, "val"))
+            .and_then(|v| v.parse().ok())
+            .or_else(|| heading_level(&name));
+        let num = ppr.and_then(|p| child(&p, "numPr")).and_then(|num_pr| {
+            let id = child(&num_pr, "numId").and_then(|n| attr(&n, "val"))?;
+            let level = child(&num_pr, "ilvl")
+                .and_then(|n| attr(&n, "val"))
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0);
+            Some((id.to_string(), level))
+        });
+        let based_on = child(&node, "basedOn")
+            .and_then(|n| attr(&n, "val"))
+            .map(str::to_string);
+        styles.insert(id.to_string(), Style { name, based_on, outline_level, num });
+    }
+    styles
+}
+
+/// 根据内置样式名判断大纲级别（从 0 开始）
+fn heading_level(name: &str) -> Option<u8> {
+    let lower = name.to_ascii_lowercase();
+    if lower == "title" {
+        return Some(0);
+    }
+    lower
+        .strip_prefix("heading")
+        .and_then(|n| n.trim().parse::<u8>().ok())
+        .filter(|n| (1..=9).contains(n))
+        .map(|n| n - 1)
+}
+
+fn parse_numbering(doc: &roxmltree::Document) -> HashMap<String, HashMap<u8, String>> {
+    let root = doc.root_element();
+    let abstract_nums: HashMap<&str, HashMap<u8, String>> = root
+        .children()
+        .filter(|n| is(n, "abstractNum"))
+        .filter_map(|n| {
+            let id = attr(&n, "abstractNumId")?;
+            let levels = n
+                .children()
+                .filter(|l| is(l, "lvl"))
+                .filter_map(|l| {
+                    let level = attr(&l, "ilvl")?.parse().ok()?;
+                    let format = child(&l, "numFmt").and_then(|f| attr(&f, "val"))?;
+                    Some((level, format.to_string()))
+                })
+                .collect();
+            Some((id, levels))
+        })
+        .collect();
+
+    root.children()
+        .filter(|n| is(n, "num"))
+        .filter_map(|n| {
+            let id = attr(&n, "numId")?;
+            let abstract_id = child(&n, "abstractNumId").and_then(|a| attr(&a, "val"))?;
+            let levels = abstract_nums.get(abstract_id)?.clone();
+            Some((id.to_string(), levels))
+        })
+        .collect()
+}
+
+/// 尾注与脚注的 id 可能重复，尾注加 `e` 前缀区分
+fn note_id(kind: &str, id: &str) -> String {
+    if kind == "endnote" {
+        format!("e{}", id)
+    } else {
+        id.to_string()
+    }
+}
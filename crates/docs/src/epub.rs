@@ -0,0 +1,1008 @@
+//! EPUB 电子书：container.xml 指向 OPF，OPF 给出元数据、清单与书脊；
+//! 目录优先取 EPUB 3 导航文档，其次是 NCX。章节按需读取，清理为不含脚本的 XHTML，
+//! 图片、字体等资源转换为 data URI。
+
+use crate::{
+    error::DocsError,
+    metadata::DocumentMetadata,
+    ooxml::{attr, child, is, resolve, Package},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use roxmltree::{Document as XmlDocument, Node, ParsingOptions};
+use serde::Serialize;
+use std::{borrow::Cow, collections::HashMap};
+
+const CONTAINER_PART: &str = "META-INF/container.xml";
+const ENCRYPTION_PART: &str = "META-INF/encryption.xml";
+const NCX_MEDIA_TYPE: &str = "application/x-dtbncx+xml";
+/// 字体混淆算法，只影响字体，不妨碍阅读
+const FONT_OBFUSCATION: [&str; 2] = [
+    "http://www.idpf.org/2008/embedding",
+    "http://ns.adobe.com/pdf/enc#RC",
+];
+/// 目录允许的最大嵌套层数
+const MAX_TOC_DEPTH: usize = 16;
+
+/// 保留的元素，其余元素只保留内容
+const ALLOWED_TAGS: &[&str] = &[
+    "a",
+    "abbr",
+    "article",
+    "aside",
+    "b",
+    "bdi",
+    "bdo",
+    "big",
+    "blockquote",
+    "br",
+    "caption",
+    "center",
+    "cite",
+    "code",
+    "col",
+    "colgroup",
+    "dd",
+    "del",
+    "dfn",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "i",
+    "img",
+    "ins",
+    "kbd",
+    "li",
+    "mark",
+    "ol",
+    "p",
+    "pre",
+    "q",
+    "rp",
+    "rt",
+    "ruby",
+    "s",
+    "samp",
+    "section",
+    "small",
+    "span",
+    "strike",
+    "strong",
+    "sub",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "time",
+    "tr",
+    "tt",
+    "u",
+    "ul",
+    "var",
+    "wbr",
+];
+/// 连同内容一起丢弃的元素
+const DROPPED_TAGS: &[&str] = &[
+    "audio", "base", "button", "canvas", "embed", "form", "head", "iframe", "input", "link",
+    "math", "meta", "noscript", "object", "script", "select", "style", "template", "textarea",
+    "title", "video",
+];
+const VOID_TAGS: &[&str] = &["br", "col", "hr", "img", "wbr"];
+/// 原样保留的属性
+const ALLOWED_ATTRIBUTES: &[&str] = &[
+    "abbr", "alt", "class", "colspan", "dir", "headers", "height", "id", "lang", "reversed",
+    "rowspan", "scope", "span", "start", "title", "type", "value", "width",
+];
+
+/// 电子书概要，章节内容通过 [`Epub::chapter`] 按需读取
+#[derive(Debug, Clone, Serialize)]
+pub struct Book {
+    pub metadata: BookMetadata,
+    /// 封面图片的 data URI
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover: Option<String>,
+    /// 书脊中的章节，按阅读顺序排列
+    pub chapters: Vec<Chapter>,
+    pub toc: Vec<TocEntry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BookMetadata {
+    pub title: Option<String>,
+    pub authors: Vec<String>,
+    pub language: Option<String>,
+    pub publisher: Option<String>,
+    pub description: Option<String>,
+    pub identifier: Option<String>,
+    /// 出版日期
+    pub date: Option<String>,
+    pub subjects: Vec<String>,
+    /// EPUB 版本，如 `2.0`、`3.0`
+    pub version: Option<String>,
+}
+
+/// 书脊中的一项
+#[derive(Debug, Clone, Serialize)]
+pub struct Chapter {
+    /// 包内路径
+    pub href: String,
+    /// 目录中第一个指向该章节的标题
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// `linear="no"` 的章节（如注释页）不在正常阅读顺序中
+    pub linear: bool,
+}
+
+/// 目录项
+#[derive(Debug, Clone, Serialize)]
+pub struct TocEntry {
+    pub title: String,
+    /// 指向的章节序号，目标不在书脊中时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chapter: Option<usize>,
+    /// 章节内的锚点
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TocEntry>,
+}
+
+/// 清理后的章节内容
+#[derive(Debug, Clone, Serialize)]
+pub struct ChapterContent {
+    pub index: usize,
+    /// `<body>` 内的 XHTML；指向其他章节的链接改为 `href="#"`，
+    /// 并带有 `data-chapter` 与 `data-anchor` 属性
+    pub html: String,
+    /// 章节引用的样式表，`@import` 已移除，`url()` 已转换为 data URI
+    pub styles: Vec<String>,
+}
+
+/// 清单中的一项
+struct ManifestItem {
+    /// 包内路径
+    href: String,
+    media_type: String,
+    properties: String,
+}
+
+/// 打开的 EPUB 包
+pub struct Epub {
+    package: Package,
+    metadata: BookMetadata,
+    /// 清单 id -> 项
+    manifest: HashMap<String, ManifestItem>,
+    /// 书脊：(清单 id, 是否线性)
+    spine: Vec<(String, bool)>,
+    /// 书脊指定的 NCX 的清单 id
+    ncx: Option<String>,
+    /// EPUB 2 `<meta name="cover">` 指定的清单 id
+    cover_id: Option<String>,
+}
+
+impl Epub {
+    /// 打开电子书并解析 OPF
+    pub fn open(path: &str) -> Result<Self, DocsError> {
+        let mut package = Package::open(path)?;
+        check_encryption(&mut package)?;
+
+        let container = package
+            .read_string(CONTAINER_PART)?
+            .ok_or_else(|| DocsError::missing_part(CONTAINER_PART))?;
+        let container = parse_xml(&container, CONTAINER_PART)?;
+        let opf_path = container
+            .descendants()
+            .filter(|n| is(n, "rootfile"))
+            .find(|n| attr(n, "media-type").map_or(true, |t| t == "application/oebps-package+xml"))
+            .and_then(|n| attr(&n, "full-path"))
+            .map(|p| resolve("", &percent_decode(p)))
+            .ok_or_else(|| DocsError::corrupt("没有指定 OPF").in_part(CONTAINER_PART))?;
+
+        let opf = package
+            .read_string(&opf_path)?
+            .ok_or_else(|| DocsError::missing_part(&opf_path))?;
+        let opf = parse_xml(&opf, &opf_path)?;
+        let root = opf.root_element();
+        let opf_dir = opf_path.rsplit_once('/').map_or("", |(dir, _)| dir);
+
+        let mut manifest = HashMap::new();
+        for item in child(&root, "manifest")
+            .into_iter()
+            .flat_map(|m| m.children().filter(|n| is(n, "item")))
+        {
+            let (Some(id), Some(href)) = (attr(&item, "id"), attr(&item, "href")) else {
+                continue;
+            };
+            manifest.insert(
+                id.to_string(),
+                ManifestItem {
+                    href: resolve(opf_dir, &percent_decode(strip_fragment(href))),
+                    media_type: attr(&item, "media-type").unwrap_or_default().to_string(),
+                    properties: attr(&item, "properties").unwrap_or_default().to_string(),
+                },
+            );
+        }
+
+        let spine_node = child(&root, "spine");
+        let spine = spine_node
+            .iter()
+            .flat_map(|s| s.children().filter(|n| is(n, "itemref")))
+            .filter_map(|n| {
+                let id = attr(&n, "idref")?;
+                manifest
+                    .contains_key(id)
+                    .then(|| (id.to_string(), attr(&n, "linear") != Some("no")))
+            })
+            .collect::<Vec<_>>();
+        if spine.is_empty() {
+            return Err(DocsError::corrupt("书脊为空").in_part(&opf_path));
+        }
+        let ncx = spine_node
+            .and_then(|s| attr(&s, "toc"))
+            .map(str::to_string)
+            .or_else(|| {
+                manifest
+                    .iter()
+                    .find(|(_, item)| item.media_type == NCX_MEDIA_TYPE)
+                    .map(|(id, _)| id.clone())
+            });
+
+        let mut metadata = BookMetadata {
+            version: attr(&root, "version").map(str::to_string),
+            ..Default::default()
+        };
+        let mut cover_id = None;
+        for node in child(&root, "metadata")
+            .into_iter()
+            .flat_map(|m| m.descendants().filter(Node::is_element))
+        {
+            let text = node_text(&node);
+            match node.tag_name().name() {
+                "title" if metadata.title.is_none() => metadata.title = text,
+                "creator" => metadata.authors.extend(text),
+                "language" if metadata.language.is_none() => metadata.language = text,
+                "publisher" => metadata.publisher = text,
+                "description" => metadata.description = text,
+                "identifier" if metadata.identifier.is_none() => metadata.identifier = text,
+                "date" if metadata.date.is_none() => metadata.date = text,
+                "subject" => metadata.subjects.extend(text),
+                "meta" if attr(&node, "name") == Some("cover") => {
+                    cover_id = attr(&node, "content").map(str::to_string)
+                },
+                _ => {},
+            }
+        }
+
+        log::info!("打开电子书: {}, 共 {} 章", path, spine.len());
+        Ok(Epub {
+            package,
+            metadata,
+            manifest,
+            spine,
+            ncx,
+            cover_id,
+        })
+    }
+
+    /// 读取目录与封面，返回电子书概要
+    pub fn book(&mut self) -> Result<Book, DocsError> {
+        let toc = match self.nav_toc() {
+            Ok(Some(toc)) => toc,
+            result => {
+                if let Err(e) = result {
+                    log::error!("解析导航文档失败: {}", e);
+                }
+                self.ncx_toc().unwrap_or_else(|e| {
+                    log::error!("解析 NCX 目录失败: {}", e);
+                    Vec::new()
+                })
+            },
+        };
+
+        let mut chapters: Vec<Chapter> = self
+            .spine
+            .iter()
+            .map(|(id, linear)| Chapter {
+                href: self.manifest[id].href.clone(),
+                title: None,
+                linear: *linear,
+            })
+            .collect();
+        fn titles(entries: &[TocEntry], chapters: &mut [Chapter]) {
+            for entry in entries {
+                if let Some(chapter) = entry.chapter.and_then(|i| chapters.get_mut(i)) {
+                    chapter.title.get_or_insert_with(|| entry.title.clone());
+                }
+                titles(&entry.children, chapters);
+            }
+        }
+        titles(&toc, &mut chapters);
+
+        let cover = self.cover();
+        Ok(Book {
+            metadata: self.metadata.clone(),
+            cover,
+            chapters,
+            toc,
+        })
+    }
+
+    /// 读取第 `index` 章（从 0 开始）并清理
+    pub fn chapter(&mut self, index: usize) -> Result<ChapterContent, DocsError> {
+        let (id, _) = self
+            .spine
+            .get(index)
+            .ok_or_else(|| DocsError::corrupt(format!("章节 {} 不存在", index + 1)))?;
+        let href = self.manifest[id].href.clone();
+        let text = self
+            .package
+            .read_string(&href)?
+            .ok_or_else(|| DocsError::missing_part(&href))?;
+        let text = html_entities(&text);
+        let doc = parse_xml(&text, &href)?;
+        let root = doc.root_element();
+        let dir = href.rsplit_once('/').map_or("", |(dir, _)| dir).to_string();
+
+        let mut styles = Vec::new();
+        if let Some(head) = child(&root, "head") {
+            for node in head.children().filter(Node::is_element) {
+                let css = match node.tag_name().name() {
+                    "style" => Some((node.text().unwrap_or_default().to_string(), dir.clone())),
+                    "link" if attr(&node, "rel").is_some_and(|r| r.contains("stylesheet")) => {
+                        let Some(target) = attr(&node, "href").and_then(|h| local_path(&dir, h))
+                        else {
+                            continue;
+                        };
+                        let base = target.rsplit_once('/').map_or("", |(d, _)| d).to_string();
+                        self.package.read_string(&target)?.map(|css| (css, base))
+                    },
+                    _ => None,
+                };
+                if let Some((css, base)) = css {
+                    styles.push(self.sanitize_css(&css, &base));
+                }
+            }
+        }
+
+        let spine_index: HashMap<&str, usize> = self
+            .spine
+            .iter()
+            .enumerate()
+            .map(|(i, (id, _))| (self.manifest[id].href.as_str(), i))
+            .collect();
+        let mut sanitizer = Sanitizer {
+            package: &mut self.package,
+            dir: &dir,
+            href: &href,
+            spine: &spine_index,
+            html: String::new(),
+        };
+        match child(&root, "body") {
+            Some(body) => sanitizer.children(&body),
+            None => sanitizer.children(&root),
+        }
+        let html = sanitizer.html;
+        Ok(ChapterContent { index, html, styles })
+    }
+
+    /// EPUB 3 导航文档中的目录，没有导航文档时返回 `None`
+    fn nav_toc(&mut self) -> Result<Option<Vec<TocEntry>>, DocsError> {
+        let Some(href) = self
+            .manifest
+            .values()
+            .find(|item| item.properties.split_whitespace().any(|p| p == "nav"))
+            .map(|item| item.href.clone())
+        else {
+            return Ok(None);
+        };
+        let Some(text) = self.package.read_string(&href)? else {
+            return Ok(None);
+        };
+        let text = html_entities(&text);
+        let doc = parse_xml(&text, &href)?;
+        let navs: Vec<Node> = doc.descendants().filter(|n| is(n, "nav")).collect();
+        let nav = navs
+            .iter()
+            .find(|n| attr(n, "type").is_some_and(|t| t.split_whitespace().any(|t| t == "toc")))
+            .or(navs.first());
+        let Some(list) = nav.and_then(|n| n.descendants().find(|n| is(n, "ol") || is(n, "ul")))
+        else {
+            return Ok(None);
+        };
+        let dir = href.rsplit_once('/').map_or("", |(dir, _)| dir);
+        let toc = self.nav_list(&list, dir, 0);
+        Ok((!toc.is_empty()).then_some(toc))
+    }
+
+    fn nav_list(&self, list: &Node, dir: &str, depth: usize) -> Vec<TocEntry> {
+        if depth >= MAX_TOC_DEPTH {
+            return Vec::new();
+        }
+        let mut entries = Vec::new();
+        for item in list.children().filter(|n| is(n, "li")) {
+            let label = item.children().find(|n| is(n, "a") || is(n, "span"));
+            let children = item
+                .children()
+                .find(|n| is(n, "ol") || is(n, "ul"))
+                .map(|l| self.nav_list(&l, dir, depth + 1))
+                .unwrap_or_default();
+            let title = label.map(|n| collapse_text(&n)).unwrap_or_default();
+            let target = label.and_then(|n| attr(&n, "href"));
+            if title.is_empty() && children.is_empty() {
+                continue;
+            }
+            let (chapter, anchor) = self.locate(dir, target);
+            entries.push(TocEntry { title, chapter, anchor, children });
+        }
+        entries
+    }
+
+    /// NCX 中的目录
+    fn ncx_toc(&mut self) -> Result<Vec<TocEntry>, DocsError> {
+        let Some(href) = self
+            .ncx
+            .as_ref()
+            .and_then(|id| self.manifest.get(id))
+            .map(|item| item.href.clone())
+        else {
+            return Ok(Vec::new());
+        };
+        let Some(text) = self.package.read_string(&href)? else {
+            return Ok(Vec::new());
+        };
+        let doc = parse_xml(&text, &href)?;
+        let dir = href.rsplit_once('/').map_or("", |(dir, _)| dir);
+        let Some(map) = doc.descendants().find(|n| is(n, "navMap")) else {
+            return Ok(Vec::new());
+        };
+        Ok(self.nav_points(&map, dir, 0))
+    }
+
+    fn nav_points(&self, parent: &Node, dir: &str, depth: usize) -> Vec<TocEntry> {
+        if depth >= MAX_TOC_DEPTH {
+            return Vec::new();
+        }
+        parent
+            .children()
+            .filter(|n| is(n, "navPoint"))
+            .map(|point| {
+                let title = child(&point, "navLabel")
+                    .map(|label| collapse_text(&label))
+                    .unwrap_or_default();
+                let target = child(&point, "content").and_then(|c| attr(&c, "src"));
+                let (chapter, anchor) = self.locate(dir, target);
+                let children = self.nav_points(&point, dir, depth + 1);
+                TocEntry { title, chapter, anchor, children }
+            })
+            .collect()
+    }
+
+    /// 将相对于 `dir` 的链接解析为 (章节序号, 锚点)
+    fn locate(&self, dir: &str, target: Option<&str>) -> (Option<usize>, Option<String>) {
+        let Some(target) = target else {
+            return (None, None);
+        };
+        let (path, anchor) = split_fragment(target);
+        let chapter = local_path(dir, path).and_then(|path| {
+            self.spine
+                .iter()
+                .position(|(id, _)| self.manifest[id].href == path)
+        });
+        (chapter, anchor)
+    }
+
+    /// 封面：EPUB 3 的 `cover-image` 属性，其次是 EPUB 2 的 `<meta name="cover">`，
+    /// 最后是 id 或路径含 cover 的图片
+    fn cover(&mut self) -> Option<String> {
+        let is_image = |item: &ManifestItem| item.media_type.starts_with("image/");
+        let href = self
+            .manifest
+            .values()
+            .find(|item| {
+                item.properties
+                    .split_whitespace()
+                    .any(|p| p == "cover-image")
+            })
+            .or_else(|| self.cover_id.as_ref().and_then(|id| self.manifest.get(id)))
+            .filter(|item| is_image(item))
+            .or_else(|| {
+                self.manifest.iter().find_map(|(id, item)| {
+                    let named = id.to_lowercase().contains("cover")
+                        || item.href.to_lowercase().contains("cover");
+                    (named && is_image(item)).then_some(item)
+                })
+            })?
+            .href
+            .clone();
+        self.package.image_data_uri(&href)
+    }
+
+    /// 移除 `@import`，将 `url()` 引用的图片和字体转换为 data URI，其余引用移除
+    fn sanitize_css(&mut self, css: &str, dir: &str) -> String {
+        let mut result = String::with_capacity(css.len());
+        let mut rest = css;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            let import = lower.find("@import");
+            let url = lower.find("url(");
+            match (import, url) {
+                (Some(i), u) if u.map_or(true, |u| i < u) => {
+                    result.push_str(&rest[..i]);
+                    let end = rest[i..].find(';').map_or(rest.len(), |e| i + e + 1);
+                    rest = &rest[end..];
+                },
+                (_, Some(u)) => {
+                    result.push_str(&rest[..u]);
+                    let Some(close) = rest[u..].find(')') else {
+                        break;
+                    };
+                    let target = rest[u + 4..u + close].trim().trim_matches(['"', '\'']);
+                    let uri = if target.starts_with("data:") {
+                        Some(target.to_string())
+                    } else {
+                        local_path(dir, split_fragment(target).0)
+                            .and_then(|path| resource_data_uri(&mut self.package, &path))
+                    };
+                    result.push_str(&format!("url(\"{}\")", uri.unwrap_or_default()));
+                    rest = &rest[u + close + 1..];
+                },
+                _ => {
+                    result.push_str(rest);
+                    break;
+                },
+            }
+        }
+        result
+    }
+}
+
+/// 图片或字体资源的 data URI
+fn resource_data_uri(package: &mut Package, path: &str) -> Option<String> {
+    let ext = path.rsplit_once('.')?.1.to_ascii_lowercase();
+    let mime = match ext.as_str() {
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        _ => return package.image_data_uri(path),
+    };
+    let data = package.read(path).ok()??;
+    Some(format!("data:{};base64,{}", mime, STANDARD.encode(data)))
+}
+
+/// 内容文档加密（DRM）时无法阅读；只混淆字体的不受影响
+fn check_encryption(package: &mut Package) -> Result<(), DocsError> {
+    let Some(xml) = package.read_string(ENCRYPTION_PART)? else {
+        return Ok(());
+    };
+    let doc = parse_xml(&xml, ENCRYPTION_PART)?;
+    let encrypted = doc
+        .descendants()
+        .filter(|n| is(n, "EncryptedData"))
+        .any(|data| {
+            let algorithm = data
+                .descendants()
+                .find(|n| is(n, "EncryptionMethod"))
+                .and_then(|n| attr(&n, "Algorithm"))
+                .unwrap_or_default();
+            !FONT_OBFUSCATION.contains(&algorithm)
+        });
+    if encrypted {
+        return Err(DocsError::Encrypted);
+    }
+    Ok(())
+}
+
+/// 将章节 XHTML 转换为只含白名单元素与属性的片段
+struct Sanitizer<'a> {
+    package: &'a mut Package,
+    /// 章节所在目录
+    dir: &'a str,
+    /// 章节的包内路径
+    href: &'a str,
+    /// 包内路径 -> 章节序号
+    spine: &'a HashMap<&'a str, usize>,
+    html: String,
+}
+
+impl Sanitizer<'_> {
+    fn children(&mut self, node: &Node) {
+        for child in node.children() {
+            if child.is_text() {
+                escape_into(&mut self.html, child.text().unwrap_or_default(), false);
+            } else if child.is_element() {
+                self.element(&child);
+            }
+        }
+    }
+
+    fn element(&mut self, node: &Node) {
+        let name = node.tag_name().name().to_ascii_lowercase();
+        if name == "svg" {
+            // 封面页常用 SVG 包裹图片，只保留其中的图片
+            for image in node.descendants().filter(|n| is(n, "image")) {
+                if let Some(src) = attr(&image, "href").and_then(|h| self.image(h)) {
+                    self.html.push_str("<img src=\"");
+                    escape_into(&mut self.html, &src, true);
+                    self.html.push_str("\"/>");
+                }
+            }
+            return;
+        }
+        if DROPPED_TAGS.contains(&name.as_str()) {
+            return;
+        }
+        if !ALLOWED_TAGS.contains(&name.as_str()) {
+            self.children(node);
+            return;
+        }
+
+        self.html.push('<');
+        self.html.push_str(&name);
+        for attribute in node.attributes() {
+            let key = attribute.name().to_ascii_lowercase();
+            let value = attribute.value();
+            match key.as_str() {
+                "href" if name == "a" => self.link(value),
+                "src" if name == "img" => {
+                    if let Some(src) = self.image(value) {
+                        self.push_attribute("src", &src);
+                    }
+                },
+                "style" => {
+                    let lower = value.to_ascii_lowercase();
+                    if !["url(", "expression", "javascript:"]
+                        .iter()
+                        .any(|s| lower.contains(s))
+                    {
+                        self.push_attribute("style", value);
+                    }
+                },
+                // epub:type 标注语义，如脚注
+                "type" if attribute.namespace().is_some_and(|ns| ns.contains("ops")) => {
+                    self.push_attribute("data-epub-type", value);
+                },
+                key if ALLOWED_ATTRIBUTES.contains(&key) => self.push_attribute(key, value),
+                _ => {},
+            }
+        }
+        if VOID_TAGS.contains(&name.as_str()) {
+            self.html.push_str("/>");
+            return;
+        }
+        self.html.push('>');
+        self.children(node);
+        self.html.push_str("</");
+        self.html.push_str(&name);
+        self.html.push('>');
+    }
+
+    fn push_attribute(&mut self, key: &str, value: &str) {
+        self.html.push(' ');
+        self.html.push_str(key);
+        self.html.push_str("=\"");
+        escape_into(&mut self.html, value, true);
+        self.html.push('"');
+    }
+
+    /// 外部链接原样保留，书内链接改为章节序号与锚点
+    fn link(&mut self, target: &str) {
+        let lower = target.trim().to_ascii_lowercase();
+        if ["http://", "https://", "mailto:"]
+            .iter()
+            .any(|s| lower.starts_with(s))
+        {
+            self.push_attribute("href", target.trim());
+            return;
+        }
+        let (path, anchor) = split_fragment(target);
+        let chapter = if path.is_empty() {
+            self.spine.get(self.href).copied()
+        } else {
+            local_path(self.dir, path).and_then(|p| self.spine.get(p.as_str()).copied())
+        };
+        self.push_attribute("href", "#");
+        if let Some(chapter) = chapter {
+            self.push_attribute("data-chapter", &chapter.to_string());
+        }
+        if let Some(anchor) = anchor {
+            self.push_attribute("data-anchor", &anchor);
+        }
+    }
+
+    fn image(&mut self, src: &str) -> Option<String> {
+        if src.starts_with("data:image/") {
+            return Some(src.to_string());
+        }
+        let path = local_path(self.dir, split_fragment(src).0)?;
+        resource_data_uri(self.package, &path)
+    }
+}
+
+/// 将相对链接解析为包内路径，带协议的外部地址返回 `None`
+fn local_path(dir: &str, target: &str) -> Option<String> {
+    let target = target.trim();
+    if target.is_empty() || target.contains(':') {
+        return None;
+    }
+    Some(resolve(dir, &percent_decode(target)))
+}
+
+fn strip_fragment(href: &str) -> &str {
+    split_fragment(href).0
+}
+
+fn split_fragment(href: &str) -> (&str, Option<String>) {
+    match href.split_once('#') {
+        Some((path, anchor)) => (path, (!anchor.is_empty()).then(|| percent_decode(anchor))),
+        None => (href, None),
+    }
+}
+
+/// 解码 URL 中的 `%XX`
+fn percent_decode(text: &str) -> String {
+    if !text.contains('%') {
+        return text.to_string();
+    }
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            },
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            },
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// XHTML 常用但 XML 未定义的实体替换为字符引用，其余未知实体转义，之后再按 XML 解析
+fn html_entities(text: &str) -> Cow<'_, str> {
+    const ENTITIES: &[(&str, u32)] = &[
+        ("nbsp", 0xA0),
+        ("copy", 0xA9),
+        ("reg", 0xAE),
+        ("trade", 0x2122),
+        ("mdash", 0x2014),
+        ("ndash", 0x2013),
+        ("hellip", 0x2026),
+        ("lsquo", 0x2018),
+        ("rsquo", 0x2019),
+        ("ldquo", 0x201C),
+        ("rdquo", 0x201D),
+        ("laquo", 0xAB),
+        ("raquo", 0xBB),
+        ("middot", 0xB7),
+        ("bull", 0x2022),
+        ("deg", 0xB0),
+        ("times", 0xD7),
+        ("shy", 0xAD),
+        ("ensp", 0x2002),
+        ("emsp", 0x2003),
+        ("thinsp", 0x2009),
+        ("zwnj", 0x200C),
+        ("zwj", 0x200D),
+        ("sect", 0xA7),
+        ("para", 0xB6),
+        ("eacute", 0xE9),
+    ];
+    const XML_ENTITIES: [&str; 5] = ["amp", "lt", "gt", "quot", "apos"];
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let name = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 12)
+            .map(|end| &rest[1..end + 1]);
+        let known = name.and_then(|n| ENTITIES.iter().find(|(e, _)| *e == n));
+        match (name, known) {
+            (_, Some((name, code))) => {
+                result.push_str(&format!("&#{};", code));
+                rest = &rest[name.len() + 2..];
+            },
+            (Some(name), None) if name.starts_with('#') || XML_ENTITIES.contains(&name) => {
+                result.push('&');
+                rest = &rest[1..];
+            },
+            // 未知实体与裸露的 & 按字面显示
+            _ => {
+                result.push_str("&amp;");
+                rest = &rest[1..];
+            },
+        }
+    }
+    result.push_str(rest);
+    Cow::Owned(result)
+}
+
+fn escape_into(html: &mut String, text: &str, attribute: bool) {
+    for c in text.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' if attribute => html.push_str("&quot;"),
+            c => html.push(c),
+        }
+    }
+}
+
+fn node_text(node: &Node) -> Option<String> {
+    let text = collapse_text(node);
+    (!text.is_empty()).then_some(text)
+}
+
+/// 所有后代文本，空白合并为一个空格
+fn collapse_text(node: &Node) -> String {
+    let text: String = node
+        .descendants()
+        .filter(Node::is_text)
+        .filter_map(|n| n.text())
+        .collect();
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 解析 XML 部件，内容文档常带有 DOCTYPE 声明
+fn parse_xml<'a>(text: &'a str, part: &str) -> Result<XmlDocument<'a>, DocsError> {
+    let options = ParsingOptions { allow_dtd: true, ..Default::default() };
+    XmlDocument::parse_with_options(text, options).map_err(|e| DocsError::from(e).in_part(part))
+}
+
+/// 解析电子书概要
+pub fn parse(path: &str) -> Result<Book, DocsError> {
+    Epub::open(path)?.book()
+}
+
+impl From<&BookMetadata> for DocumentMetadata {
+    fn from(book: &BookMetadata) -> Self {
+        DocumentMetadata {
+            title: book.title.clone(),
+            description: book.description.clone(),
+            keywords: book.subjects.clone(),
+            author: book.authors.first().cloned(),
+            created: book.date.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs::File, io::Write};
+
+    const CONTAINER: &str = r#"<?xml version="1.0"?>
+<container xmlns="urn:oasis:names:tc:opendocument:xmlns:container" version="1.0">
+  <rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles>
+</container>"#;
+
+    const OPF: &str = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Test</dc:title></metadata>
+  <manifest>
+    <item id="ch1" href="text/ch1.xhtml" media-type="application/xhtml+xml"/>
+    <item id="ch2" href="text/ch2.xhtml" media-type="application/xhtml+xml"/>
+    <item id="img" href="images/dot.png" media-type="image/png"/>
+  </manifest>
+  <spine><itemref idref="ch1"/><itemref idref="ch2"/></spine>
+</package>"#;
+
+    /// 写入含两章的 EPUB，第一章为 `body`
+    fn epub(name: &str, head: &str, body: &str) -> String {
+        let path = std::env::temp_dir().join(format!("{}-{}.epub", name, std::process::id()));
+        let chapter = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops">
+<head>{}</head><body>{}</body></html>"#,
+            head, body
+        );
+        let parts = [
+            ("mimetype", "application/epub+zip"),
+            ("META-INF/container.xml", CONTAINER),
+            ("OEBPS/content.opf", OPF),
+            ("OEBPS/text/ch1.xhtml", chapter.as_str()),
+            (
+                "OEBPS/text/ch2.xhtml",
+                "<html><body><p id=\"n1\">two</p></body></html>",
+            ),
+            ("OEBPS/images/dot.png", "\u{89}PNG"),
+        ];
+        let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+        for (name, content) in parts {
+            writer
+                .start_file(name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn chapter(name: &str, head: &str, body: &str) -> ChapterContent {
+        let path = epub(name, head, body);
+        let content = Epub::open(&path).unwrap().chapter(0).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        content
+    }
+
+    #[test]
+    fn scripts_and_event_handlers_are_removed() {
+        let head = "<script>alert(0)</script><title>t</title>";
+        let body = r#"<p onclick="alert(1)" OnMouseOver="x" class="c">hi<script>alert(2)</script></p>
+<iframe src="https://example.com/"></iframe><custom>kept</custom>
+<img src="../images/dot.png" onerror="alert(3)"/>
+<svg><image href="../images/dot.png"/><script>alert(4)</script></svg>"#;
+        let html = chapter("scripts", head, body).html;
+        assert!(!html.contains("alert"), "{}", html);
+        assert!(!html.to_ascii_lowercase().contains(" on"), "{}", html);
+        assert!(html.contains(r#"<p class="c">hi</p>"#));
+        assert!(html.contains("kept") && !html.contains("custom") && !html.contains("iframe"));
+        assert_eq!(
+            html.matches(r#"<img src="data:image/png;base64,"#).count(),
+            2
+        );
+    }
+
+    #[test]
+    fn javascript_urls_are_removed() {
+        let body = r##"<a href="javascript:alert(1)">a</a><a href=" JaVaScRiPt:alert(1)">b</a>
+<img src="data:text/html,x"/><p style="background:url(javascript:alert(1))">c</p>
+<p style="width: expression(alert(1))">d</p><p style="color:red">e</p>
+<a href="https://example.com/">f</a><a href="ch2.xhtml#n1">g</a>"##;
+        let html = chapter("javascript", "", body).html;
+        assert!(
+            !html.to_ascii_lowercase().contains("javascript"),
+            "{}",
+            html
+        );
+        assert!(
+            !html.contains("expression") && !html.contains("text/html"),
+            "{}",
+            html
+        );
+        assert!(html.contains(r##"<a href="#">a</a><a href="#">b</a>"##));
+        assert!(html.contains("<img/>"));
+        assert!(html.contains(r#"<p>c</p>"#) && html.contains(r#"<p style="color:red">e</p>"#));
+        assert!(html.contains(r#"<a href="https://example.com/">f</a>"#));
+        assert!(html.contains(r##"<a href="#" data-chapter="1" data-anchor="n1">g</a>"##));
+    }
+
+    #[test]
+    fn stylesheets_are_inlined_without_remote_references() {
+        let head = r#"<style>@import "x.css"; p { background: url(../images/dot.png) } a { background: url(https://example.com/x.png) }</style>"#;
+        let styles = chapter("styles", head, "<p>x</p>").styles;
+        assert_eq!(styles.len(), 1);
+        assert!(!styles[0].contains("@import") && !styles[0].contains("example.com"));
+        assert!(styles[0].contains(r#"url("data:image/png;base64,"#));
+        assert!(styles[0].contains(r#"url("")"#));
+    }
+}
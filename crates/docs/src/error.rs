@@ -0,0 +1,299 @@
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::{fmt, io};
+
+#[derive(Debug)]
+pub enum DocsError {
+    Io(io::Error),
+    /// 文档已加密或受密码保护
+    Encrypted,
+    /// 文档包或其中的部件损坏
+    Corrupt {
+        reason: String,
+        part: Option<String>,
+    },
+    /// 不支持的格式或版本
+    Unsupported(String),
+    SheetNotFound(String),
+    /// 超出大小等限制
+    LimitExceeded {
+        limit: String,
+        part: Option<String>,
+    },
+}
+
+impl DocsError {
+    /// 稳定的错误码，供前端等调用方区分错误类型，不要修改已有的取值
+    pub fn code(&self) -> &'static str {
+        match self {
+            DocsError::Io(_) => "io",
+            DocsError::Encrypted => "encrypted",
+            DocsError::Corrupt { .. } => "corrupt",
+            DocsError::Unsupported(_) => "unsupported",
+            DocsError::SheetNotFound(_) => "sheet_not_found",
+            DocsError::LimitExceeded { .. } => "limit_exceeded",
+        }
+    }
+
+    /// 出错的部件（包内路径）
+    pub fn part(&self) -> Option<&str> {
+        match self {
+            DocsError::Corrupt { part, .. } | DocsError::LimitExceeded { part, .. } => {
+                part.as_deref()
+            },
+            _ => None,
+        }
+    }
+
+    /// 数据损坏
+    pub fn corrupt(reason: impl Into<String>) -> Self {
+        DocsError::Corrupt { reason: reason.into(), part: None }
+    }
+
+    /// 缺少必需的部件
+    pub fn missing_part(part: &str) -> Self {
+        DocsError::Corrupt {
+            reason: "缺少必需的部件".to_string(),
+            part: Some(part.to_string()),
+        }
+    }
+
+    /// 补充出错的部件，已有的不会被覆盖
+    pub fn in_part(self, name: &str) -> Self {
+        let name = Some(name.to_string());
+        match self {
+            DocsError::Corrupt { reason, part } => {
+                DocsError::Corrupt { reason, part: part.or(name) }
+            },
+            DocsError::LimitExceeded { limit, part } => {
+                DocsError::LimitExceeded { limit, part: part.or(name) }
+            },
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for DocsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DocsError::Io(err) => write!(f, "读取文件失败: {}", err),
+            DocsError::Encrypted => write!(f, "文档已加密，需要密码"),
+            DocsError::Corrupt { reason, part: Some(part) } => {
+                write!(f, "文档已损坏: {} ({})", reason, part)
+            },
+            DocsError::Corrupt { reason, part: None } => write!(f, "文档已损坏: {}", reason),
+            DocsError::Unsupported(format) => write!(f, "不支持的格式: {}", format),
+            DocsError::SheetNotFound(name) => write!(f, "工作表不存在: {}", name),
+            DocsError::LimitExceeded { limit, .. } => write!(f, "超出限制: {}", limit),
+        }
+    }
+}
+
+impl std::error::Error for DocsError {}
+
+/// 序列化为 `{ code, message, part? }`
+impl Serialize for DocsError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("DocsError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        match self.part() {
+            Some(part) => state.serialize_field("part", part)?,
+            None => state.skip_field("part")?,
+        }
+        state.end()
+    }
+}
+
+impl From<io::Error> for DocsError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof => {
+                DocsError::corrupt(err.to_string())
+            },
+            _ => DocsError::Io(err),
+        }
+    }
+}
+
+impl From<zip::result::ZipError> for DocsError {
+    fn from(err: zip::result::ZipError) -> Self {
+        use zip::result::ZipError;
+
+        match err {
+            ZipError::Io(err) => err.into(),
+            ZipError::UnsupportedArchive(ZipError::PASSWORD_REQUIRED)
+            | ZipError::InvalidPassword => DocsError::Encrypted,
+            ZipError::UnsupportedArchive(reason) => DocsError::Unsupported(reason.to_string()),
+            err => DocsError::corrupt(err.to_string()),
+        }
+    }
+}
+
+impl From<lopdf::Error> for DocsError {
+    fn from(err: lopdf::Error) -> Self {
+        match err {
+            lopdf::Error::IO(err) => err.into(),
+            lopdf::Error::Decryption(_) => DocsError::Encrypted,
+            err => DocsError::corrupt(err.to_string()),
+        }
+    }
+}
+
+impl From<roxmltree::Error> for DocsError {
+    fn from(err: roxmltree::Error) -> Self {
+        DocsError::corrupt(err.to_string())
+    }
+}
+
+impl From<quick_xml::Error> for DocsError {
+    fn from(err: quick_xml::Error) -> Self {
+        DocsError::corrupt(err.to_string())
+    }
+}
+
+impl From<csv::Error> for DocsError {
+    fn from(err: csv::Error) -> Self {
+        let reason = err.to_string();
+        match err.into_kind() {
+            csv::ErrorKind::Io(err) => err.into(),
+            _ => DocsError::corrupt(reason),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for DocsError {
+    fn from(err: rusqlite::Error) -> Self {
+        use rusqlite::ErrorCode;
+
+        let rusqlite::Error::SqliteFailure(failure, message) = &err else {
+            // 列类型与值的转换失败等，说明数据与声明的结构不符
+            return DocsError::corrupt(err.to_string());
+        };
+        match failure.code {
+            ErrorCode::NotADatabase | ErrorCode::DatabaseCorrupt => {
+                DocsError::corrupt(err.to_string())
+            },
+            ErrorCode::CannotOpen
+            | ErrorCode::PermissionDenied
+            | ErrorCode::DatabaseBusy
+            | ErrorCode::DatabaseLocked
+            | ErrorCode::SystemIoFailure => DocsError::Io(io::Error::other(err.to_string())),
+            ErrorCode::TooBig => DocsError::LimitExceeded { limit: err.to_string(), part: None },
+            // 未编译的虚拟表模块（如 fts5、rtree）、自定义排序规则或函数
+            ErrorCode::Unknown
+                if message.as_deref().is_some_and(|m| {
+                    ["no such module", "no such collation", "no such function"]
+                        .iter()
+                        .any(|prefix| m.starts_with(prefix))
+                }) =>
+            {
+                DocsError::Unsupported(err.to_string())
+            },
+            _ => DocsError::corrupt(err.to_string()),
+        }
+    }
+}
+
+impl From<parquet::errors::ParquetError> for DocsError {
+    fn from(err: parquet::errors::ParquetError) -> Self {
+        use parquet::errors::ParquetError;
+
+        match err {
+            ParquetError::NYI(reason) => DocsError::Unsupported(reason),
+            // 未编译的压缩算法，如 brotli
+            ParquetError::General(reason) if reason.starts_with("Disabled feature") => {
+                DocsError::Unsupported(reason)
+            },
+            ParquetError::External(err) => match err.downcast::<io::Error>() {
+                Ok(err) => (*err).into(),
+                Err(err) => DocsError::corrupt(err.to_string()),
+            },
+            err => DocsError::corrupt(err.to_string()),
+        }
+    }
+}
+
+impl From<arrow_schema::ArrowError> for DocsError {
+    fn from(err: arrow_schema::ArrowError) -> Self {
+        use arrow_schema::ArrowError;
+
+        match err {
+            ArrowError::NotYetImplemented(reason) => DocsError::Unsupported(reason),
+            ArrowError::IoError(_, err) => err.into(),
+            err => DocsError::corrupt(err.to_string()),
+        }
+    }
+}
+
+impl From<calamine::Error> for DocsError {
+    fn from(err: calamine::Error) -> Self {
+        use calamine::{Error, OdsError, XlsError, XlsbError};
+
+        match err {
+            Error::Io(err) => err.into(),
+            Error::Xlsx(err) => err.into(),
+            Error::Xls(XlsError::Io(err))
+            | Error::Xlsb(XlsbError::Io(err))
+            | Error::Ods(OdsError::Io(err)) => err.into(),
+            Error::Xlsb(XlsbError::Zip(err)) | Error::Ods(OdsError::Zip(err)) => err.into(),
+            Error::Xls(XlsError::Password)
+            | Error::Xlsb(XlsbError::Password)
+            | Error::Ods(OdsError::Password) => DocsError::Encrypted,
+            Error::Xls(XlsError::WorksheetNotFound(name))
+            | Error::Xlsb(XlsbError::WorksheetNotFound(name))
+            | Error::Ods(OdsError::WorksheetNotFound(name)) => DocsError::SheetNotFound(name),
+            Error::Msg(msg) if msg.contains("detect file format") => {
+                DocsError::Unsupported(msg.to_string())
+            },
+            err => DocsError::corrupt(err.to_string()),
+        }
+    }
+}
+
+impl From<calamine::XlsxError> for DocsError {
+    fn from(err: calamine::XlsxError) -> Self {
+        use calamine::XlsxError;
+
+        match err {
+            XlsxError::Io(err) => err.into(),
+            XlsxError::Zip(err) => err.into(),
+            XlsxError::Password => DocsError::Encrypted,
+            XlsxError::WorksheetNotFound(name) => DocsError::SheetNotFound(name),
+            err => DocsError::corrupt(err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::{Connection, OpenFlags};
+
+    fn sqlite_code(result: rusqlite::Result<impl Sized>) -> &'static str {
+        DocsError::from(result.err().expect("应当失败")).code()
+    }
+
+    #[test]
+    fn sqlite_errors() {
+        let path = std::env::temp_dir().join(format!("not-a-db-{}.db", std::process::id()));
+        std::fs::write(&path, [b'x'; 4096]).unwrap();
+        let garbage = Connection::open_with_flags(&path, OpenFlags::SQLITE_OPEN_READ_ONLY).unwrap();
+        let query = garbage.query_row("SELECT count(*) FROM sqlite_master", [], |r| {
+            r.get::<_, i64>(0)
+        });
+        assert_eq!(sqlite_code(query), "corrupt");
+        std::fs::remove_file(&path).unwrap();
+
+        let missing = path.with_extension("missing");
+        let open = Connection::open_with_flags(&missing, OpenFlags::SQLITE_OPEN_READ_ONLY);
+        assert_eq!(sqlite_code(open), "io");
+
+        let memory = Connection::open_in_memory().unwrap();
+        let module = memory.execute("CREATE VIRTUAL TABLE t USING no_such_module", []);
+        assert_eq!(sqlite_code(module), "unsupported");
+        let syntax = memory.execute("SELEC 1", []);
+        assert_eq!(sqlite_code(syntax), "corrupt");
+        let conversion = memory.query_row("SELECT 'x'", [], |r| r.get::<_, i64>(0));
+        assert_eq!(sqlite_code(conversion), "corrupt");
+    }
+}
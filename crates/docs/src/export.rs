@@ -0,0 +1,331 @@
+//! 导出工作表：CSV（自选分隔符）、以表头为键的 JSON 数组、Markdown 表格
+
+use crate::{
+    error::DocsError,
+    sheet::{Cell, CellValue, DSheet},
+};
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+use std::{collections::HashSet, ops::Range};
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv {
+        #[serde(default = "default_delimiter")]
+        delimiter: char,
+    },
+    /// 对象数组，键来自表头
+    Json,
+    Markdown,
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+/// 导出区域，行列为 `rows` 中的下标（相对于已使用区域），首尾都包含
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct SheetRange {
+    pub first_row: u32,
+    pub first_col: u32,
+    pub last_row: u32,
+    pub last_col: u32,
+}
+
+impl SheetRange {
+    /// 起始行与行数；首尾颠倒的区域无效
+    pub fn rows(&self) -> Result<(u32, u32), DocsError> {
+        if self.first_row > self.last_row || self.first_col > self.last_col {
+            return Err(DocsError::Unsupported(format!(
+                "无效的导出区域: ({}, {}) - ({}, {})",
+                self.first_row, self.first_col, self.last_row, self.last_col
+            )));
+        }
+        Ok((
+            self.first_row,
+            (self.last_row - self.first_row).saturating_add(1),
+        ))
+    }
+
+    /// 列下标范围
+    pub(crate) fn cols(&self) -> Range<usize> {
+        self.first_col as usize..self.last_col as usize + 1
+    }
+}
+
+impl ExportFormat {
+    /// 导出文件的扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv { delimiter: '\t' } => "tsv",
+            ExportFormat::Csv { .. } => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+        }
+    }
+}
+
+/// 将工作表或其中的区域导出为文本
+///
+/// 分隔符文本识别出的表头总是作为首行（JSON 的键）输出；
+/// 没有表头时 JSON 与 Markdown 以区域的第一行作为表头。
+pub fn export(
+    sheet: &DSheet,
+    range: Option<SheetRange>,
+    format: ExportFormat,
+) -> Result<String, DocsError> {
+    let rows = match range {
+        Some(range) => {
+            let (first, count) = range.rows()?;
+            let end = (first as usize + count as usize).min(sheet.rows.len());
+            sheet.rows.get(first as usize..end).unwrap_or_default()
+        },
+        None => &sheet.rows[..],
+    };
+    let cols = range.map(|r| r.cols());
+    export_rows(sheet.header.as_deref(), rows, cols, format)
+}
+
+/// 导出已按行截取的单元格，`cols` 为空时导出全部列
+pub(crate) fn export_rows(
+    header: Option<&[String]>,
+    rows: &[Vec<Cell>],
+    cols: Option<Range<usize>>,
+    format: ExportFormat,
+) -> Result<String, DocsError> {
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let width = width.max(header.map_or(0, <[String]>::len));
+    let cols = match cols {
+        Some(cols) => cols.start..cols.end.min(width),
+        None => 0..width,
+    };
+    let empty = Cell::empty();
+    let table: Vec<Vec<&Cell>> = rows
+        .iter()
+        .map(|row| cols.clone().map(|c| row.get(c).unwrap_or(&empty)).collect())
+        .collect();
+    let header: Option<Vec<String>> = header.map(|header| {
+        cols.clone()
+            .map(|c| header.get(c).cloned().unwrap_or_default())
+            .collect()
+    });
+
+    match format {
+        ExportFormat::Csv { delimiter } => csv(header, &table, delimiter),
+        ExportFormat::Json => json(header, &table),
+        ExportFormat::Markdown => Ok(markdown(header, &table)),
+    }
+}
+
+/// 没有表头时取第一行
+fn split_header<'a>(
+    header: Option<Vec<String>>,
+    table: &'a [Vec<&'a Cell>],
+) -> (Vec<String>, &'a [Vec<&'a Cell>]) {
+    match header {
+        Some(header) => (header, table),
+        None => match table.split_first() {
+            Some((first, rest)) => (first.iter().map(|c| c.display.clone()).collect(), rest),
+            None => (Vec::new(), table),
+        },
+    }
+}
+
+fn csv(
+    header: Option<Vec<String>>,
+    table: &[Vec<&Cell>],
+    delimiter: char,
+) -> Result<String, DocsError> {
+    let delimiter = u8::try_from(delimiter)
+        .ok()
+        .filter(u8::is_ascii)
+        .ok_or_else(|| DocsError::Unsupported(format!("分隔符 {}", delimiter)))?;
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_writer(Vec::new());
+    if let Some(header) = header {
+        writer.write_record(&header)?;
+    }
+    for row in table {
+        writer.write_record(row.iter().map(|c| c.display.as_str()))?;
+    }
+    let data = writer
+        .into_inner()
+        .map_err(|e| DocsError::Io(e.into_error()))?;
+    Ok(String::from_utf8_lossy(&data).into_owned())
+}
+
+fn json(header: Option<Vec<String>>, table: &[Vec<&Cell>]) -> Result<String, DocsError> {
+    let (header, rows) = split_header(header, table);
+    let keys = unique_keys(header);
+    let records: Vec<Record> = rows
+        .iter()
+        .map(|cells| Record { keys: &keys, cells })
+        .collect();
+    serde_json::to_string_pretty(&records).map_err(|e| DocsError::corrupt(e.to_string()))
+}
+
+/// 空的键使用列号，重复的键加上序号
+fn unique_keys(header: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    header
+        .into_iter()
+        .enumerate()
+        .map(|(i, key)| {
+            let key = key.trim();
+            let key = if key.is_empty() {
+                format!("column{}", i + 1)
+            } else {
+                key.to_string()
+            };
+            let mut unique = key.clone();
+            let mut n = 1;
+            while !seen.insert(unique.clone()) {
+                n += 1;
+                unique = format!("{}_{}", key, n);
+            }
+            unique
+        })
+        .collect()
+}
+
+/// 按表头顺序输出的一行
+struct Record<'a> {
+    keys: &'a [String],
+    cells: &'a [&'a Cell],
+}
+
+impl Serialize for Record<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.keys.len()))?;
+        for (i, key) in self.keys.iter().enumerate() {
+            match self.cells.get(i).map(|c| (&c.value, c)) {
+                None | Some((CellValue::Empty, _)) => map.serialize_entry(key, &())?,
+                Some((CellValue::Int(v), _)) => map.serialize_entry(key, v)?,
+                Some((CellValue::Float(v), _)) if v.is_finite() => map.serialize_entry(key, v)?,
+                Some((CellValue::Bool(v), _)) => map.serialize_entry(key, v)?,
+                // 日期、时长与错误值按显示文本输出
+                Some((_, cell)) => map.serialize_entry(key, &cell.display)?,
+            }
+        }
+        map.end()
+    }
+}
+
+fn markdown(header: Option<Vec<String>>, table: &[Vec<&Cell>]) -> String {
+    let (header, rows) = split_header(header, table);
+    if header.is_empty() {
+        return String::new();
+    }
+    let line = |cells: &mut dyn Iterator<Item = &str>| {
+        let cells: Vec<String> = cells.map(escape_markdown).collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+    let mut text = line(&mut header.iter().map(String::as_str));
+    text.push_str(&line(&mut header.iter().map(|_| "---")));
+    for row in rows {
+        text.push_str(&line(&mut row.iter().map(|c| c.display.as_str())));
+    }
+    text
+}
+
+/// 转义竖线，单元格内换行改为 `<br>`
+fn escape_markdown(text: &str) -> String {
+    text.trim()
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\r', '\n'], "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(rows: &[&[&str]]) -> Vec<Vec<Cell>> {
+        rows.iter()
+            .map(|row| row.iter().map(|text| Cell::from_text(text)).collect())
+            .collect()
+    }
+
+    fn header(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn csv_quotes_and_delimiters() {
+        let data = rows(&[&["1", "a,b"], &["2", "say \"hi\""]]);
+        let header = header(&["id", "text"]);
+        let csv = export_rows(
+            Some(&header),
+            &data,
+            None,
+            ExportFormat::Csv { delimiter: ',' },
+        );
+        assert_eq!(csv.unwrap(), "id,text\n1,\"a,b\"\n2,\"say \"\"hi\"\"\"\n");
+        let tsv = export_rows(
+            None,
+            &data,
+            Some(1..2),
+            ExportFormat::Csv { delimiter: '\t' },
+        );
+        assert_eq!(tsv.unwrap(), "a,b\n\"say \"\"hi\"\"\"\n");
+        let wide = export_rows(None, &data, None, ExportFormat::Csv { delimiter: '；' });
+        assert!(matches!(wide, Err(DocsError::Unsupported(_))));
+    }
+
+    #[test]
+    fn json_keys_and_values() {
+        let data = rows(&[&["a", "a", "", "a_2"], &["1", "2.5", "true", ""], &["x"]]);
+        let json = export_rows(None, &data, None, ExportFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!([
+                { "a": 1, "a_2": 2.5, "column3": true, "a_2_2": null },
+                { "a": "x", "a_2": null, "column3": null, "a_2_2": null },
+            ])
+        );
+        // 键按表头顺序输出
+        let order: Vec<usize> = ["\"a\"", "\"a_2\"", "\"column3\"", "\"a_2_2\""]
+            .iter()
+            .map(|key| json.find(key).unwrap())
+            .collect();
+        assert!(order.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn markdown_escapes_cells() {
+        let data = rows(&[&["a|b", "line1\nline2"], &["c\\d"]]);
+        let header = header(&["name", "note"]);
+        let markdown = export_rows(Some(&header), &data, None, ExportFormat::Markdown).unwrap();
+        assert_eq!(
+            markdown,
+            "| name | note |\n| --- | --- |\n| a\\|b | line1<br>line2 |\n| c\\\\d |  |\n"
+        );
+        assert_eq!(
+            export_rows(None, &[], None, ExportFormat::Markdown).unwrap(),
+            ""
+        );
+    }
+
+    #[test]
+    fn inverted_range_is_rejected() {
+        let range = SheetRange {
+            first_row: 0,
+            first_col: 3,
+            last_row: 0,
+            last_col: 1,
+        };
+        assert!(matches!(range.rows(), Err(DocsError::Unsupported(_))));
+        let range = SheetRange {
+            first_row: 0,
+            first_col: 0,
+            last_row: u32::MAX,
+            last_col: 0,
+        };
+        assert_eq!(range.rows().unwrap(), (0, u32::MAX));
+    }
+}
@@ -0,0 +1,358 @@
+use crate::{arrow::ARROW_MAGIC, error::DocsError, sqlite::SQLITE_MAGIC};
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek},
+    path::Path,
+};
+use zip::ZipArchive;
+
+/// OLE2 复合文档的文件头
+pub const OLE2_MAGIC: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+/// 用于识别格式的文件头大小
+const HEADER_SIZE: u64 = 8 * 1024;
+
+/// 按文件内容识别出的文档格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DocsFormat {
+    Xlsx,
+    Xlsb,
+    Xls,
+    Ods,
+    Docx,
+    Pptx,
+    Odt,
+    Odp,
+    /// Word 97-2003
+    Doc,
+    /// PowerPoint 97-2003
+    Ppt,
+    Rtf,
+    Epub,
+    Pdf,
+    /// Jupyter Notebook
+    Notebook,
+    /// SQLite 数据库
+    Sqlite,
+    /// Apache Parquet 列式数据文件
+    Parquet,
+    /// Arrow IPC 文件或流，包括 Feather v2
+    Arrow,
+    /// 分隔符文本
+    Csv,
+    /// 含表格的 HTML，常见于导出为 .xls 的报表
+    Html,
+}
+
+impl DocsFormat {
+    /// 格式名，与序列化的取值一致
+    pub fn name(&self) -> &'static str {
+        match self {
+            DocsFormat::Xlsx => "xlsx",
+            DocsFormat::Xlsb => "xlsb",
+            DocsFormat::Xls => "xls",
+            DocsFormat::Ods => "ods",
+            DocsFormat::Docx => "docx",
+            DocsFormat::Pptx => "pptx",
+            DocsFormat::Odt => "odt",
+            DocsFormat::Odp => "odp",
+            DocsFormat::Doc => "doc",
+            DocsFormat::Ppt => "ppt",
+            DocsFormat::Rtf => "rtf",
+            DocsFormat::Epub => "epub",
+            DocsFormat::Pdf => "pdf",
+            DocsFormat::Notebook => "ipynb",
+            DocsFormat::Sqlite => "sqlite",
+            DocsFormat::Parquet => "parquet",
+            DocsFormat::Arrow => "arrow",
+            DocsFormat::Csv => "csv",
+            DocsFormat::Html => "html",
+        }
+    }
+
+    /// 是否按表格预览
+    pub fn is_sheet(&self) -> bool {
+        matches!(
+            self,
+            DocsFormat::Xlsx
+                | DocsFormat::Xlsb
+                | DocsFormat::Xls
+                | DocsFormat::Ods
+                | DocsFormat::Csv
+                | DocsFormat::Html
+                | DocsFormat::Parquet
+                | DocsFormat::Arrow
+        )
+    }
+
+    /// 根据扩展名推断格式
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "xlsx" | "xlsm" | "xlam" => Some(DocsFormat::Xlsx),
+            "xlsb" => Some(DocsFormat::Xlsb),
+            "xls" | "xla" => Some(DocsFormat::Xls),
+            "ods" => Some(DocsFormat::Ods),
+            "docx" => Some(DocsFormat::Docx),
+            "pptx" => Some(DocsFormat::Pptx),
+            "odt" => Some(DocsFormat::Odt),
+            "odp" => Some(DocsFormat::Odp),
+            "doc" | "dot" => Some(DocsFormat::Doc),
+            "ppt" | "pps" | "pot" => Some(DocsFormat::Ppt),
+            "rtf" => Some(DocsFormat::Rtf),
+            "epub" => Some(DocsFormat::Epub),
+            "pdf" => Some(DocsFormat::Pdf),
+            "ipynb" => Some(DocsFormat::Notebook),
+            "db" | "db3" | "sqlite" | "sqlite3" => Some(DocsFormat::Sqlite),
+            "parquet" => Some(DocsFormat::Parquet),
+            "arrow" | "arrows" | "feather" | "ipc" => Some(DocsFormat::Arrow),
+            "csv" | "tsv" | "psv" | "tab" => Some(DocsFormat::Csv),
+            "htm" | "html" => Some(DocsFormat::Html),
+            _ => None,
+        }
+    }
+
+    /// 识别文档格式，优先使用文件内容，空文件回退到扩展名
+    pub fn detect(path: &str) -> Result<Self, DocsError> {
+        let mut file = File::open(path)?;
+        let mut header = Vec::with_capacity(HEADER_SIZE as usize);
+        (&mut file).take(HEADER_SIZE).read_to_end(&mut header)?;
+        file.rewind()?;
+
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        let format = if header.is_empty() {
+            Self::from_extension(&extension).ok_or(DocsError::Unsupported(extension))?
+        } else if header.starts_with(&OLE2_MAGIC) {
+            detect_ole2(file)?
+        } else if header.starts_with(b"PK\x03\x04") {
+            detect_package(file)?
+        } else if is_pdf(&header) {
+            DocsFormat::Pdf
+        } else if header.starts_with(SQLITE_MAGIC) {
+            DocsFormat::Sqlite
+        } else if header.starts_with(b"PAR1") || header.starts_with(b"PARE") {
+            DocsFormat::Parquet
+        } else if header.starts_with(ARROW_MAGIC) {
+            DocsFormat::Arrow
+        } else if header.starts_with(b"FEA1") {
+            return Err(DocsError::Unsupported("Feather v1".to_string()));
+        } else if Self::from_extension(&extension) == Some(DocsFormat::Arrow) {
+            // 流格式没有文件头
+            DocsFormat::Arrow
+        } else {
+            detect_text(&header, &extension).ok_or(DocsError::Unsupported(extension))?
+        };
+        log::info!("识别文档格式: {} -> {}", path, format.name());
+        Ok(format)
+    }
+}
+
+/// OLE2 复合文档：旧版 Office 二进制格式，或加密后的 OOXML
+pub(crate) fn detect_ole2(file: File) -> Result<DocsFormat, DocsError> {
+    let cfb = cfb::CompoundFile::open(file)?;
+    if cfb.is_stream("/EncryptedPackage") {
+        Err(DocsError::Encrypted)
+    } else if cfb.is_stream("/Workbook") || cfb.is_stream("/Book") {
+        Ok(DocsFormat::Xls)
+    } else if cfb.is_stream("/WordDocument") {
+        Ok(DocsFormat::Doc)
+    } else if cfb.is_stream("/PowerPoint Document") {
+        Ok(DocsFormat::Ppt)
+    } else {
+        Err(DocsError::Unsupported("OLE2 复合文档".to_string()))
+    }
+}
+
+/// zip 包：ODF 与 EPUB 由 mimetype 区分，OOXML 由主部件区分
+fn detect_package(file: File) -> Result<DocsFormat, DocsError> {
+    let mut archive = ZipArchive::new(BufReader::new(file))?;
+    if let Ok(mut entry) = archive.by_name("mimetype") {
+        let mut mimetype = String::new();
+        entry.by_ref().take(256).read_to_string(&mut mimetype)?;
+        let mimetype = mimetype.trim();
+        if mimetype == "application/epub+zip" {
+            return Ok(DocsFormat::Epub);
+        }
+        return match mimetype.strip_prefix("application/vnd.oasis.opendocument.") {
+            Some("text") => Ok(DocsFormat::Odt),
+            Some("spreadsheet") => Ok(DocsFormat::Ods),
+            Some("presentation") => Ok(DocsFormat::Odp),
+            _ => Err(DocsError::Unsupported(mimetype.to_string())),
+        };
+    }
+
+    let parts = [
+        ("word/document.xml", DocsFormat::Docx),
+        ("ppt/presentation.xml", DocsFormat::Pptx),
+        ("xl/workbook.xml", DocsFormat::Xlsx),
+        ("xl/workbook.bin", DocsFormat::Xlsb),
+    ];
+    parts
+        .into_iter()
+        .find(|(part, _)| archive.index_for_name(part).is_some())
+        .map(|(_, format)| format)
+        .ok_or(DocsError::Unsupported("zip".to_string()))
+}
+
+/// PDF 文件头 `%PDF-` 允许出现在前 1024 字节内
+fn is_pdf(header: &[u8]) -> bool {
+    header[..header.len().min(1024)]
+        .windows(5)
+        .any(|w| w == b"%PDF-")
+}
+
+/// 文本文件：RTF、Jupyter Notebook、含表格的 HTML，否则按分隔符文本处理；含 NUL 的视为二进制
+fn detect_text(header: &[u8], extension: &str) -> Option<DocsFormat> {
+    let utf16 = header.starts_with(&[0xFF, 0xFE]) || header.starts_with(&[0xFE, 0xFF]);
+    if !utf16 && header.contains(&0) {
+        return None;
+    }
+    let start = String::from_utf8_lossy(header).to_ascii_lowercase();
+    let start = start.trim_start_matches('\u{feff}').trim_start();
+    if start.starts_with("{\\rtf") {
+        return Some(DocsFormat::Rtf);
+    }
+    // nbformat 字段通常位于文件末尾，文件头中不一定能看到，此时依据扩展名
+    if start.starts_with('{') && (extension == "ipynb" || start.contains("\"nbformat\"")) {
+        return Some(DocsFormat::Notebook);
+    }
+    let is_html = start.starts_with("<!doctype html")
+        || start.starts_with("<html")
+        || (start.starts_with('<') && start.contains("<table"));
+    Some(if is_html {
+        DocsFormat::Html
+    } else {
+        DocsFormat::Csv
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// 以给定文件名写入内容后识别格式
+    fn detect(name: &str, data: &[u8]) -> Result<DocsFormat, DocsError> {
+        let path = std::env::temp_dir().join(format!("detect-{}-{}", std::process::id(), name));
+        std::fs::write(&path, data).unwrap();
+        let format = DocsFormat::detect(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        format
+    }
+
+    fn zip(parts: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for (name, content) in parts {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn ole2(stream: &str) -> Vec<u8> {
+        let mut cfb = cfb::CompoundFile::create(std::io::Cursor::new(Vec::new())).unwrap();
+        cfb.create_stream(stream)
+            .unwrap()
+            .write_all(b"data")
+            .unwrap();
+        cfb.flush().unwrap();
+        cfb.into_inner().into_inner()
+    }
+
+    #[test]
+    fn packages_by_content() {
+        let docx = zip(&[("word/document.xml", "<w:document/>")]);
+        assert_eq!(detect("report.xlsx", &docx).unwrap(), DocsFormat::Docx);
+        let xlsb = zip(&[("xl/workbook.bin", "")]);
+        assert_eq!(detect("book.xlsx", &xlsb).unwrap(), DocsFormat::Xlsb);
+        let odt = zip(&[("mimetype", "application/vnd.oasis.opendocument.text")]);
+        assert_eq!(detect("letter.docx", &odt).unwrap(), DocsFormat::Odt);
+        let epub = zip(&[("mimetype", "application/epub+zip\n")]);
+        assert_eq!(detect("book.zip", &epub).unwrap(), DocsFormat::Epub);
+        let other = zip(&[("readme.txt", "hi")]);
+        assert!(matches!(
+            detect("fake.docx", &other),
+            Err(DocsError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn ole2_by_stream() {
+        assert_eq!(
+            detect("old.xls", &ole2("/WordDocument")).unwrap(),
+            DocsFormat::Doc
+        );
+        assert_eq!(
+            detect("old.doc", &ole2("/Workbook")).unwrap(),
+            DocsFormat::Xls
+        );
+        assert_eq!(
+            detect("old.bin", &ole2("/PowerPoint Document")).unwrap(),
+            DocsFormat::Ppt
+        );
+        let encrypted = detect("secret.docx", &ole2("/EncryptedPackage"));
+        assert!(matches!(encrypted, Err(DocsError::Encrypted)));
+    }
+
+    #[test]
+    fn binary_signatures() {
+        assert_eq!(
+            detect("scan.doc", b"junk\n%PDF-1.7\n").unwrap(),
+            DocsFormat::Pdf
+        );
+        let mut sqlite = SQLITE_MAGIC.to_vec();
+        sqlite.resize(100, 0);
+        assert_eq!(detect("data.dat", &sqlite).unwrap(), DocsFormat::Sqlite);
+        assert_eq!(
+            detect("table.csv", b"PAR1\0\0\0\0").unwrap(),
+            DocsFormat::Parquet
+        );
+        assert_eq!(
+            detect("table.bin", b"ARROW1\0\0").unwrap(),
+            DocsFormat::Arrow
+        );
+        assert!(matches!(
+            detect("old.feather", b"FEA1\0\0"),
+            Err(DocsError::Unsupported(_))
+        ));
+        assert!(matches!(
+            detect("blob.xls", b"\x01\x02\0\x03"),
+            Err(DocsError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn text_by_content() {
+        assert_eq!(
+            detect("memo.doc", b"{\\rtf1\\ansi hello}").unwrap(),
+            DocsFormat::Rtf
+        );
+        let html = b"<meta charset=utf-8>\n<table><tr><td>1</td></tr></table>";
+        assert_eq!(detect("report.xls", html).unwrap(), DocsFormat::Html);
+        assert_eq!(
+            detect("export.xls", b"a,b\n1,2\n").unwrap(),
+            DocsFormat::Csv
+        );
+        let notebook = b"{\n \"cells\": [],\n \"nbformat\": 4\n}";
+        assert_eq!(
+            detect("notes.json", notebook).unwrap(),
+            DocsFormat::Notebook
+        );
+    }
+
+    #[test]
+    fn empty_files_use_extension() {
+        assert_eq!(detect("empty.xlsx", b"").unwrap(), DocsFormat::Xlsx);
+        assert!(matches!(
+            detect("empty.foo", b""),
+            Err(DocsError::Unsupported(_))
+        ));
+    }
+}
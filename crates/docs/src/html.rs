@@ -0,0 +1,356 @@
+//! HTML 表格：导出为 .xls 的报表常常是 HTML，每个顶层 `<table>` 作为一个工作表
+
+use crate::{
+    delimited::detect_encoding,
+    error::DocsError,
+    ooxml::MAX_PART_SIZE,
+    sheet::{Cell, DSheet, MergedRange, SheetLayout},
+};
+use encoding_rs::Encoding;
+use std::{collections::HashSet, fs};
+
+/// 单元格允许的最大跨行/跨列数
+const MAX_SPAN: u32 = 1000;
+
+/// 读取 HTML 中的所有顶层表格
+pub(crate) fn tables(path: &str) -> Result<Vec<DSheet>, DocsError> {
+    let size = fs::metadata(path)?.len();
+    if size > MAX_PART_SIZE {
+        return Err(DocsError::LimitExceeded {
+            limit: format!("文件大小 {} 字节", size),
+            part: None,
+        });
+    }
+    let data = fs::read(path)?;
+    let text = decode(&data);
+
+    let mut tables = Vec::new();
+    let mut builder = TableBuilder::default();
+    let mut depth = 0;
+    let mut rest = text.as_str();
+    while let Some(start) = rest.find('<') {
+        builder.text(&rest[..start]);
+        rest = &rest[start..];
+        let Some(tag) = Tag::parse(rest) else {
+            builder.text("<");
+            rest = &rest[1..];
+            continue;
+        };
+        rest = &rest[tag.len..];
+
+        match (tag.name.as_str(), tag.closing) {
+            ("script" | "style", false) => {
+                let end = format!("</{}", tag.name);
+                let skip = rest.to_ascii_lowercase().find(&end).unwrap_or(rest.len());
+                rest = &rest[skip..];
+            },
+            ("table", false) => depth += 1,
+            ("table", true) if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    let table = std::mem::take(&mut builder);
+                    if let Some(sheet) = table.finish(format!("sheet{}", tables.len() + 1)) {
+                        tables.push(sheet);
+                    }
+                }
+            },
+            ("br", _) => builder.push('\n'),
+            // 嵌套表格的内容并入外层单元格的文本
+            _ if depth != 1 => {},
+            ("tr", false) => builder.start_row(),
+            ("tr", true) => builder.end_row(),
+            ("td" | "th", false) => builder.start_cell(&tag),
+            ("td" | "th", true) => builder.end_cell(),
+            _ => {},
+        }
+    }
+    Ok(tables)
+}
+
+/// BOM 优先，其次是 `<meta charset>`，最后按内容猜测
+fn decode(data: &[u8]) -> String {
+    if let Some((encoding, len)) = Encoding::for_bom(data) {
+        return encoding
+            .decode_without_bom_handling(&data[len..])
+            .0
+            .into_owned();
+    }
+    let head = String::from_utf8_lossy(&data[..data.len().min(4096)]).to_ascii_lowercase();
+    let declared = head.find("charset=").and_then(|i| {
+        let label: String = head[i + 8..]
+            .trim_start_matches(['"', '\''])
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+            .collect();
+        Encoding::for_label(label.as_bytes())
+    });
+    let encoding = declared.unwrap_or_else(|| detect_encoding(data, false));
+    encoding.decode_without_bom_handling(data).0.into_owned()
+}
+
+/// 开始或结束标签
+struct Tag {
+    name: String,
+    closing: bool,
+    attributes: Vec<(String, String)>,
+    /// 标签在源文本中的长度
+    len: usize,
+}
+
+impl Tag {
+    /// 解析 `text` 开头的标签，注释与声明作为无名标签跳过
+    fn parse(text: &str) -> Option<Self> {
+        let skipped = |len: usize| Tag {
+            name: String::new(),
+            closing: false,
+            attributes: Vec::new(),
+            len,
+        };
+        if text.starts_with("<!--") {
+            let len = text.find("-->").map_or(text.len(), |i| i + 3);
+            return Some(skipped(len));
+        }
+        if text.starts_with("<!") || text.starts_with("<?") {
+            let len = text.find('>').map_or(text.len(), |i| i + 1);
+            return Some(skipped(len));
+        }
+
+        let closing = text[1..].starts_with('/');
+        let body = &text[if closing { 2 } else { 1 }..];
+        let name_len = body
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(body.len());
+        if name_len == 0 {
+            return None;
+        }
+        let name = body[..name_len].to_ascii_lowercase();
+
+        // 属性值中可能出现 `>`
+        let mut attributes = Vec::new();
+        let mut chars = body[name_len..].char_indices().peekable();
+        let mut end = None;
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '>' => {
+                    end = Some(i);
+                    break;
+                },
+                c if c.is_whitespace() || c == '/' => {},
+                _ => {
+                    let mut key = String::from(c);
+                    while let Some(&(_, c)) = chars.peek() {
+                        if c.is_whitespace() || matches!(c, '=' | '>' | '/') {
+                            break;
+                        }
+                        key.push(c);
+                        chars.next();
+                    }
+                    while chars.peek().is_some_and(|(_, c)| c.is_whitespace()) {
+                        chars.next();
+                    }
+                    let mut value = String::new();
+                    if chars.peek().is_some_and(|&(_, c)| c == '=') {
+                        chars.next();
+                        while chars.peek().is_some_and(|(_, c)| c.is_whitespace()) {
+                            chars.next();
+                        }
+                        match chars.peek().map(|&(_, c)| c) {
+                            Some(quote @ ('"' | '\'')) => {
+                                chars.next();
+                                for (_, c) in chars.by_ref() {
+                                    if c == quote {
+                                        break;
+                                    }
+                                    value.push(c);
+                                }
+                            },
+                            _ => {
+                                while let Some(&(_, c)) = chars.peek() {
+                                    if c.is_whitespace() || c == '>' {
+                                        break;
+                                    }
+                                    value.push(c);
+                                    chars.next();
+                                }
+                            },
+                        }
+                    }
+                    attributes.push((key.to_ascii_lowercase(), value));
+                },
+            }
+        }
+        let prefix = text.len() - body.len() + name_len;
+        Some(Tag {
+            name,
+            closing,
+            attributes,
+            len: end.map_or(text.len(), |i| prefix + i + 1),
+        })
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn span(&self, name: &str) -> u32 {
+        self.attribute(name)
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .unwrap_or(1)
+            .clamp(1, MAX_SPAN)
+    }
+}
+
+/// 正在构建的表格，按跨行跨列占位后确定单元格的位置
+#[derive(Default)]
+struct TableBuilder {
+    rows: Vec<Vec<Cell>>,
+    merges: Vec<MergedRange>,
+    /// 被上方跨行单元格占据的位置
+    occupied: HashSet<(u32, u32)>,
+    /// 当前行号与下一个单元格的起始列
+    row: Option<(u32, u32)>,
+    /// 当前单元格的位置与文本
+    cell: Option<((u32, u32), String)>,
+}
+
+impl TableBuilder {
+    fn start_row(&mut self) {
+        self.end_row();
+        let row = self.row.map_or(0, |(row, _)| row + 1);
+        self.row = Some((row, 0));
+    }
+
+    fn end_row(&mut self) {
+        self.end_cell();
+    }
+
+    fn start_cell(&mut self, tag: &Tag) {
+        self.end_cell();
+        if self.row.is_none() {
+            self.start_row();
+        }
+        let Some((row, mut col)) = self.row else {
+            return;
+        };
+        while self.occupied.contains(&(row, col)) {
+            col += 1;
+        }
+        let (row_span, col_span) = (tag.span("rowspan"), tag.span("colspan"));
+        if row_span > 1 || col_span > 1 {
+            for r in row..row + row_span {
+                for c in col..col + col_span {
+                    self.occupied.insert((r, c));
+                }
+            }
+            let end = (row + row_span - 1, col + col_span - 1);
+            self.merges.push(MergedRange::new((row, col), end));
+        }
+        self.row = Some((row, col + col_span));
+        self.cell = Some(((row, col), String::new()));
+    }
+
+    fn end_cell(&mut self) {
+        let Some(((row, col), text)) = self.cell.take() else {
+            return;
+        };
+        let text = text
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (row, col) = (row as usize, col as usize);
+        if self.rows.len() <= row {
+            self.rows.resize(row + 1, Vec::new());
+        }
+        let cells = &mut self.rows[row];
+        if cells.len() <= col {
+            cells.resize(col + 1, Cell::empty());
+        }
+        cells[col] = Cell::from_text(text.trim());
+    }
+
+    /// 单元格内的文本，单元格外的忽略
+    fn text(&mut self, text: &str) {
+        if let Some((_, content)) = self.cell.as_mut() {
+            // 源码中的换行只是空白，`<br>` 才是换行
+            content.push_str(&unescape(&text.replace(['\r', '\n'], " ")));
+        }
+    }
+
+    fn push(&mut self, c: char) {
+        if let Some((_, content)) = self.cell.as_mut() {
+            content.push(c);
+        }
+    }
+
+    fn finish(mut self, name: String) -> Option<DSheet> {
+        self.end_cell();
+        if self.rows.is_empty() {
+            return None;
+        }
+        let width = self.rows.iter().map(Vec::len).max().unwrap_or(0);
+        for row in self.rows.iter_mut() {
+            row.resize(width, Cell::empty());
+        }
+        Some(DSheet {
+            name,
+            hidden: false,
+            origin: (0, 0),
+            header: None,
+            rows: self.rows,
+            layout: SheetLayout {
+                merges: self.merges,
+                ..Default::default()
+            },
+            formulas: Default::default(),
+            comments: Default::default(),
+            hyperlinks: Default::default(),
+        })
+    }
+}
+
+/// 解码字符实体
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest[1..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '#'))
+            .map(|i| i + 1)
+            .unwrap_or(rest.len());
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse::<u32>))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                result.push(c);
+                rest = &rest[end..];
+                rest = rest.strip_prefix(';').unwrap_or(rest);
+            },
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            },
+        }
+    }
+    result.push_str(rest);
+    result
+}
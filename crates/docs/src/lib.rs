@@ -1,76 +1,169 @@
-use calamine::Reader;
 use serde::Serialize;
-use std::fs::File;
+
+mod arrow;
+pub mod columnar;
+pub mod delimited;
+pub mod doc;
+pub mod document;
+pub mod docx;
+pub mod epub;
+pub mod error;
+pub mod export;
+pub mod format;
+mod html;
+pub mod metadata;
+pub mod notebook;
+pub mod numfmt;
+pub mod odf;
+mod ole;
+pub mod ooxml;
+mod parquet;
+pub mod pdf;
+pub mod ppt;
+pub mod pptx;
+pub mod rtf;
+pub mod search;
+pub mod sheet;
+pub mod slides;
+pub mod sqlite;
+pub mod workbook;
+pub mod xlsx;
+
+pub use columnar::ColumnarInfo;
+pub use document::Document;
+pub use epub::Book;
+pub use error::DocsError;
+pub use export::{ExportFormat, SheetRange};
+pub use format::DocsFormat;
+pub use metadata::DocumentMetadata;
+pub use notebook::Notebook;
+pub use pdf::PdfInfo;
+pub use search::{SearchHit, SearchLocation, SearchOptions};
+pub use sheet::{Cell, CellValue, DSheet};
+pub use slides::Presentation;
+pub use sqlite::DatabaseInfo;
+pub use workbook::{LazyWorkbook, SheetInfo, SheetWindow};
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, Serialize)]
 pub enum Docs {
     Excel(Vec<DSheet>),
-    Docx(String),
+    Docx(Document),
+    Pptx(Presentation),
+    Odt(Document),
+    Odp(Presentation),
+    Doc(Document),
+    Ppt(Presentation),
+    Rtf(Document),
+    Epub(Book),
+    Pdf(PdfInfo),
+    Notebook(Notebook),
+    Sqlite(DatabaseInfo),
+    Columnar(ColumnarInfo),
+}
+
+/// `Docs::open` 的结果：识别出的格式与解析出的内容
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectedDocs {
+    pub format: DocsFormat,
+    #[serde(flatten)]
+    pub docs: Docs,
 }
 
 #[allow(unused)]
 impl Docs {
-    pub fn excel(file_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let target = excel(file_path)?;
+    /// 按文件内容识别格式并选择对应的解析器，不依赖扩展名
+    pub fn open(file_path: &str) -> Result<DetectedDocs, DocsError> {
+        let format = DocsFormat::detect(file_path)?;
+        let docs = match format {
+            DocsFormat::Xlsx | DocsFormat::Xlsb | DocsFormat::Xls | DocsFormat::Ods => {
+                Docs::excel(file_path)?
+            },
+            DocsFormat::Csv => Docs::csv(file_path)?,
+            DocsFormat::Html => Docs::Excel(html::tables(file_path)?),
+            DocsFormat::Docx => Docs::docx(file_path)?,
+            DocsFormat::Pptx => Docs::pptx(file_path)?,
+            DocsFormat::Odt => Docs::odt(file_path)?,
+            DocsFormat::Odp => Docs::odp(file_path)?,
+            DocsFormat::Doc => Docs::doc(file_path)?,
+            DocsFormat::Ppt => Docs::ppt(file_path)?,
+            DocsFormat::Rtf => Docs::rtf(file_path)?,
+            DocsFormat::Epub => Docs::epub(file_path)?,
+            DocsFormat::Pdf => Docs::pdf(file_path)?,
+            DocsFormat::Notebook => Docs::notebook(file_path)?,
+            DocsFormat::Sqlite => Docs::sqlite(file_path)?,
+            DocsFormat::Parquet | DocsFormat::Arrow => Docs::columnar(file_path)?,
+        };
+        Ok(DetectedDocs { format, docs })
+    }
+
+    pub fn excel(file_path: &str) -> Result<Self, DocsError> {
+        let target = sheet::excel(file_path)?;
         Ok(Docs::Excel(target))
     }
 
-    pub fn csv(file_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let target = csv(file_path)?;
+    pub fn csv(file_path: &str) -> Result<Self, DocsError> {
+        let target = sheet::csv(file_path)?;
         Ok(Docs::Excel(target))
     }
 
-    pub fn docx(file_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        // let target = docx(file_path)?;
-        // todo 解析工作目前由 web 端解析
-        Ok(Docs::Docx(file_path.to_string()))
+    pub fn docx(file_path: &str) -> Result<Self, DocsError> {
+        let target = docx::parse(file_path)?;
+        Ok(Docs::Docx(target))
     }
-}
 
-#[derive(Debug, Clone, Serialize)]
-pub struct DSheet {
-    name: String,
-    rows: Vec<Vec<String>>,
-}
+    pub fn pptx(file_path: &str) -> Result<Self, DocsError> {
+        let target = pptx::parse(file_path)?;
+        Ok(Docs::Pptx(target))
+    }
 
-fn excel(file_path: &str) -> Result<Vec<DSheet>, Box<dyn std::error::Error>> {
-    let mut workbook = calamine::open_workbook_auto(file_path)?;
-    let sheets = workbook.sheet_names().to_owned();
-    let mut target: Vec<DSheet> = Vec::new();
-
-    for sheet in sheets {
-        let range = workbook.worksheet_range(&sheet)?;
-        let mut rows = Vec::new();
-        for row in range.rows() {
-            let mut cell_list: Vec<String> = Vec::new();
-            for cell in row.iter() {
-                cell_list.push(cell.to_string());
-            }
-            rows.push(cell_list);
-        }
-        let map = DSheet { name: sheet, rows };
-
-        target.push(map);
+    pub fn odt(file_path: &str) -> Result<Self, DocsError> {
+        let target = odf::parse_text(file_path)?;
+        Ok(Docs::Odt(target))
+    }
+
+    pub fn odp(file_path: &str) -> Result<Self, DocsError> {
+        let target = odf::parse_presentation(file_path)?;
+        Ok(Docs::Odp(target))
+    }
+
+    pub fn doc(file_path: &str) -> Result<Self, DocsError> {
+        let target = doc::parse(file_path)?;
+        Ok(Docs::Doc(target))
+    }
+
+    pub fn ppt(file_path: &str) -> Result<Self, DocsError> {
+        let target = ppt::parse(file_path)?;
+        Ok(Docs::Ppt(target))
+    }
+
+    pub fn rtf(file_path: &str) -> Result<Self, DocsError> {
+        let target = rtf::parse(file_path)?;
+        Ok(Docs::Rtf(target))
+    }
+
+    pub fn epub(file_path: &str) -> Result<Self, DocsError> {
+        let target = epub::parse(file_path)?;
+        Ok(Docs::Epub(target))
+    }
+
+    pub fn pdf(file_path: &str) -> Result<Self, DocsError> {
+        let target = pdf::parse(file_path)?;
+        Ok(Docs::Pdf(target))
+    }
+
+    pub fn notebook(file_path: &str) -> Result<Self, DocsError> {
+        let target = notebook::parse(file_path)?;
+        Ok(Docs::Notebook(target))
+    }
+
+    pub fn sqlite(file_path: &str) -> Result<Self, DocsError> {
+        let target = sqlite::parse(file_path)?;
+        Ok(Docs::Sqlite(target))
     }
-    Ok(target)
-}
 
-fn csv(file_path: &str) -> Result<Vec<DSheet>, Box<dyn std::error::Error>> {
-    let file = File::open(file_path)?;
-    let mut rdr = csv::ReaderBuilder::new()
-        .has_headers(true)
-        .from_reader(file);
-
-    let mut rows: Vec<Vec<String>> = vec![];
-    for result in rdr.records() {
-        let record = result?;
-        let mut cols: Vec<String> = Vec::new();
-        for i in 0..record.len() {
-            cols.push(record[i].to_string());
-        }
-        rows.push(cols);
+    pub fn columnar(file_path: &str) -> Result<Self, DocsError> {
+        let target = columnar::parse(file_path)?;
+        Ok(Docs::Columnar(target))
     }
-    let target: Vec<DSheet> = vec![DSheet { name: "sheet1".to_string(), rows }];
-    Ok(target)
 }
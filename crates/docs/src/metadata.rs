@@ -0,0 +1,338 @@
+use crate::{
+    error::DocsError,
+    format::DocsFormat,
+    ole::{self, codepage_encoding, u16_at, u32_at, utf16_text},
+    ooxml::{child, Package},
+};
+use chrono::DateTime;
+use roxmltree::Node;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// OOXML 属性部件的默认位置，包关系中未声明时使用
+const CORE_PART: &str = "docProps/core.xml";
+const APP_PART: &str = "docProps/app.xml";
+const CUSTOM_PART: &str = "docProps/custom.xml";
+
+const SUMMARY_STREAM: &str = "\u{5}SummaryInformation";
+const DOCUMENT_SUMMARY_STREAM: &str = "\u{5}DocumentSummaryInformation";
+/// 1601-01-01 到 1970-01-01 的秒数
+const FILETIME_EPOCH: i64 = 11_644_473_600;
+
+/// 文档元数据
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DocumentMetadata {
+    pub title: Option<String>,
+    pub subject: Option<String>,
+    pub description: Option<String>,
+    pub keywords: Vec<String>,
+    /// 作者（最初创建者）
+    pub author: Option<String>,
+    /// 最后修改者
+    pub last_modified_by: Option<String>,
+    /// 创建时间，ISO 8601
+    pub created: Option<String>,
+    /// 修改时间，ISO 8601
+    pub modified: Option<String>,
+    /// 生成文档的应用程序
+    pub application: Option<String>,
+    /// 修订次数
+    pub revision: Option<String>,
+    pub page_count: Option<u32>,
+    pub word_count: Option<u32>,
+    pub slide_count: Option<u32>,
+    /// 自定义属性
+    pub custom: BTreeMap<String, String>,
+}
+
+impl DocumentMetadata {
+    /// 按文件内容识别格式并读取元数据，不支持元数据的格式返回 `None`
+    pub fn read(path: &str) -> Result<Option<Self>, DocsError> {
+        match DocsFormat::detect(path)? {
+            DocsFormat::Docx | DocsFormat::Pptx | DocsFormat::Xlsx | DocsFormat::Xlsb => {
+                Self::from_ooxml(&mut Package::open(path)?)
+            },
+            DocsFormat::Odt | DocsFormat::Odp | DocsFormat::Ods => {
+                match Package::open(path)?.read_string("meta.xml")? {
+                    Some(xml) => Ok(Some(Self::from_odf_meta(&xml)?)),
+                    None => Ok(None),
+                }
+            },
+            DocsFormat::Doc | DocsFormat::Ppt | DocsFormat::Xls => {
+                Self::from_ole(&mut ole::open(path)?)
+            },
+            DocsFormat::Rtf => Ok(crate::rtf::parse(path)?.metadata),
+            DocsFormat::Pdf => Ok(crate::pdf::Pdf::open(path)?.metadata()),
+            DocsFormat::Epub => {
+                let book = crate::epub::Epub::open(path)?.book()?;
+                Ok(Some(DocumentMetadata::from(&book.metadata)))
+            },
+            DocsFormat::Notebook => {
+                let notebook = crate::notebook::parse(path)?;
+                Ok(Some(DocumentMetadata::from(&notebook)))
+            },
+            DocsFormat::Parquet | DocsFormat::Arrow => {
+                let columnar = crate::columnar::Columnar::open(path)?;
+                Ok(Some(DocumentMetadata::from(columnar.info())))
+            },
+            _ => Ok(None),
+        }
+    }
+
+    /// 解析 OOXML 的 docProps/core.xml、app.xml 与 custom.xml，三者都不存在时返回 `None`
+    pub fn from_ooxml(package: &mut Package) -> Result<Option<Self>, DocsError> {
+        let rels = package.relationships("")?;
+        let part = |kind: &str, default: &str| {
+            rels.values()
+                .find(|r| r.kind == kind && !r.external)
+                .map_or(default.to_string(), |r| r.target.clone())
+        };
+        let core = package.read_string(&part("core-properties", CORE_PART))?;
+        let app = package.read_string(&part("extended-properties", APP_PART))?;
+        let custom = package.read_string(&part("custom-properties", CUSTOM_PART))?;
+        if core.is_none() && app.is_none() && custom.is_none() {
+            return Ok(None);
+        }
+
+        let mut metadata = DocumentMetadata::default();
+        if let Some(xml) = core {
+            let doc = roxmltree::Document::parse(&xml).map_err(|e| part_error(e, CORE_PART))?;
+            for node in doc.root_element().children().filter(Node::is_element) {
+                let text = node_text(&node);
+                match node.tag_name().name() {
+                    "title" => metadata.title = text,
+                    "subject" => metadata.subject = text,
+                    "description" => metadata.description = text,
+                    "keywords" => {
+                        metadata.keywords = text.map(|k| split_keywords(&k)).unwrap_or_default()
+                    },
+                    "creator" => metadata.author = text,
+                    "lastModifiedBy" => metadata.last_modified_by = text,
+                    "created" => metadata.created = text,
+                    "modified" => metadata.modified = text,
+                    "revision" => metadata.revision = text,
+                    _ => {},
+                }
+            }
+        }
+
+        if let Some(xml) = app {
+            let doc = roxmltree::Document::parse(&xml).map_err(|e| part_error(e, APP_PART))?;
+            let root = doc.root_element();
+            let count = |name: &str| child(&root, name).and_then(|n| n.text()?.trim().parse().ok());
+            metadata.application = child(&root, "Application").and_then(|n| node_text(&n));
+            metadata.page_count = count("Pages");
+            metadata.word_count = count("Words");
+            metadata.slide_count = count("Slides");
+        }
+
+        if let Some(xml) = custom {
+            let doc = roxmltree::Document::parse(&xml).map_err(|e| part_error(e, CUSTOM_PART))?;
+            for property in doc
+                .root_element()
+                .children()
+                .filter(|n| n.has_tag_name("property"))
+            {
+                // 值是唯一的 vt:* 子元素
+                let value = property
+                    .children()
+                    .find(Node::is_element)
+                    .and_then(|n| node_text(&n));
+                if let (Some(name), Some(value)) = (property.attribute("name"), value) {
+                    metadata.custom.insert(name.to_string(), value);
+                }
+            }
+        }
+        Ok(Some(metadata))
+    }
+
+    /// 读取 OLE2 复合文档的 SummaryInformation 与 DocumentSummaryInformation 属性集
+    pub fn from_ole(cfb: &mut cfb::CompoundFile<std::fs::File>) -> Result<Option<Self>, DocsError> {
+        let Some(data) = ole::read_stream(cfb, SUMMARY_STREAM)? else {
+            return Ok(None);
+        };
+        let mut summary = property_set(&data).map_err(|e| e.in_part(SUMMARY_STREAM))?;
+        let mut text = |id: u32| match summary.remove(&id) {
+            Some(Property::Text(text)) if !text.trim().is_empty() => Some(text.trim().to_string()),
+            _ => None,
+        };
+
+        let mut metadata = DocumentMetadata {
+            title: text(2),
+            subject: text(3),
+            author: text(4),
+            keywords: text(5).map(|k| split_keywords(&k)).unwrap_or_default(),
+            description: text(6),
+            last_modified_by: text(8),
+            revision: text(9),
+            application: text(18),
+            ..Default::default()
+        };
+        let time = |id: u32| match summary.get(&id) {
+            Some(Property::Time(time)) => Some(time.clone()),
+            _ => None,
+        };
+        let count = |properties: &BTreeMap<u32, Property>, id: u32| match properties.get(&id) {
+            Some(Property::Int(count)) if *count > 0 => Some(*count as u32),
+            _ => None,
+        };
+        metadata.created = time(12);
+        metadata.modified = time(13);
+        metadata.page_count = count(&summary, 14);
+        metadata.word_count = count(&summary, 15);
+
+        if let Some(data) = ole::read_stream(cfb, DOCUMENT_SUMMARY_STREAM)? {
+            match property_set(&data) {
+                Ok(properties) => metadata.slide_count = count(&properties, 7),
+                Err(e) => log::error!("解析 DocumentSummaryInformation 失败: {}", e),
+            }
+        }
+        Ok(Some(metadata))
+    }
+
+    /// 解析 ODF 的 meta.xml
+    pub fn from_odf_meta(xml: &str) -> Result<Self, DocsError> {
+        let doc = roxmltree::Document::parse(xml)?;
+        let mut metadata = DocumentMetadata::default();
+        let Some(meta) = child(&doc.root_element(), "meta") else {
+            return Ok(metadata);
+        };
+
+        for node in meta.children().filter(|n| n.is_element()) {
+            let text = || node_text(&node);
+            match node.tag_name().name() {
+                "title" => metadata.title = text(),
+                "subject" => metadata.subject = text(),
+                "description" => metadata.description = text(),
+                "keyword" => metadata.keywords.extend(text()),
+                "initial-creator" => metadata.author = text(),
+                "creator" => metadata.last_modified_by = text(),
+                "creation-date" => metadata.created = text(),
+                "date" => metadata.modified = text(),
+                "generator" => metadata.application = text(),
+                "editing-cycles" => metadata.revision = text(),
+                "document-statistic" => {
+                    let count = |name: &str| {
+                        node.attributes()
+                            .find(|a| a.name() == name)
+                            .and_then(|a| a.value().parse().ok())
+                    };
+                    metadata.page_count = count("page-count");
+                    metadata.word_count = count("word-count");
+                },
+                "user-defined" => {
+                    let name = node.attributes().find(|a| a.name() == "name");
+                    if let (Some(name), Some(value)) = (name, text()) {
+                        metadata.custom.insert(name.value().to_string(), value);
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        // 只有 dc:creator 时视为作者
+        if metadata.author.is_none() {
+            metadata.author = metadata.last_modified_by.clone();
+        }
+        Ok(metadata)
+    }
+}
+
+fn node_text(node: &Node) -> Option<String> {
+    node.text()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+}
+
+fn part_error(err: roxmltree::Error, part: &str) -> DocsError {
+    DocsError::from(err).in_part(part)
+}
+
+pub(crate) fn split_keywords(text: &str) -> Vec<String> {
+    text.split([',', ';', '，', '；'])
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 属性集中的值
+enum Property {
+    Text(String),
+    Int(i64),
+    /// ISO 8601
+    Time(String),
+}
+
+/// 解析属性集流的第一个节，键为属性 id
+fn property_set(data: &[u8]) -> Result<BTreeMap<u32, Property>, DocsError> {
+    let truncated = || DocsError::corrupt("属性集不完整");
+    if u16_at(data, 0) != Some(0xFFFE) {
+        return Err(DocsError::corrupt("属性集字节序标记无效"));
+    }
+    let section = u32_at(data, 44).ok_or_else(truncated)? as usize;
+    let count = u32_at(data, section + 4).ok_or_else(truncated)? as usize;
+
+    let mut entries = Vec::new();
+    for i in 0..count.min(1024) {
+        let id = u32_at(data, section + 8 + i * 8).ok_or_else(truncated)?;
+        let offset = u32_at(data, section + 12 + i * 8).ok_or_else(truncated)? as usize;
+        entries.push((id, section + offset));
+    }
+    // 字符串的编码由代码页属性决定
+    let codepage = entries
+        .iter()
+        .find(|(id, _)| *id == 1)
+        .and_then(|&(_, offset)| u16_at(data, offset + 4))
+        .unwrap_or(1252);
+    let encoding = codepage_encoding(codepage);
+
+    let mut properties = BTreeMap::new();
+    for (id, offset) in entries {
+        let Some(kind) = u32_at(data, offset) else {
+            continue;
+        };
+        let value = offset + 4;
+        let property = match kind {
+            // VT_I2
+            0x02 => u16_at(data, value).map(|v| Property::Int(v as i16 as i64)),
+            // VT_I4
+            0x03 => u32_at(data, value).map(|v| Property::Int(v as i32 as i64)),
+            // VT_LPSTR
+            0x1E => u32_at(data, value).and_then(|len| {
+                let bytes = data.get(value + 4..value + 4 + len as usize)?;
+                let (text, _) = encoding.decode_without_bom_handling(bytes);
+                Some(Property::Text(text.trim_end_matches('\0').to_string()))
+            }),
+            // VT_LPWSTR
+            0x1F => u32_at(data, value).and_then(|len| {
+                let bytes = data.get(value + 4..value + 4 + len as usize * 2)?;
+                Some(Property::Text(utf16_text(bytes)))
+            }),
+            // VT_FILETIME，100 纳秒为单位
+            0x40 => {
+                let low = u32_at(data, value);
+                let high = u32_at(data, value + 4);
+                low.zip(high).and_then(|(low, high)| {
+                    let ticks = ((high as u64) << 32 | low as u64) as i64;
+                    if ticks == 0 {
+                        return None;
+                    }
+                    let time = DateTime::from_timestamp(
+                        ticks / 10_000_000 - FILETIME_EPOCH,
+                        (ticks % 10_000_000) as u32 * 100,
+                    )?;
+                    Some(Property::Time(
+                        time.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+                    ))
+                })
+            },
+            _ => None,
+        };
+        if let Some(property) = property {
+            properties.insert(id, property);
+        }
+    }
+    Ok(properties)
+}
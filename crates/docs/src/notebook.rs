@@ -0,0 +1,397 @@
+//! Jupyter Notebook（nbformat v4）：单元格、执行输出与错误回溯
+
+use crate::{error::DocsError, metadata::DocumentMetadata, ooxml::MAX_PART_SIZE};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::HashMap, fs};
+
+/// 笔记本
+#[derive(Debug, Clone, Serialize)]
+pub struct Notebook {
+    pub nbformat: u32,
+    pub nbformat_minor: u32,
+    /// 内核语言，如 `python`
+    pub language: Option<String>,
+    /// 内核显示名，如 `Python 3 (ipykernel)`
+    pub kernel: Option<String>,
+    pub title: Option<String>,
+    pub authors: Vec<String>,
+    pub cells: Vec<NotebookCell>,
+}
+
+/// 单元格
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum NotebookCell {
+    /// Markdown 源码，`attachment:` 引用的图片已替换为 data URI
+    Markdown { source: String },
+    Code {
+        source: String,
+        /// 单元格自身指定的语言，否则为内核语言
+        language: Option<String>,
+        execution_count: Option<u32>,
+        outputs: Vec<CellOutput>,
+    },
+    Raw {
+        source: String,
+        /// 原始内容的 MIME 类型，如 `text/latex`
+        format: Option<String>,
+    },
+}
+
+/// 执行输出，文本中的 ANSI 控制序列已去除
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum CellOutput {
+    /// stdout 或 stderr，相邻的同名输出已合并
+    Stream { name: String, text: String },
+    /// `execute_result` 与 `display_data`
+    Data {
+        execution_count: Option<u32>,
+        data: OutputData,
+    },
+    Error {
+        ename: String,
+        evalue: String,
+        traceback: String,
+    },
+}
+
+/// 富文本输出中可预览的表示
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OutputData {
+    /// `text/plain`
+    pub plain: Option<String>,
+    /// `text/html`，应在沙箱中显示
+    pub html: Option<String>,
+    /// `image/png` 或 `image/jpeg` 的 data URI
+    pub image: Option<String>,
+}
+
+/// 源码与文本输出既可以是字符串，也可以是按行拆分的字符串数组
+#[derive(Debug, Default, Deserialize)]
+#[serde(untagged)]
+enum MultiLine {
+    #[default]
+    Empty,
+    Text(String),
+    Lines(Vec<String>),
+}
+
+impl MultiLine {
+    fn into_string(self) -> String {
+        match self {
+            MultiLine::Empty => String::new(),
+            MultiLine::Text(text) => text,
+            MultiLine::Lines(lines) => lines.concat(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RawNotebook {
+    nbformat: u32,
+    #[serde(default)]
+    nbformat_minor: u32,
+    #[serde(default)]
+    metadata: Value,
+    #[serde(default)]
+    cells: Vec<RawCell>,
+}
+
+#[derive(Deserialize)]
+struct RawCell {
+    cell_type: String,
+    #[serde(default)]
+    source: MultiLine,
+    #[serde(default)]
+    metadata: Value,
+    #[serde(default)]
+    execution_count: Option<u32>,
+    #[serde(default)]
+    outputs: Vec<RawOutput>,
+    /// 文件名 -> MIME 类型 -> base64 内容
+    #[serde(default)]
+    attachments: HashMap<String, HashMap<String, Value>>,
+}
+
+#[derive(Deserialize)]
+struct RawOutput {
+    output_type: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    text: MultiLine,
+    #[serde(default)]
+    data: HashMap<String, Value>,
+    #[serde(default)]
+    execution_count: Option<u32>,
+    #[serde(default)]
+    ename: String,
+    #[serde(default)]
+    evalue: String,
+    #[serde(default)]
+    traceback: Vec<String>,
+}
+
+/// 解析 .ipynb 文件
+pub fn parse(path: &str) -> Result<Notebook, DocsError> {
+    let size = fs::metadata(path)?.len();
+    if size > MAX_PART_SIZE {
+        return Err(DocsError::LimitExceeded {
+            limit: format!("文件大小 {} 字节", size),
+            part: None,
+        });
+    }
+    let data = fs::read(path)?;
+    let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(&data);
+    let raw: RawNotebook =
+        serde_json::from_slice(data).map_err(|e| DocsError::corrupt(e.to_string()))?;
+    if raw.nbformat != 4 {
+        return Err(DocsError::Unsupported(format!("nbformat {}", raw.nbformat)));
+    }
+
+    let metadata = &raw.metadata;
+    let language = str_at(metadata, &["kernelspec", "language"])
+        .or_else(|| str_at(metadata, &["language_info", "name"]));
+    let authors = metadata
+        .get("authors")
+        .and_then(Value::as_array)
+        .map(|authors| {
+            authors
+                .iter()
+                .filter_map(|a| a.get("name").and_then(Value::as_str).or(a.as_str()))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let cells = raw
+        .cells
+        .into_iter()
+        .filter_map(|cell| convert_cell(cell, language.as_deref()))
+        .collect();
+    Ok(Notebook {
+        nbformat: raw.nbformat,
+        nbformat_minor: raw.nbformat_minor,
+        kernel: str_at(metadata, &["kernelspec", "display_name"]),
+        title: str_at(metadata, &["title"]),
+        authors,
+        language,
+        cells,
+    })
+}
+
+/// 未知类型的单元格跳过
+fn convert_cell(cell: RawCell, language: Option<&str>) -> Option<NotebookCell> {
+    let source = cell.source.into_string();
+    match cell.cell_type.as_str() {
+        "markdown" => Some(NotebookCell::Markdown {
+            source: resolve_attachments(source, &cell.attachments),
+        }),
+        "code" => Some(NotebookCell::Code {
+            source,
+            // VS Code 等编辑器允许单元格使用与内核不同的语言
+            language: str_at(&cell.metadata, &["vscode", "languageId"])
+                .or_else(|| language.map(str::to_string)),
+            execution_count: cell.execution_count,
+            outputs: convert_outputs(cell.outputs),
+        }),
+        "raw" => Some(NotebookCell::Raw {
+            source,
+            format: str_at(&cell.metadata, &["format"])
+                .or_else(|| str_at(&cell.metadata, &["raw_mimetype"])),
+        }),
+        other => {
+            log::warn!("跳过未知类型的单元格: {}", other);
+            None
+        },
+    }
+}
+
+fn convert_outputs(outputs: Vec<RawOutput>) -> Vec<CellOutput> {
+    let mut result: Vec<CellOutput> = Vec::with_capacity(outputs.len());
+    for output in outputs {
+        match output.output_type.as_str() {
+            "stream" => {
+                let text = output.text.into_string();
+                if let Some(CellOutput::Stream { name, text: last }) = result.last_mut() {
+                    if *name == output.name {
+                        last.push_str(&text);
+                        continue;
+                    }
+                }
+                result.push(CellOutput::Stream { name: output.name, text });
+            },
+            "execute_result" | "display_data" => {
+                let text = |mime: &str| output.data.get(mime).and_then(multiline);
+                let image = ["image/png", "image/jpeg"].iter().find_map(|mime| {
+                    text(mime).map(|data| {
+                        let data: String = data.split_whitespace().collect();
+                        format!("data:{};base64,{}", mime, data)
+                    })
+                });
+                let data = OutputData {
+                    plain: text("text/plain").map(|t| strip_ansi(&t)),
+                    html: text("text/html"),
+                    image,
+                };
+                if data.plain.is_some() || data.html.is_some() || data.image.is_some() {
+                    result.push(CellOutput::Data {
+                        execution_count: output.execution_count,
+                        data,
+                    });
+                }
+            },
+            "error" => result.push(CellOutput::Error {
+                ename: output.ename,
+                evalue: strip_ansi(&output.evalue),
+                traceback: strip_ansi(&output.traceback.join("\n")),
+            }),
+            other => log::warn!("跳过未知类型的输出: {}", other),
+        }
+    }
+    // 合并后再处理回车，进度条的多次刷新可能分散在不同输出中
+    for output in &mut result {
+        if let CellOutput::Stream { text, .. } = output {
+            *text = apply_carriage_returns(&strip_ansi(text));
+        }
+    }
+    result
+}
+
+/// 读取嵌套的字符串字段
+fn str_at(value: &Value, path: &[&str]) -> Option<String> {
+    path.iter()
+        .try_fold(value, |value, key| value.get(key))?
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// MIME 数据同样可以是字符串或字符串数组
+fn multiline(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Array(lines) => Some(lines.iter().filter_map(Value::as_str).collect()),
+        _ => None,
+    }
+}
+
+/// 将 Markdown 中的 `attachment:文件名` 替换为 data URI
+fn resolve_attachments(
+    mut source: String,
+    attachments: &HashMap<String, HashMap<String, Value>>,
+) -> String {
+    for (name, bundle) in attachments {
+        let image = bundle
+            .iter()
+            .filter(|(mime, _)| mime.starts_with("image/") && *mime != "image/svg+xml")
+            .find_map(|(mime, data)| multiline(data).map(|data| (mime, data)));
+        if let Some((mime, data)) = image {
+            let data: String = data.split_whitespace().collect();
+            let reference = format!("attachment:{}", name);
+            source = source.replace(&reference, &format!("data:{};base64,{}", mime, data));
+        }
+    }
+    source
+}
+
+/// 去除 ANSI 转义序列：CSI（颜色、光标）、OSC（标题、超链接）及其他两字节序列
+fn strip_ansi(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('[') => {
+                // 参数与中间字节之后，以 0x40..=0x7E 的字节结束
+                for c in chars.by_ref() {
+                    if ('\u{40}'..='\u{7e}').contains(&c) {
+                        break;
+                    }
+                }
+            },
+            Some(']') => {
+                // 以 BEL 或 ESC \ 结束
+                while let Some(c) = chars.next() {
+                    if c == '\u{7}' {
+                        break;
+                    }
+                    if c == '\u{1b}' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+    result
+}
+
+/// 模拟终端的回车：每行只保留最后一个 `\r` 之后的内容，常见于进度条
+fn apply_carriage_returns(text: &str) -> String {
+    if !text.contains('\r') {
+        return text.to_string();
+    }
+    text.replace("\r\n", "\n")
+        .split('\n')
+        .map(|line| {
+            line.trim_end_matches('\r')
+                .rsplit('\r')
+                .next()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl From<&Notebook> for DocumentMetadata {
+    fn from(notebook: &Notebook) -> Self {
+        DocumentMetadata {
+            title: notebook.title.clone(),
+            author: notebook.authors.first().cloned(),
+            application: notebook.kernel.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ansi_sequences_are_stripped() {
+        assert_eq!(strip_ansi("\x1b[1;31mError\x1b[0m: bad"), "Error: bad");
+        assert_eq!(strip_ansi("\x1b[2K\x1b[1Gdone"), "done");
+        // OSC 以 BEL 或 ESC \ 结束
+        assert_eq!(strip_ansi("\x1b]0;title\x07text"), "text");
+        assert_eq!(
+            strip_ansi("\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\"),
+            "link"
+        );
+        assert_eq!(strip_ansi("a\x1bcb"), "ab");
+        assert_eq!(strip_ansi("中文\x1b[32m绿色"), "中文绿色");
+        // 未结束的序列不会吞掉之前的内容
+        assert_eq!(strip_ansi("tail\x1b["), "tail");
+    }
+
+    #[test]
+    fn carriage_returns_keep_last_segment() {
+        assert_eq!(apply_carriage_returns("no returns"), "no returns");
+        assert_eq!(
+            apply_carriage_returns(" 10%\r 50%\r100%\ndone"),
+            "100%\ndone"
+        );
+        // Windows 换行与行尾回车不清空整行
+        assert_eq!(apply_carriage_returns("a\r\nb\r\n"), "a\nb\n");
+        assert_eq!(apply_carriage_returns("line\r"), "line");
+        assert_eq!(apply_carriage_returns("old\rnew\r\r\nnext"), "new\nnext");
+    }
+}
@@ -0,0 +1,700 @@
+//! Excel 数字格式代码的显示格式化
+//!
+//! 支持常见的格式：常规、小数位、千分位、百分比、科学计数、货币/文字前后缀、
+//! 多段格式（正;负;零）以及日期时间。不支持的写法按常规格式输出。
+
+use chrono::{Datelike, NaiveDateTime, Timelike};
+
+/// 内置格式 id 对应的格式代码
+pub fn builtin_format(id: u32) -> Option<&'static str> {
+    Some(match id {
+        0 => "General",
+        1 => "0",
+        2 => "0.00",
+        3 => "#,##0",
+        4 => "#,##0.00",
+        9 => "0%",
+        10 => "0.00%",
+        11 => "0.00E+00",
+        12 => "# ?/?",
+        13 => "# ??/??",
+        14 => "yyyy-mm-dd",
+        15 => "d-mmm-yy",
+        16 => "d-mmm",
+        17 => "mmm-yy",
+        18 => "h:mm AM/PM",
+        19 => "h:mm:ss AM/PM",
+        20 => "h:mm",
+        21 => "h:mm:ss",
+        22 => "yyyy-mm-dd h:mm",
+        37 => "#,##0 ;(#,##0)",
+        38 => "#,##0 ;[Red](#,##0)",
+        39 => "#,##0.00;(#,##0.00)",
+        40 => "#,##0.00;[Red](#,##0.00)",
+        45 => "mm:ss",
+        46 => "[h]:mm:ss",
+        47 => "mmss.0",
+        48 => "##0.0E+0",
+        49 => "@",
+        _ => return None,
+    })
+}
+
+/// 格式代码中的一个片段
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Literal(String),
+    /// 数字占位部分（`0`、`#`、`?`、`,`、`.`、`E+` 等）
+    Number(String),
+    /// 日期时间占位（已转小写），如 `yyyy`、`mm`、`[h]`、`am/pm`
+    Date(String),
+    Percent,
+    /// `@` 文本占位
+    Text,
+}
+
+/// 按格式代码格式化数值
+pub fn format_number(value: f64, code: &str) -> String {
+    let sections = split_sections(code);
+    let (section, value) = select_section(&sections, value);
+    let tokens = tokenize(section);
+
+    if tokens.is_empty() || section.trim().eq_ignore_ascii_case("general") {
+        return general(value);
+    }
+    if tokens.iter().any(|t| matches!(t, Token::Date(_))) {
+        return format_date_tokens(&tokens, value, None);
+    }
+    format_number_tokens(&tokens, value)
+}
+
+/// 按格式代码格式化日期时间；格式中没有日期占位时使用默认格式
+pub fn format_datetime(datetime: NaiveDateTime, serial: f64, code: Option<&str>) -> String {
+    if let Some(code) = code {
+        let sections = split_sections(code);
+        let tokens = tokenize(sections[0]);
+        if tokens.iter().any(|t| matches!(t, Token::Date(_))) {
+            return format_date_tokens(&tokens, serial, Some(datetime));
+        }
+    }
+    default_datetime(datetime, serial)
+}
+
+/// 日期时间的默认显示：整数日期只显示日期，小于一天只显示时间
+pub fn default_datetime(datetime: NaiveDateTime, serial: f64) -> String {
+    if serial.fract() == 0.0 {
+        datetime.format("%Y-%m-%d").to_string()
+    } else if (0.0..1.0).contains(&serial) {
+        datetime.format("%H:%M:%S").to_string()
+    } else {
+        datetime.format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+}
+
+/// 时长显示为 `[h]:mm:ss`
+pub fn format_duration(seconds: f64) -> String {
+    let negative = seconds < 0.0;
+    let total = seconds.abs().round() as u64;
+    format!(
+        "{}{}:{:02}:{:02}",
+        if negative { "-" } else { "" },
+        total / 3600,
+        total / 60 % 60,
+        total % 60
+    )
+}
+
+/// 常规格式：最多 11 位有效数字，去掉多余的 0；带小数且整数部分超过 11 位时使用科学计数
+pub fn general(value: f64) -> String {
+    if value.is_nan() {
+        return "#NUM!".to_string();
+    }
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        return format!("{}", value as i64);
+    }
+    let abs = value.abs();
+    if abs != 0.0 && !(1e-9..1e11).contains(&abs) {
+        let formatted = format!("{:.5E}", value);
+        return trim_exponent(&formatted);
+    }
+    let digits = 10usize.saturating_sub(abs.log10().floor().max(0.0) as usize);
+    let formatted = format!("{:.*}", digits, value);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+    if trimmed == "-0" {
+        "0".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// `1.50000E3` -> `1.5E+03`
+fn trim_exponent(formatted: &str) -> String {
+    let Some((mantissa, exponent)) = formatted.split_once('E') else {
+        return formatted.to_string();
+    };
+    let mantissa = if mantissa.contains('.') {
+        mantissa.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        mantissa
+    };
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    format!(
+        "{}E{}{:02}",
+        mantissa,
+        if exponent < 0 { '-' } else { '+' },
+        exponent.abs()
+    )
+}
+
+/// 按未被引号包围的 `;` 拆分格式段
+fn split_sections(code: &str) -> Vec<&str> {
+    let mut sections = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in code.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' => escaped = true,
+            '"' => quoted = !quoted,
+            ';' if !quoted => {
+                sections.push(&code[start..i]);
+                start = i + 1;
+            },
+            _ => {},
+        }
+    }
+    sections.push(&code[start..]);
+    sections
+}
+
+/// 选择正数/负数/零对应的格式段，使用负数段时数值取绝对值
+fn select_section<'a>(sections: &[&'a str], value: f64) -> (&'a str, f64) {
+    match sections.len() {
+        1 => (sections[0], value),
+        _ if value < 0.0 => (sections[1], value.abs()),
+        n if value == 0.0 && n >= 3 => (sections[2], value),
+        _ => (sections[0], value),
+    }
+}
+
+fn tokenize(section: &str) -> Vec<Token> {
+    let chars: Vec<char> = section.chars().collect();
+    let mut tokens = Vec::new();
+    let mut literal = String::new();
+    let mut i = 0;
+
+    let push_literal = |tokens: &mut Vec<Token>, literal: &mut String| {
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(std::mem::take(literal)));
+        }
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '"' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    literal.push(chars[i]);
+                    i += 1;
+                }
+            },
+            '\\' => {
+                if let Some(&next) = chars.get(i + 1) {
+                    literal.push(next);
+                    i += 1;
+                }
+            },
+            // `_x` 表示与 x 等宽的空白，`*x` 表示用 x 填充
+            '_' => {
+                literal.push(' ');
+                i += 1;
+            },
+            '*' => i += 1,
+            '[' => {
+                let end = chars[i..].iter().position(|&c| c == ']').map(|p| i + p);
+                let Some(end) = end else {
+                    break;
+                };
+                let inner: String = chars[i + 1..end].iter().collect();
+                let lower = inner.to_ascii_lowercase();
+                if let Some(currency) = inner.strip_prefix('$') {
+                    // [$€-407]：取 `-` 前的货币符号
+                    literal.push_str(currency.split('-').next().unwrap_or_default());
+                } else if matches!(lower.as_str(), "h" | "hh" | "m" | "mm" | "s" | "ss") {
+                    push_literal(&mut tokens, &mut literal);
+                    tokens.push(Token::Date(format!("[{}]", lower)));
+                }
+                // 颜色与条件直接忽略
+                i = end;
+            },
+            '%' => {
+                push_literal(&mut tokens, &mut literal);
+                tokens.push(Token::Percent);
+            },
+            '@' => {
+                push_literal(&mut tokens, &mut literal);
+                tokens.push(Token::Text);
+            },
+            '0' | '#' | '?' | '.' | ',' => {
+                push_literal(&mut tokens, &mut literal);
+                let mut number = String::new();
+                while i < chars.len() {
+                    match chars[i] {
+                        c @ ('0'..='9' | '#' | '?' | '.' | ',') => number.push(c),
+                        'E' | 'e' if matches!(chars.get(i + 1), Some('+' | '-')) => {
+                            number.push('E');
+                            number.push(chars[i + 1]);
+                            i += 1;
+                        },
+                        _ => break,
+                    }
+                    i += 1;
+                }
+                // 日期格式中秒的小数部分（`ss.0`）
+                if matches!(tokens.last(), Some(Token::Date(d)) if d.starts_with('s'))
+                    && number.starts_with('.')
+                {
+                    tokens.push(Token::Date(number));
+                } else if number == "," || number == "." {
+                    literal.push_str(&number);
+                } else {
+                    tokens.push(Token::Number(number));
+                }
+                continue;
+            },
+            'a' | 'A' if starts_with_ignore_case(&chars[i..], "am/pm") => {
+                push_literal(&mut tokens, &mut literal);
+                tokens.push(Token::Date("am/pm".to_string()));
+                i += 5;
+                continue;
+            },
+            'a' | 'A' if starts_with_ignore_case(&chars[i..], "a/p") => {
+                push_literal(&mut tokens, &mut literal);
+                tokens.push(Token::Date("a/p".to_string()));
+                i += 3;
+                continue;
+            },
+            'y' | 'Y' | 'm' | 'M' | 'd' | 'D' | 'h' | 'H' | 's' | 'S' => {
+                push_literal(&mut tokens, &mut literal);
+                let lower = c.to_ascii_lowercase();
+                let mut run = String::new();
+                while i < chars.len() && chars[i].to_ascii_lowercase() == lower {
+                    run.push(lower);
+                    i += 1;
+                }
+                tokens.push(Token::Date(run));
+                continue;
+            },
+            // 常见的直接显示字符
+            c => literal.push(c),
+        }
+        i += 1;
+    }
+    push_literal(&mut tokens, &mut literal);
+
+    // General 之外的纯文字段，例如 "General" 拼写在文字里
+    if tokens.len() == 1 {
+        if let Token::Literal(text) = &tokens[0] {
+            if text.eq_ignore_ascii_case("general") {
+                return Vec::new();
+            }
+        }
+    }
+    tokens
+}
+
+fn starts_with_ignore_case(chars: &[char], pattern: &str) -> bool {
+    chars.len() >= pattern.len()
+        && chars
+            .iter()
+            .zip(pattern.chars())
+            .all(|(a, b)| a.to_ascii_lowercase() == b)
+}
+
+fn format_number_tokens(tokens: &[Token], value: f64) -> String {
+    let percent = tokens.iter().filter(|t| **t == Token::Percent).count();
+    let value = value * 100f64.powi(percent as i32);
+    // 多个数字片段（如分数）时只按第一个格式化
+    let mut formatted_number = false;
+    let mut out = String::new();
+    for token in tokens {
+        match token {
+            Token::Literal(text) => out.push_str(text),
+            Token::Percent => out.push('%'),
+            Token::Text => out.push_str(&general(value)),
+            Token::Number(pattern) if !formatted_number => {
+                out.push_str(&format_pattern(value, pattern));
+                formatted_number = true;
+            },
+            Token::Number(_) | Token::Date(_) => {},
+        }
+    }
+    out
+}
+
+/// 按数字占位格式化，如 `#,##0.00`、`0.00E+00`
+fn format_pattern(value: f64, pattern: &str) -> String {
+    if let Some((mantissa, exponent)) = pattern.split_once('E') {
+        return format_scientific(value, mantissa, exponent);
+    }
+
+    // 末尾的逗号表示按千缩放
+    let trimmed = pattern.trim_end_matches(',');
+    let scale = pattern.len() - trimmed.len();
+    let value = value / 1000f64.powi(scale as i32);
+
+    let (integer_pattern, decimal_pattern) = trimmed.split_once('.').unwrap_or((trimmed, ""));
+    let grouping = integer_pattern.contains(',');
+    let min_integer = integer_pattern.chars().filter(|c| *c == '0').count();
+    let required_decimals = decimal_pattern.chars().filter(|c| *c == '0').count();
+    let max_decimals = decimal_pattern
+        .chars()
+        .filter(|c| matches!(c, '0' | '#' | '?'))
+        .count();
+
+    let formatted = format!("{:.*}", max_decimals, value.abs());
+    let (int_part, dec_part) = formatted.split_once('.').unwrap_or((&formatted, ""));
+
+    let mut dec_part = dec_part.to_string();
+    while dec_part.len() > required_decimals && dec_part.ends_with('0') {
+        dec_part.pop();
+    }
+
+    let int_part = int_part.trim_start_matches('0');
+    let mut int_part = if int_part.len() < min_integer {
+        format!("{}{}", "0".repeat(min_integer - int_part.len()), int_part)
+    } else {
+        int_part.to_string()
+    };
+    if grouping {
+        int_part = group_thousands(&int_part);
+    }
+
+    let mut out = String::new();
+    let is_zero =
+        int_part.chars().all(|c| c == '0' || c == ',') && dec_part.chars().all(|c| c == '0');
+    if value < 0.0 && !is_zero {
+        out.push('-');
+    }
+    out.push_str(&int_part);
+    if !dec_part.is_empty() {
+        out.push('.');
+        out.push_str(&dec_part);
+    } else if decimal_pattern.is_empty() && trimmed.ends_with('.') {
+        out.push('.');
+    }
+    if out.is_empty() || out == "-" {
+        out.push('0');
+    }
+    out
+}
+
+/// 科学计数：指数位数取自 `E` 之后的 `0`，整数部分有多个占位时指数取其倍数（如 `##0.0E+0`）
+fn format_scientific(value: f64, mantissa: &str, exponent: &str) -> String {
+    let placeholder = |c: &char| matches!(c, '0' | '#' | '?');
+    let (integer, decimal) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let step = integer.chars().filter(placeholder).count().max(1) as i32;
+    let decimals = decimal.chars().filter(placeholder).count();
+    let exponent_digits = exponent.chars().filter(|c| *c == '0').count().max(1);
+
+    let mut power = if value == 0.0 || !value.is_finite() {
+        0
+    } else {
+        (value.abs().log10().floor() as i32).div_euclid(step) * step
+    };
+    let mut text = format!("{:.*}", decimals, value / 10f64.powi(power));
+    // 舍入后进位到下一档，如 9.99 按 0.0E+0 显示为 1.0E+1
+    if text
+        .trim_start_matches('-')
+        .split('.')
+        .next()
+        .unwrap_or("")
+        .len()
+        > step as usize
+    {
+        power += step;
+        text = format!("{:.*}", decimals, value / 10f64.powi(power));
+    }
+    let sign = match (power < 0, exponent.starts_with('+')) {
+        (true, _) => "-",
+        (false, true) => "+",
+        (false, false) => "",
+    };
+    format!(
+        "{}E{}{:0width$}",
+        text,
+        sign,
+        power.abs(),
+        width = exponent_digits
+    )
+}
+
+fn group_thousands(digits: &str) -> String {
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+fn format_date_tokens(tokens: &[Token], serial: f64, datetime: Option<NaiveDateTime>) -> String {
+    let Some(mut datetime) = datetime.or_else(|| serial_to_datetime(serial)) else {
+        return general(serial);
+    };
+    // 没有秒的小数部分时按四舍五入到秒显示
+    let subsecond = tokens
+        .iter()
+        .any(|t| matches!(t, Token::Date(d) if d.starts_with('.')));
+    if !subsecond {
+        datetime = (datetime + chrono::Duration::milliseconds(500))
+            .with_nanosecond(0)
+            .unwrap_or(datetime);
+    }
+    let seconds = (serial * 86400.0).round() as i64;
+    let twelve_hour = tokens
+        .iter()
+        .any(|t| matches!(t, Token::Date(d) if d == "am/pm" || d == "a/p"));
+
+    let mut out = String::new();
+    for (index, token) in tokens.iter().enumerate() {
+        let Token::Date(part) = token else {
+            match token {
+                Token::Literal(text) => out.push_str(text),
+                Token::Percent => out.push('%'),
+                _ => {},
+            }
+            continue;
+        };
+
+        // `m` 紧跟在小时之后或位于秒之前时表示分钟
+        let is_minute = part.starts_with('m')
+            && (previous_date(tokens, index)
+                .is_some_and(|p| p.starts_with('h') || p.starts_with("[h"))
+                || next_date(tokens, index)
+                    .is_some_and(|n| n.starts_with('s') || n.starts_with("[s")));
+
+        match part.as_str() {
+            "yy" | "y" => out.push_str(&format!("{:02}", datetime.year() % 100)),
+            p if p.starts_with('y') => out.push_str(&format!("{:04}", datetime.year())),
+            "m" | "mm" if is_minute => {
+                pad(&mut out, datetime.minute(), part.len());
+            },
+            "m" | "mm" => pad(&mut out, datetime.month(), part.len()),
+            "mmm" => out.push_str(&MONTHS[datetime.month0() as usize][..3]),
+            "mmmmm" => out.push_str(&MONTHS[datetime.month0() as usize][..1]),
+            p if p.starts_with("mmmm") => out.push_str(MONTHS[datetime.month0() as usize]),
+            "d" | "dd" => pad(&mut out, datetime.day(), part.len()),
+            "ddd" => {
+                out.push_str(&WEEKDAYS[datetime.weekday().num_days_from_monday() as usize][..3])
+            },
+            p if p.starts_with("dddd") => {
+                out.push_str(WEEKDAYS[datetime.weekday().num_days_from_monday() as usize])
+            },
+            "h" | "hh" => {
+                let hour = if twelve_hour {
+                    match datetime.hour() % 12 {
+                        0 => 12,
+                        h => h,
+                    }
+                } else {
+                    datetime.hour()
+                };
+                pad(&mut out, hour, part.len());
+            },
+            "s" | "ss" => pad(&mut out, datetime.second(), part.len()),
+            "[h]" | "[hh]" => out.push_str(&(seconds.div_euclid(3600)).to_string()),
+            "[m]" | "[mm]" => out.push_str(&(seconds.div_euclid(60)).to_string()),
+            "[s]" | "[ss]" => out.push_str(&seconds.to_string()),
+            "am/pm" => out.push_str(if datetime.hour() < 12 { "AM" } else { "PM" }),
+            "a/p" => out.push(if datetime.hour() < 12 { 'A' } else { 'P' }),
+            p if p.starts_with('.') => {
+                let digits = p.len() - 1;
+                let fraction = datetime.nanosecond() as f64 / 1e9;
+                let formatted = format!("{:.*}", digits, fraction);
+                out.push_str(formatted.trim_start_matches('0'));
+            },
+            other => out.push_str(other),
+        }
+    }
+    out
+}
+
+fn previous_date(tokens: &[Token], index: usize) -> Option<&str> {
+    tokens[..index].iter().rev().find_map(|t| match t {
+        Token::Date(d) => Some(d.as_str()),
+        _ => None,
+    })
+}
+
+fn next_date(tokens: &[Token], index: usize) -> Option<&str> {
+    tokens[index + 1..].iter().find_map(|t| match t {
+        Token::Date(d) => Some(d.as_str()),
+        _ => None,
+    })
+}
+
+fn pad(out: &mut String, value: u32, width: usize) {
+    if width >= 2 {
+        out.push_str(&format!("{:02}", value));
+    } else {
+        out.push_str(&value.to_string());
+    }
+}
+
+/// 1900 日期系统的序列值转日期时间（含 1900-02-29 的兼容处理）
+fn serial_to_datetime(serial: f64) -> Option<NaiveDateTime> {
+    if !(0.0..2_958_466.0).contains(&serial) {
+        return None;
+    }
+    let epoch = chrono::NaiveDate::from_ymd_opt(1899, 12, 30)?.and_hms_opt(0, 0, 0)?;
+    let serial = if serial < 60.0 { serial + 1.0 } else { serial };
+    let ms = (serial * 86_400_000.0).round() as i64;
+    epoch.checked_add_signed(chrono::Duration::milliseconds(ms))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn general_switches_to_scientific_above_eleven_integer_digits() {
+        assert_eq!(general(12345678901.5), "12345678902");
+        assert_eq!(general(99999999999.4), "99999999999");
+        assert_eq!(general(123456789012.5), "1.23457E+11");
+        assert_eq!(general(-123456789012345.5), "-1.23457E+14");
+        assert_eq!(general(1e15 + 0.5), "1E+15");
+    }
+
+    #[test]
+    fn general_keeps_integers_and_trims_fractions() {
+        assert_eq!(general(123456789012.0), "123456789012");
+        assert_eq!(general(-42.0), "-42");
+        assert_eq!(general(1234.5678), "1234.5678");
+        assert_eq!(general(0.1 + 0.2), "0.3");
+        assert_eq!(general(1.5e-10), "1.5E-10");
+        assert_eq!(general(-1e-12), "-1E-12");
+        assert_eq!(general(f64::NAN), "#NUM!");
+    }
+
+    #[test]
+    fn scientific_exponent_digits_and_engineering() {
+        assert_eq!(format_number(12345.678, "0.00E+00"), "1.23E+04");
+        assert_eq!(format_number(0.000123, "0.00E+00"), "1.23E-04");
+        assert_eq!(format_number(0.0, "0.00E+00"), "0.00E+00");
+        assert_eq!(format_number(9.99, "0.0E+0"), "1.0E+1");
+        assert_eq!(format_number(-12345.0, "0.0E-0"), "-1.2E4");
+        assert_eq!(format_number(12345.0, "##0.0E+0"), "12.3E+3");
+        assert_eq!(format_number(0.00012345, "##0.0E+0"), "123.5E-6");
+        assert_eq!(format_number(999999.0, "##0.0E+0"), "1.0E+6");
+    }
+
+    #[test]
+    fn times_round_to_the_second() {
+        // 29 分 59.997 秒
+        assert_eq!(format_number(0.0208333, "mm:ss"), "30:00");
+        assert_eq!(format_number(1.4999999, "[h]:mm:ss"), "36:00:00");
+        assert_eq!(format_number(0.0208333, "[mm]:ss"), "30:00");
+        assert_eq!(format_number(0.5000001, "h:mm:ss.00"), "12:00:00.01");
+    }
+
+    #[test]
+    fn number_formats() {
+        assert_eq!(format_number(1234.567, "0"), "1235");
+        assert_eq!(format_number(1234.567, "0.00"), "1234.57");
+        assert_eq!(format_number(1234567.891, "#,##0"), "1,234,568");
+        assert_eq!(format_number(-1234.5, "#,##0.00"), "-1,234.50");
+        assert_eq!(format_number(7.0, "000"), "007");
+        assert_eq!(format_number(0.5, "#.##"), ".5");
+        assert_eq!(format_number(1234567.0, "#,##0.0,"), "1,234.6");
+        assert_eq!(format_number(-0.001, "0.00"), "0.00");
+    }
+
+    #[test]
+    fn percent_literals_and_sections() {
+        assert_eq!(format_number(0.1234, "0%"), "12%");
+        assert_eq!(format_number(0.12345, "0.00%"), "12.35%");
+        assert_eq!(format_number(5.0, "\"¥\"#,##0.00"), "¥5.00");
+        assert_eq!(format_number(5.0, "$#,##0_);($#,##0)"), "$5 ");
+        assert_eq!(format_number(-1234.0, "#,##0 ;(#,##0)"), "(1,234)");
+        assert_eq!(format_number(-5.0, "[Red]0.0"), "-5.0");
+        assert_eq!(format_number(0.0, "0.00;-0.00;\"zero\""), "zero");
+        assert_eq!(format_number(-5.0, "0.00;-0.00;\"zero\""), "-5.00");
+        assert_eq!(format_number(3.0, "@"), "3");
+        assert_eq!(format_number(45000.0, "General"), "45000");
+    }
+
+    #[test]
+    fn date_formats() {
+        assert_eq!(
+            format_number(45000.75, "yyyy-mm-dd h:mm"),
+            "2023-03-15 18:00"
+        );
+        assert_eq!(format_number(45000.0, "d-mmm-yy"), "15-Mar-23");
+        assert_eq!(
+            format_number(45000.0, "dddd, mmmm d"),
+            "Wednesday, March 15"
+        );
+        assert_eq!(
+            format_number(45000.0, "yyyy\"年\"m\"月\"d\"日\""),
+            "2023年3月15日"
+        );
+        assert_eq!(format_number(0.75, "h:mm AM/PM"), "6:00 PM");
+        assert_eq!(format_number(0.0, "h:mm AM/PM"), "12:00 AM");
+        // 1900 年 2 月 29 日的兼容处理
+        assert_eq!(format_number(59.0, "yyyy-mm-dd"), "1900-02-28");
+        assert_eq!(format_number(61.0, "yyyy-mm-dd"), "1900-03-01");
+        assert_eq!(format_number(-1.0, "yyyy-mm-dd"), "-1");
+    }
+
+    #[test]
+    fn builtin_formats() {
+        let format = |value, id| format_number(value, builtin_format(id).unwrap());
+        assert_eq!(format(1234.5, 4), "1,234.50");
+        assert_eq!(format(0.256, 9), "26%");
+        assert_eq!(format(12345.0, 11), "1.23E+04");
+        assert_eq!(format(45000.0, 14), "2023-03-15");
+        assert_eq!(format(1.5, 46), "36:00:00");
+        assert_eq!(format(12345.0, 48), "12.3E+3");
+        assert_eq!(builtin_format(5), None);
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(format_duration(3661.4), "1:01:01");
+        assert_eq!(format_duration(-59.6), "-0:01:00");
+        assert_eq!(format_duration(360000.0), "100:00:00");
+    }
+}
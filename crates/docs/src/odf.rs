@@ -0,0 +1,675 @@
+use crate::{
+    document::{
+        plain_text, Block, Document, Image, Inline, ListInfo, Note, Paragraph, Run, Table,
+        TableCell, TableRow,
+    },
+    error::DocsError,
+    metadata::DocumentMetadata,
+    ooxml::{attr, child, is, Package},
+    slides::{Frame, Presentation, Shape, ShapeContent, Slide},
+};
+use roxmltree::Node;
+use std::collections::HashMap;
+
+/// 表格单元格重复次数上限，防止 `number-columns-repeated` 撑爆内存
+const MAX_REPEAT: usize = 256;
+
+/// 样式中与文字相关的属性，未设置的为 `None`，用于沿父样式链合并
+#[derive(Debug, Clone, Default)]
+struct TextProps {
+    bold: Option<bool>,
+    italic: Option<bool>,
+    underline: Option<bool>,
+    strike: Option<bool>,
+    superscript: Option<bool>,
+    subscript: Option<bool>,
+    color: Option<String>,
+    size: Option<f32>,
+    font: Option<String>,
+}
+
+impl TextProps {
+    /// 用 `other` 中已设置的属性覆盖当前属性
+    fn merge(&mut self, other: &TextProps) {
+        fn take<T: Clone>(target: &mut Option<T>, value: &Option<T>) {
+            if value.is_some() {
+                target.clone_from(value);
+            }
+        }
+        take(&mut self.bold, &other.bold);
+        take(&mut self.italic, &other.italic);
+        take(&mut self.underline, &other.underline);
+        take(&mut self.strike, &other.strike);
+        take(&mut self.superscript, &other.superscript);
+        take(&mut self.subscript, &other.subscript);
+        take(&mut self.color, &other.color);
+        take(&mut self.size, &other.size);
+        take(&mut self.font, &other.font);
+    }
+
+    fn apply(&self, run: &mut Run) {
+        run.bold = self.bold.unwrap_or(false);
+        run.italic = self.italic.unwrap_or(false);
+        run.underline = self.underline.unwrap_or(false);
+        run.strike = self.strike.unwrap_or(false);
+        run.superscript = self.superscript.unwrap_or(false);
+        run.subscript = self.subscript.unwrap_or(false);
+        run.color = self.color.clone();
+        run.size = self.size;
+        run.font = self.font.clone();
+    }
+}
+
+#[derive(Debug, Default)]
+struct Style {
+    display_name: Option<String>,
+    parent: Option<String>,
+    text: TextProps,
+    align: Option<String>,
+    outline_level: Option<u8>,
+    hidden: bool,
+}
+
+/// content.xml 与 styles.xml 中的样式
+#[derive(Debug, Default)]
+struct Styles {
+    styles: HashMap<String, Style>,
+    /// 列表样式名 -> 各层级是否为编号
+    lists: HashMap<String, Vec<bool>>,
+}
+
+impl Styles {
+    fn load(&mut self, doc: &roxmltree::Document) {
+        for node in doc.descendants().filter(|n| is(n, "style")) {
+            let Some(name) = attr(&node, "name") else {
+                continue;
+            };
+            let mut style = Style {
+                display_name: attr(&node, "display-name").map(str::to_string),
+                parent: attr(&node, "parent-style-name").map(str::to_string),
+                outline_level: attr(&node, "default-outline-level").and_then(|v| v.parse().ok()),
+                ..Default::default()
+            };
+            if let Some(props) = child(&node, "text-properties") {
+                style.text = text_props(&props);
+            }
+            if let Some(props) = child(&node, "paragraph-properties") {
+                style.align = attr(&props, "text-align").and_then(align);
+            }
+            if let Some(props) = child(&node, "drawing-page-properties") {
+                style.hidden = attr(&props, "visibility") == Some("hidden");
+            }
+            self.styles.insert(name.to_string(), style);
+        }
+
+        for node in doc.descendants().filter(|n| is(n, "list-style")) {
+            let Some(name) = attr(&node, "name") else {
+                continue;
+            };
+            let mut levels = Vec::new();
+            for level in node.children().filter(|n| n.is_element()) {
+                let index = attr(&level, "level")
+                    .and_then(|v| v.parse::<usize>().ok())
+                    .unwrap_or(levels.len() + 1);
+                if levels.len() < index {
+                    levels.resize(index, false);
+                }
+                // 编号格式为空时视为无编号
+                levels[index - 1] = is(&level, "list-level-style-number")
+                    && attr(&level, "num-format").is_some_and(|f| !f.is_empty());
+            }
+            self.lists.insert(name.to_string(), levels);
+        }
+    }
+
+    /// 沿父样式链（最多 16 层）查找
+    fn chain<'a>(&'a self, name: Option<&str>) -> impl Iterator<Item = &'a Style> {
+        let mut next = name.and_then(|n| self.styles.get(n));
+        std::iter::from_fn(move || {
+            let current = next?;
+            next = current.parent.as_deref().and_then(|p| self.styles.get(p));
+            Some(current)
+        })
+        .take(16)
+    }
+
+    fn text_props(&self, name: Option<&str>) -> TextProps {
+        let chain: Vec<_> = self.chain(name).collect();
+        let mut props = TextProps::default();
+        for style in chain.iter().rev() {
+            props.merge(&style.text);
+        }
+        props
+    }
+
+    fn align(&self, name: Option<&str>) -> Option<String> {
+        self.chain(name).find_map(|s| s.align.clone())
+    }
+
+    fn outline_level(&self, name: Option<&str>) -> Option<u8> {
+        self.chain(name).find_map(|s| s.outline_level)
+    }
+
+    fn display_name(&self, name: &str) -> String {
+        self.styles
+            .get(name)
+            .and_then(|s| s.display_name.clone())
+            .unwrap_or_else(|| name.replace("_20_", " "))
+    }
+
+    fn is_ordered(&self, list_style: Option<&str>, level: u8) -> bool {
+        list_style
+            .and_then(|s| self.lists.get(s))
+            .and_then(|levels| levels.get(level as usize).copied())
+            .unwrap_or(false)
+    }
+}
+
+/// 列表上下文：列表样式与嵌套层级
+#[derive(Debug, Clone, Copy)]
+struct ListContext<'a> {
+    style: Option<&'a str>,
+    level: u8,
+}
+
+struct Parser {
+    package: Package,
+    styles: Styles,
+    notes: Vec<Note>,
+}
+
+impl Parser {
+    /// 打开 ODF 包并加载样式
+    fn open(path: &str, mimetype: &str) -> Result<Self, DocsError> {
+        let mut package = Package::open(path)?;
+        // mimetype 缺失时仍尝试解析
+        if let Some(actual) = package.read_string("mimetype")? {
+            if !actual.trim().starts_with(mimetype) {
+                return Err(DocsError::Unsupported(actual.trim().to_string()));
+            }
+        }
+        // 加密的 ODF 在清单中记录每个部件的加密参数
+        if let Some(manifest) = package.read_string("META-INF/manifest.xml")? {
+            if manifest.contains("encryption-data") {
+                return Err(DocsError::Encrypted);
+            }
+        }
+        Ok(Parser {
+            package,
+            styles: Styles::default(),
+            notes: Vec::new(),
+        })
+    }
+
+    fn metadata(&mut self) -> Option<DocumentMetadata> {
+        let xml = self.package.read_string("meta.xml").ok()??;
+        DocumentMetadata::from_odf_meta(&xml)
+            .map_err(|e| log::error!("解析 meta.xml 失败: {}", e))
+            .ok()
+    }
+
+    fn blocks(&mut self, parent: Node, list: Option<ListContext>, out: &mut Vec<Block>) {
+        for node in parent.children().filter(Node::is_element) {
+            match node.tag_name().name() {
+                "p" | "h" => out.push(Block::Paragraph(self.paragraph(node, list))),
+                "list" => {
+                    let context = ListContext {
+                        style: attr(&node, "style-name").or(list.and_then(|l| l.style)),
+                        level: list.map_or(0, |l| l.level + 1),
+                    };
+                    for item in node
+                        .children()
+                        .filter(|n| is(n, "list-item") || is(n, "list-header"))
+                    {
+                        self.blocks(item, Some(context), out);
+                    }
+                },
+                "table" => out.push(Block::Table(self.table(node))),
+                "section" | "index-body" | "table-of-content" | "alphabetical-index"
+                | "illustration-index" | "bibliography" | "text-box" => {
+                    self.blocks(node, None, out)
+                },
+                _ => {},
+            }
+        }
+    }
+
+    fn paragraph(&mut self, node: Node, list: Option<ListContext>) -> Paragraph {
+        let style = attr(&node, "style-name");
+        let mut paragraph = Paragraph {
+            style: style.map(|s| self.styles.display_name(s)),
+            align: self.styles.align(style),
+            ..Default::default()
+        };
+
+        let outline_level = if is(&node, "h") {
+            attr(&node, "outline-level")
+                .and_then(|v| v.parse().ok())
+                .or_else(|| self.styles.outline_level(style))
+                .or(Some(1))
+        } else {
+            None
+        };
+        paragraph.heading = outline_level.map(|l: u8| l.clamp(1, 6));
+        paragraph.list = list.map(|l| ListInfo {
+            level: l.level,
+            ordered: self.styles.is_ordered(l.style, l.level),
+        });
+
+        let props = self.styles.text_props(style);
+        self.inlines(node, &props, None, &mut paragraph.inlines);
+        paragraph
+    }
+
+    fn inlines(
+        &mut self,
+        parent: Node,
+        props: &TextProps,
+        href: Option<&str>,
+        out: &mut Vec<Inline>,
+    ) {
+        for node in parent.children() {
+            if node.is_text() {
+                let text = collapse_whitespace(node.text().unwrap_or_default());
+                if !text.is_empty() {
+                    let mut run = Run {
+                        text,
+                        href: href.map(str::to_string),
+                        ..Default::default()
+                    };
+                    props.apply(&mut run);
+                    out.push(Inline::Text(run));
+                }
+                continue;
+            }
+            if !node.is_element() {
+                continue;
+            }
+
+            match node.tag_name().name() {
+                "span" => {
+                    let mut inner = props.clone();
+                    inner.merge(&self.styles.text_props(attr(&node, "style-name")));
+                    self.inlines(node, &inner, href, out);
+                },
+                "a" => {
+                    let target = attr(&node, "href").or(href);
+                    self.inlines(node, props, target, out);
+                },
+                "s" => {
+                    let count = attr(&node, "c")
+                        .and_then(|c| c.parse().ok())
+                        .unwrap_or(1usize);
+                    let mut run = Run {
+                        text: " ".repeat(count.min(MAX_REPEAT)),
+                        ..Default::default()
+                    };
+                    props.apply(&mut run);
+                    out.push(Inline::Text(run));
+                },
+                "tab" => {
+                    let mut run = Run {
+                        text: "\t".to_string(),
+                        ..Default::default()
+                    };
+                    props.apply(&mut run);
+                    out.push(Inline::Text(run));
+                },
+                "line-break" => out.push(Inline::Break),
+                "note" => {
+                    let citation = child(&node, "note-citation")
+                        .and_then(|c| c.text())
+                        .map(str::to_string)
+                        .or_else(|| attr(&node, "id").map(str::to_string))
+                        .unwrap_or_default();
+                    let mut blocks = Vec::new();
+                    if let Some(body) = child(&node, "note-body") {
+                        self.blocks(body, None, &mut blocks);
+                    }
+                    self.notes.push(Note { id: citation.clone(), blocks });
+                    out.push(Inline::Note { id: citation });
+                },
+                "frame" => {
+                    if let Some(image) = self.image(&node) {
+                        out.push(Inline::Image(image));
+                    }
+                },
+                "bookmark" | "bookmark-start" | "bookmark-end" | "soft-page-break"
+                | "annotation" | "annotation-end" | "reference-mark" | "sequence-decls" => {},
+                // 字段（页码、日期等）及其他容器只保留其中的文字
+                _ => self.inlines(node, props, href, out),
+            }
+        }
+    }
+
+    /// 解析 `draw:frame` 中的图片
+    fn image(&mut self, frame: &Node) -> Option<Image> {
+        let image = child(frame, "image")?;
+        let href = attr(&image, "href")?;
+        // 外部链接的图片不加载
+        if href.contains("://") {
+            return None;
+        }
+        let src = self.package.image_data_uri(href.trim_start_matches("./"))?;
+        let alt = child(frame, "desc")
+            .or_else(|| child(frame, "title"))
+            .and_then(|n| n.text())
+            .map(str::to_string)
+            .or_else(|| attr(frame, "name").map(str::to_string));
+        Some(Image {
+            src,
+            alt,
+            width: attr(frame, "width").and_then(length_to_px),
+            height: attr(frame, "height").and_then(length_to_px),
+        })
+    }
+
+    fn table(&mut self, node: Node) -> Table {
+        let mut table = Table::default();
+        self.table_rows(node, &mut table);
+        table
+    }
+
+    fn table_rows(&mut self, parent: Node, table: &mut Table) {
+        for node in parent.children().filter(Node::is_element) {
+            match node.tag_name().name() {
+                "table-row" => {
+                    let mut row = TableRow::default();
+                    for cell in node.children().filter(|n| is(n, "table-cell")) {
+                        let span = |name: &str| {
+                            attr(&cell, name)
+                                .and_then(|v| v.parse::<u32>().ok())
+                                .unwrap_or(1)
+                                .max(1)
+                        };
+                        let repeat = attr(&cell, "number-columns-repeated")
+                            .and_then(|v| v.parse::<usize>().ok())
+                            .unwrap_or(1)
+                            .clamp(1, MAX_REPEAT);
+                        let mut blocks = Vec::new();
+                        self.blocks(cell, None, &mut blocks);
+                        for _ in 0..repeat {
+                            row.cells.push(TableCell {
+                                blocks: blocks.clone(),
+                                col_span: span("number-columns-spanned"),
+                                row_span: span("number-rows-spanned"),
+                            });
+                        }
+                    }
+                    table.rows.push(row);
+                },
+                "table-header-rows" | "table-rows" | "table-row-group" => {
+                    self.table_rows(node, table)
+                },
+                _ => {},
+            }
+        }
+    }
+
+    /// 解析演示文稿页面中的形状
+    fn shapes(&mut self, parent: Node, slide: &mut Slide) {
+        for node in parent.children().filter(Node::is_element) {
+            match node.tag_name().name() {
+                "frame" => {
+                    let class = attr(&node, "class");
+                    let frame = frame_of(&node);
+                    let name = attr(&node, "name").map(str::to_string);
+                    let content = if let Some(text_box) = child(&node, "text-box") {
+                        let mut blocks = Vec::new();
+                        self.blocks(text_box, None, &mut blocks);
+                        ShapeContent::Text { blocks }
+                    } else if let Some(tbl) = child(&node, "table") {
+                        ShapeContent::Table(self.table(tbl))
+                    } else if let Some(image) = self.image(&node) {
+                        ShapeContent::Image(image)
+                    } else {
+                        continue;
+                    };
+                    if let ShapeContent::Text { blocks } = &content {
+                        if blocks.is_empty() {
+                            continue;
+                        }
+                        if class == Some("title") && slide.title.is_none() {
+                            slide.title = Some(plain_text(blocks)).filter(|t| !t.is_empty());
+                        }
+                    }
+                    slide.shapes.push(Shape { name, frame, content });
+                },
+                "g" => self.shapes(node, slide),
+                "custom-shape" | "rect" | "ellipse" | "polygon" | "path" | "caption" => {
+                    let mut blocks = Vec::new();
+                    self.blocks(node, None, &mut blocks);
+                    if blocks.is_empty() {
+                        continue;
+                    }
+                    slide.shapes.push(Shape {
+                        name: attr(&node, "name").map(str::to_string),
+                        frame: frame_of(&node),
+                        content: ShapeContent::Text { blocks },
+                    });
+                },
+                _ => {},
+            }
+        }
+    }
+}
+
+/// 解析 odt 文件
+pub fn parse_text(path: &str) -> Result<Document, DocsError> {
+    let mut parser = Parser::open(path, "application/vnd.oasis.opendocument.text")?;
+    let styles_xml = parser
+        .package
+        .read_string("styles.xml")?
+        .unwrap_or_default();
+    let content_xml = parser
+        .package
+        .read_string("content.xml")?
+        .ok_or_else(|| DocsError::missing_part("content.xml"))?;
+
+    let styles_doc = parse_optional(&styles_xml)?;
+    let content = roxmltree::Document::parse(&content_xml)?;
+    if let Some(doc) = &styles_doc {
+        parser.styles.load(doc);
+    }
+    parser.styles.load(&content);
+
+    let body = content
+        .descendants()
+        .find(|n| is(n, "text") && n.parent().is_some_and(|p| is(&p, "body")))
+        .ok_or_else(|| DocsError::corrupt("缺少 office:text 元素").in_part("content.xml"))?;
+
+    let mut document = Document::default();
+    parser.blocks(body, None, &mut document.blocks);
+
+    // 页眉页脚定义在 styles.xml 的母版页中
+    if let Some(doc) = &styles_doc {
+        for master in doc.descendants().filter(|n| is(n, "master-page")) {
+            for part in master.children().filter(Node::is_element) {
+                let name = part.tag_name().name();
+                let is_header = name.starts_with("header");
+                if !is_header && !name.starts_with("footer") || name.ends_with("-left") {
+                    continue;
+                }
+                let mut blocks = Vec::new();
+                parser.blocks(part, None, &mut blocks);
+                if blocks.is_empty() {
+                    continue;
+                }
+                if is_header {
+                    document.headers.push(blocks);
+                } else {
+                    document.footers.push(blocks);
+                }
+            }
+        }
+    }
+
+    document.footnotes = std::mem::take(&mut parser.notes);
+    document.metadata = parser.metadata();
+    Ok(document)
+}
+
+/// 解析 odp 文件
+pub fn parse_presentation(path: &str) -> Result<Presentation, DocsError> {
+    let mut parser = Parser::open(path, "application/vnd.oasis.opendocument.presentation")?;
+    let styles_xml = parser
+        .package
+        .read_string("styles.xml")?
+        .unwrap_or_default();
+    let content_xml = parser
+        .package
+        .read_string("content.xml")?
+        .ok_or_else(|| DocsError::missing_part("content.xml"))?;
+
+    let styles_doc = parse_optional(&styles_xml)?;
+    let content = roxmltree::Document::parse(&content_xml)?;
+    if let Some(doc) = &styles_doc {
+        parser.styles.load(doc);
+    }
+    parser.styles.load(&content);
+
+    // 页面尺寸取自母版使用的页面布局，默认 28cm x 15.75cm
+    let (width, height) = styles_doc
+        .as_ref()
+        .and_then(|doc| doc.descendants().find(|n| is(n, "page-layout-properties")))
+        .and_then(|props| {
+            Some((
+                length_to_px(attr(&props, "page-width")?)?,
+                length_to_px(attr(&props, "page-height")?)?,
+            ))
+        })
+        .unwrap_or((1058.27, 595.28));
+
+    let body = content
+        .descendants()
+        .find(|n| is(n, "presentation") && n.parent().is_some_and(|p| is(&p, "body")))
+        .ok_or_else(|| {
+            DocsError::corrupt("缺少 office:presentation 元素").in_part("content.xml")
+        })?;
+
+    let mut slides = Vec::new();
+    for (index, page) in body.children().filter(|n| is(n, "page")).enumerate() {
+        let hidden = attr(&page, "style-name")
+            .and_then(|s| parser.styles.styles.get(s))
+            .is_some_and(|s| s.hidden);
+        let mut slide = Slide {
+            index: index + 1,
+            hidden,
+            ..Default::default()
+        };
+        parser.shapes(page, &mut slide);
+
+        if let Some(notes) = child(&page, "notes") {
+            for frame in notes.descendants().filter(|n| is(n, "frame")) {
+                if attr(&frame, "class") != Some("notes") {
+                    continue;
+                }
+                if let Some(text_box) = child(&frame, "text-box") {
+                    parser.blocks(text_box, None, &mut slide.notes);
+                }
+            }
+        }
+        if slide.title.is_none() {
+            slide.title = attr(&page, "name")
+                .filter(|n| !n.starts_with("page"))
+                .map(str::to_string);
+        }
+        slides.push(slide);
+    }
+
+    let mut metadata = parser.metadata();
+    if let Some(metadata) = metadata.as_mut() {
+        metadata.slide_count = Some(slides.len() as u32);
+    }
+    Ok(Presentation { width, height, slides, metadata })
+}
+
+fn parse_optional(xml: &str) -> Result<Option<roxmltree::Document<'_>>, roxmltree::Error> {
+    if xml.is_empty() {
+        return Ok(None);
+    }
+    roxmltree::Document::parse(xml).map(Some)
+}
+
+fn text_props(props: &Node) -> TextProps {
+    // text-position 形如 `super 58%`、`-33% 58%`、`0% 100%`
+    let position = attr(props, "text-position").and_then(|p| p.split_whitespace().next());
+    let shift = position.map(|p| match p {
+        "super" => 1.0,
+        "sub" => -1.0,
+        p => p.trim_end_matches('%').parse::<f32>().unwrap_or(0.0),
+    });
+    TextProps {
+        bold: attr(props, "font-weight")
+            .map(|w| w == "bold" || w.parse::<u32>().is_ok_and(|w| w >= 600)),
+        italic: attr(props, "font-style").map(|s| s == "italic" || s == "oblique"),
+        underline: attr(props, "text-underline-style").map(|s| s != "none"),
+        strike: attr(props, "text-line-through-style").map(|s| s != "none"),
+        superscript: shift.map(|s| s > 0.0),
+        subscript: shift.map(|s| s < 0.0),
+        color: attr(props, "color")
+            .filter(|c| c.len() == 7 && c.starts_with('#'))
+            .map(|c| c.to_ascii_uppercase()),
+        size: attr(props, "font-size")
+            .and_then(|s| s.strip_suffix("pt"))
+            .and_then(|s| s.parse().ok()),
+        font: attr(props, "font-name").map(str::to_string),
+    }
+}
+
+fn align(value: &str) -> Option<String> {
+    match value {
+        "start" | "left" => Some("left".to_string()),
+        "center" => Some("center".to_string()),
+        "end" | "right" => Some("right".to_string()),
+        "justify" => Some("justify".to_string()),
+        _ => None,
+    }
+}
+
+fn frame_of(node: &Node) -> Option<Frame> {
+    Some(Frame {
+        x: attr(node, "x").and_then(length_to_px).unwrap_or(0.0),
+        y: attr(node, "y").and_then(length_to_px).unwrap_or(0.0),
+        width: length_to_px(attr(node, "width")?)?,
+        height: length_to_px(attr(node, "height")?)?,
+    })
+}
+
+/// ODF 长度（cm/mm/in/pt/pc/px）转像素
+pub fn length_to_px(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f32 = number.parse().ok()?;
+    let factor = match unit {
+        "cm" => 96.0 / 2.54,
+        "mm" => 96.0 / 25.4,
+        "in" => 96.0,
+        "pt" => 96.0 / 72.0,
+        "pc" => 16.0,
+        "px" | "" => 1.0,
+        _ => return None,
+    };
+    Some(number * factor)
+}
+
+/// ODF 中连续的空白字符按一个空格处理
+fn collapse_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last_space = false;
+    for c in text.chars() {
+        if matches!(c, ' ' | '\n' | '\r' | '\t') {
+            if !last_space {
+                out.push(' ');
+            }
+            last_space = true;
+        } else {
+            out.push(c);
+            last_space = false;
+        }
+    }
+    out
+}
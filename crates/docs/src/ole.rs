@@ -0,0 +1,66 @@
+//! OLE2 复合文档（doc/ppt/xls）的流读取与小端整数解析
+
+use crate::{error::DocsError, ooxml::MAX_PART_SIZE};
+use cfb::CompoundFile;
+use encoding_rs::{Encoding, BIG5, EUC_KR, GBK, SHIFT_JIS, UTF_16LE, UTF_8, WINDOWS_1252};
+use std::{fs::File, io::Read};
+
+/// 打开复合文档
+pub fn open(path: &str) -> Result<CompoundFile<File>, DocsError> {
+    Ok(cfb::open(path)?)
+}
+
+/// 读取流，不存在时返回 `None`
+pub fn read_stream(cfb: &mut CompoundFile<File>, name: &str) -> Result<Option<Vec<u8>>, DocsError> {
+    if !cfb.is_stream(name) {
+        return Ok(None);
+    }
+    let mut stream = cfb.open_stream(name)?;
+    if stream.len() > MAX_PART_SIZE {
+        return Err(DocsError::LimitExceeded {
+            limit: format!("流大小 {} 字节", stream.len()),
+            part: Some(name.to_string()),
+        });
+    }
+    let mut data = Vec::with_capacity(stream.len() as usize);
+    stream
+        .read_to_end(&mut data)
+        .map_err(|e| DocsError::from(e).in_part(name))?;
+    Ok(Some(data))
+}
+
+pub fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+pub fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+pub fn i32_at(data: &[u8], offset: usize) -> Option<i32> {
+    u32_at(data, offset).map(|v| v as i32)
+}
+
+/// UTF-16LE 文本，去掉末尾的 NUL
+pub fn utf16_text(data: &[u8]) -> String {
+    let (text, _) = UTF_16LE.decode_without_bom_handling(data);
+    text.trim_end_matches('\0').to_string()
+}
+
+/// Windows 代码页对应的编码，未知的按 Windows-1252 处理
+pub fn codepage_encoding(codepage: u16) -> &'static Encoding {
+    match codepage {
+        936 => GBK,
+        950 => BIG5,
+        932 => SHIFT_JIS,
+        949 => EUC_KR,
+        65001 => UTF_8,
+        1200 => UTF_16LE,
+        1250..=1258 => {
+            Encoding::for_label(format!("windows-{}", codepage).as_bytes()).unwrap_or(WINDOWS_1252)
+        },
+        _ => WINDOWS_1252,
+    }
+}
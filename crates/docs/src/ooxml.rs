@@ -0,0 +1,201 @@
+use crate::{
+    error::DocsError,
+    format::{detect_ole2, OLE2_MAGIC},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use roxmltree::Node;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, Read, Seek},
+};
+use zip::ZipArchive;
+
+/// 单个部件允许读取的最大字节数
+pub const MAX_PART_SIZE: u64 = 256 << 20;
+
+/// OOXML/ODF 等基于 zip 的文档包
+pub struct Package {
+    archive: ZipArchive<BufReader<File>>,
+}
+
+/// 部件之间的关系（`_rels/*.rels`）
+#[derive(Debug, Clone)]
+pub struct Relationship {
+    /// 关系类型 URI 的最后一段，如 `image`、`hyperlink`
+    pub kind: String,
+    /// 内部部件为包内的绝对路径（不含前导 `/`），外部链接为原始地址
+    pub target: String,
+    pub external: bool,
+}
+
+impl Package {
+    pub fn open(path: &str) -> Result<Self, DocsError> {
+        let mut file = File::open(path)?;
+        let mut magic = [0; 8];
+        if file.read_exact(&mut magic).is_ok() && magic == OLE2_MAGIC {
+            // 加密的 OOXML 文档保存为 OLE2 容器
+            return Err(match detect_ole2(file) {
+                Ok(format) => DocsError::Unsupported(format.name().to_string()),
+                Err(e) => e,
+            });
+        }
+        file.rewind()?;
+        let archive = ZipArchive::new(BufReader::new(file))?;
+        Ok(Package { archive })
+    }
+
+    /// 是否包含指定部件
+    pub fn contains(&self, name: &str) -> bool {
+        self.archive.index_for_name(name).is_some()
+    }
+
+    /// 包内所有部件名
+    pub fn names(&self) -> Vec<String> {
+        self.archive.file_names().map(|s| s.to_string()).collect()
+    }
+
+    /// 读取部件，不存在时返回 `None`
+    pub fn read(&mut self, name: &str) -> Result<Option<Vec<u8>>, DocsError> {
+        let mut file = match self.archive.by_name(name.trim_start_matches('/')) {
+            Ok(file) => file,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+            Err(e) => return Err(DocsError::from(e).in_part(name)),
+        };
+        if file.size() > MAX_PART_SIZE {
+            return Err(DocsError::LimitExceeded {
+                limit: format!("部件大小 {} 字节", file.size()),
+                part: Some(name.to_string()),
+            });
+        }
+        let mut data = Vec::with_capacity(file.size() as usize);
+        file.read_to_end(&mut data)
+            .map_err(|e| DocsError::from(e).in_part(name))?;
+        Ok(Some(data))
+    }
+
+    /// 以 UTF-8 文本读取部件
+    pub fn read_string(&mut self, name: &str) -> Result<Option<String>, DocsError> {
+        Ok(self
+            .read(name)?
+            .map(|data| String::from_utf8_lossy(strip_bom(&data)).into_owned()))
+    }
+
+    /// 读取部件的关系表，键为关系 id
+    pub fn relationships(
+        &mut self,
+        part: &str,
+    ) -> Result<HashMap<String, Relationship>, DocsError> {
+        let (dir, file) = split_part(part);
+        let rels_name = if dir.is_empty() {
+            format!("_rels/{}.rels", file)
+        } else {
+            format!("{}/_rels/{}.rels", dir, file)
+        };
+        let Some(xml) = self.read_string(&rels_name)? else {
+            return Ok(HashMap::new());
+        };
+
+        let doc = roxmltree::Document::parse(&xml)?;
+        let mut rels = HashMap::new();
+        for node in doc.descendants().filter(|n| n.has_tag_name("Relationship")) {
+            let (Some(id), Some(target)) = (node.attribute("Id"), node.attribute("Target")) else {
+                continue;
+            };
+            let external = node.attribute("TargetMode") == Some("External");
+            let kind = node
+                .attribute("Type")
+                .and_then(|t| t.rsplit('/').next())
+                .unwrap_or_default()
+                .to_string();
+            let target = if external {
+                target.to_string()
+            } else {
+                resolve(dir, target)
+            };
+            rels.insert(id.to_string(), Relationship { kind, target, external });
+        }
+        Ok(rels)
+    }
+
+    /// 读取图片部件并转换为 data URI
+    pub fn image_data_uri(&mut self, name: &str) -> Option<String> {
+        let mime = image_mime(name)?;
+        let data = self.read(name).ok()??;
+        Some(format!("data:{};base64,{}", mime, STANDARD.encode(data)))
+    }
+}
+
+fn split_part(part: &str) -> (&str, &str) {
+    let part = part.trim_start_matches('/');
+    part.rsplit_once('/').unwrap_or(("", part))
+}
+
+/// 将关系目标解析为包内绝对路径
+pub fn resolve(base_dir: &str, target: &str) -> String {
+    let mut parts: Vec<&str> = if target.starts_with('/') {
+        Vec::new()
+    } else {
+        base_dir.split('/').filter(|s| !s.is_empty()).collect()
+    };
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {},
+            ".." => {
+                parts.pop();
+            },
+            s => parts.push(s),
+        }
+    }
+    parts.join("/")
+}
+
+/// 根据扩展名推断图片 MIME 类型，非浏览器可显示的格式返回 `None`
+pub fn image_mime(name: &str) -> Option<&'static str> {
+    let ext = name.rsplit_once('.')?.1.to_ascii_lowercase();
+    match ext.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "bmp" => Some("image/bmp"),
+        "webp" => Some("image/webp"),
+        "svg" => Some("image/svg+xml"),
+        _ => None,
+    }
+}
+
+pub fn strip_bom(data: &[u8]) -> &[u8] {
+    data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data)
+}
+
+/// EMU（English Metric Unit）转像素，1px = 9525 EMU
+pub fn emu_to_px(emu: &str) -> Option<f32> {
+    emu.parse::<f64>().ok().map(|v| (v / 9525.0) as f32)
+}
+
+/// 按本地名匹配元素，忽略命名空间前缀
+pub fn is(node: &Node, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name
+}
+
+pub fn child<'a, 'input>(node: &Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| is(n, name))
+}
+
+/// 按本地名读取属性，忽略命名空间前缀
+pub fn attr<'a>(node: &Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.attributes()
+        .find(|a| a.name() == name)
+        .map(|a| a.value())
+}
+
+/// 读取关系 id 属性（`r:id`/`r:embed` 等），只匹配关系命名空间下的属性
+pub fn rel_attr<'a>(node: &Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.attributes()
+        .find(|a| {
+            a.name() == name
+                && a.namespace()
+                    .is_some_and(|ns| ns.ends_with("relationships"))
+        })
+        .map(|a| a.value())
+}
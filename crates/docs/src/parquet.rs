@@ -0,0 +1,372 @@
+//! Parquet：通过 parquet 库读取文件尾与行组，行按 Arrow 数组转换
+//!
+//! 列为顶层字段，列表、映射与结构体整体显示在一列中；行组统计仍按叶子列列出。
+
+use crate::{
+    columnar::{
+        type_name, value, ColumnChunkInfo, ColumnSchema, ColumnarInfo, RowGroupInfo, TimeUnit,
+        Value,
+    },
+    error::DocsError,
+    format::DocsFormat,
+};
+use arrow_buffer::i256;
+use half::f16;
+use parquet::{
+    arrow::arrow_reader::{
+        ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder,
+    },
+    basic::{Compression, ConvertedType, LogicalType, Type as PhysicalType},
+    file::{metadata::ColumnChunkMetaData, statistics::Statistics},
+    schema::types::ColumnDescriptor,
+};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    sync::Arc,
+};
+
+/// 加密文件尾
+const ENCRYPTED_MAGIC: &[u8; 4] = b"PARE";
+/// 由 Arrow 写入的序列化模式，与 Parquet 模式重复，不显示
+const ARROW_SCHEMA_KEY: &str = "ARROW:schema";
+
+/// 已读取文件尾的 Parquet 文件
+pub(crate) struct ParquetFile {
+    file: File,
+    metadata: ArrowReaderMetadata,
+    info: ColumnarInfo,
+    /// 顶层为基本类型的列，读取行时按其逻辑类型修正二进制值
+    leaves: Vec<Option<Arc<ColumnDescriptor>>>,
+}
+
+impl ParquetFile {
+    pub fn open(path: &str) -> Result<Self, DocsError> {
+        let mut file = File::open(path)?;
+        let size = file.metadata()?.len();
+        if size >= 4 {
+            let mut tail = [0u8; 4];
+            file.seek(SeekFrom::End(-4))?;
+            file.read_exact(&mut tail)?;
+            if &tail == ENCRYPTED_MAGIC {
+                return Err(DocsError::Encrypted);
+            }
+        }
+        let metadata = ArrowReaderMetadata::load(&file, ArrowReaderOptions::default())?;
+        let parquet = metadata.metadata();
+        let meta = parquet.file_metadata();
+
+        // 顶层字段与 Parquet 模式根节点的子节点一一对应
+        let schema = meta.schema_descr();
+        let roots = schema.root_schema().get_fields();
+        let mut leaves: Vec<Option<Arc<ColumnDescriptor>>> = vec![None; roots.len()];
+        for i in 0..schema.num_columns() {
+            let root = schema.get_column_root_idx(i);
+            if roots[root].is_primitive() {
+                leaves[root] = Some(schema.column(i));
+            }
+        }
+        let columns = metadata
+            .schema()
+            .fields()
+            .iter()
+            .zip(&leaves)
+            .map(|(field, leaf)| ColumnSchema {
+                name: field.name().clone(),
+                data_type: match leaf {
+                    Some(leaf) if leaf.logical_type() == Some(LogicalType::Uuid) => {
+                        "uuid".to_string()
+                    },
+                    _ => type_name(field.data_type()),
+                },
+                physical_type: leaf.as_ref().map(|leaf| leaf.physical_type().to_string()),
+                nullable: field.is_nullable(),
+            })
+            .collect();
+
+        let row_groups = parquet
+            .row_groups()
+            .iter()
+            .map(|group| RowGroupInfo {
+                row_count: group.num_rows().max(0) as u64,
+                compressed_size: Some(group.compressed_size().max(0) as u64),
+                uncompressed_size: Some(group.total_byte_size().max(0) as u64),
+                columns: group.columns().iter().map(column_chunk).collect(),
+            })
+            .collect();
+        let metadata_kv: BTreeMap<String, String> = meta
+            .key_value_metadata()
+            .into_iter()
+            .flatten()
+            .filter(|kv| kv.key != ARROW_SCHEMA_KEY)
+            .map(|kv| (kv.key.clone(), kv.value.clone().unwrap_or_default()))
+            .collect();
+        let info = ColumnarInfo {
+            format: DocsFormat::Parquet,
+            row_count: meta.num_rows().max(0) as u64,
+            columns,
+            row_groups,
+            metadata: metadata_kv,
+            created_by: meta.created_by().map(str::to_string),
+        };
+        Ok(ParquetFile { file, metadata, info, leaves })
+    }
+
+    pub fn info(&self) -> &ColumnarInfo {
+        &self.info
+    }
+
+    /// 按列读取从 `offset` 开始的 `count` 行，只解码覆盖这些行的行组
+    pub fn read(&mut self, offset: u64, count: usize) -> Result<Vec<Vec<Value>>, DocsError> {
+        let end = offset + count as u64;
+        let mut selected = Vec::new();
+        let mut skip = 0;
+        let mut first_row = 0u64;
+        for (index, group) in self.metadata.metadata().row_groups().iter().enumerate() {
+            let group_end = first_row + group.num_rows().max(0) as u64;
+            if group_end > offset && first_row < end {
+                if selected.is_empty() {
+                    skip = (offset - first_row) as usize;
+                }
+                selected.push(index);
+            }
+            first_row = group_end;
+        }
+
+        let reader = ParquetRecordBatchReaderBuilder::new_with_metadata(
+            self.file.try_clone()?,
+            self.metadata.clone(),
+        )
+        .with_row_groups(selected)
+        .with_offset(skip)
+        .with_limit(count)
+        .with_batch_size(count)
+        .build()?;
+        let mut columns: Vec<Vec<Value>> = vec![Vec::with_capacity(count); self.leaves.len()];
+        for batch in reader {
+            let batch = batch?;
+            for ((column, array), leaf) in columns.iter_mut().zip(batch.columns()).zip(&self.leaves)
+            {
+                column.extend(
+                    (0..array.len()).map(|i| match (value(array.as_ref(), i), leaf) {
+                        (Value::Binary(bytes), Some(leaf)) => bytes_value(leaf, &bytes),
+                        (value, _) => value,
+                    }),
+                );
+            }
+        }
+        Ok(columns)
+    }
+}
+
+/// 行组中一列的存储信息与统计
+fn column_chunk(chunk: &ColumnChunkMetaData) -> ColumnChunkInfo {
+    let (min, max) = chunk
+        .statistics()
+        .map_or((None, None), |stats| min_max(chunk.column_descr(), stats));
+    ColumnChunkInfo {
+        column: chunk.column_path().string(),
+        codec: Some(codec_name(chunk.compression()).to_string()),
+        encodings: chunk.encodings().iter().map(ToString::to_string).collect(),
+        compressed_size: Some(chunk.compressed_size().max(0) as u64),
+        uncompressed_size: Some(chunk.uncompressed_size().max(0) as u64),
+        null_count: chunk.statistics().and_then(Statistics::null_count_opt),
+        distinct_count: chunk.statistics().and_then(Statistics::distinct_count_opt),
+        min,
+        max,
+    }
+}
+
+fn codec_name(codec: Compression) -> &'static str {
+    match codec {
+        Compression::UNCOMPRESSED => "uncompressed",
+        Compression::SNAPPY => "snappy",
+        Compression::GZIP(_) => "gzip",
+        Compression::LZO => "lzo",
+        Compression::BROTLI(_) => "brotli",
+        Compression::LZ4 => "lz4",
+        Compression::ZSTD(_) => "zstd",
+        Compression::LZ4_RAW => "lz4_raw",
+    }
+}
+
+/// 最小值与最大值的显示文本
+fn min_max(column: &ColumnDescriptor, stats: &Statistics) -> (Option<String>, Option<String>) {
+    fn pair<T>(
+        min: Option<&T>,
+        max: Option<&T>,
+        convert: impl Fn(&T) -> Value,
+    ) -> (Option<String>, Option<String>) {
+        let text = |v: Option<&T>| v.map(|v| convert(v).to_string());
+        (text(min), text(max))
+    }
+
+    // 旧版的 min/max 对字节数组的排序不正确，只用于数值类型
+    if stats.is_min_max_deprecated()
+        && matches!(
+            column.physical_type(),
+            PhysicalType::BYTE_ARRAY | PhysicalType::FIXED_LEN_BYTE_ARRAY
+        )
+    {
+        return (None, None);
+    }
+    let unsigned = matches!(
+        column.converted_type(),
+        ConvertedType::UINT_8 | ConvertedType::UINT_16 | ConvertedType::UINT_32
+    );
+    match stats {
+        Statistics::Boolean(s) => pair(s.min_opt(), s.max_opt(), |v| Value::Bool(*v)),
+        Statistics::Int32(s) if unsigned => {
+            pair(s.min_opt(), s.max_opt(), |v| Value::UInt(*v as u32 as u64))
+        },
+        Statistics::Int32(s) => pair(s.min_opt(), s.max_opt(), |v| int_value(column, *v as i64)),
+        Statistics::Int64(s) => pair(s.min_opt(), s.max_opt(), |v| int_value(column, *v)),
+        Statistics::Int96(s) => pair(s.min_opt(), s.max_opt(), |v| Value::Timestamp {
+            value: v.to_nanos(),
+            unit: TimeUnit::Nano,
+        }),
+        Statistics::Float(s) => pair(s.min_opt(), s.max_opt(), |v| Value::Float32(*v)),
+        Statistics::Double(s) => pair(s.min_opt(), s.max_opt(), |v| Value::Float(*v)),
+        Statistics::ByteArray(s) => {
+            pair(s.min_opt(), s.max_opt(), |v| bytes_value(column, v.data()))
+        },
+        Statistics::FixedLenByteArray(s) => {
+            pair(s.min_opt(), s.max_opt(), |v| bytes_value(column, v.data()))
+        },
+    }
+}
+
+/// 按逻辑类型转换整数统计值
+fn int_value(column: &ColumnDescriptor, value: i64) -> Value {
+    match column.converted_type() {
+        ConvertedType::DATE => Value::Date(value),
+        ConvertedType::DECIMAL => Value::Decimal {
+            digits: value.to_string(),
+            scale: column.type_scale(),
+        },
+        ConvertedType::TIME_MILLIS => Value::Time { value, unit: TimeUnit::Milli },
+        ConvertedType::TIME_MICROS => Value::Time { value, unit: TimeUnit::Micro },
+        ConvertedType::TIMESTAMP_MILLIS => Value::Timestamp { value, unit: TimeUnit::Milli },
+        ConvertedType::TIMESTAMP_MICROS => Value::Timestamp { value, unit: TimeUnit::Micro },
+        ConvertedType::UINT_64 => Value::UInt(value as u64),
+        // 纳秒精度只有逻辑类型
+        _ => match column.logical_type() {
+            Some(LogicalType::Time { .. }) => Value::Time { value, unit: TimeUnit::Nano },
+            Some(LogicalType::Timestamp { .. }) => Value::Timestamp { value, unit: TimeUnit::Nano },
+            _ => Value::Int(value),
+        },
+    }
+}
+
+/// 按逻辑类型转换字节数组
+fn bytes_value(column: &ColumnDescriptor, bytes: &[u8]) -> Value {
+    match column.converted_type() {
+        ConvertedType::UTF8 | ConvertedType::ENUM | ConvertedType::JSON => {
+            Value::Text(String::from_utf8_lossy(bytes).into_owned())
+        },
+        ConvertedType::DECIMAL if bytes.len() <= 32 => {
+            // 大端补码，按符号位扩展到 32 字节
+            let fill = if bytes.first().is_some_and(|b| b & 0x80 != 0) {
+                0xFF
+            } else {
+                0
+            };
+            let mut buf = [fill; 32];
+            buf[32 - bytes.len()..].copy_from_slice(bytes);
+            Value::Decimal {
+                digits: i256::from_be_bytes(buf).to_string(),
+                scale: column.type_scale(),
+            }
+        },
+        _ => match column.logical_type() {
+            Some(LogicalType::Uuid) => Value::Text(uuid(bytes)),
+            Some(LogicalType::Float16) => match bytes.try_into() {
+                Ok(bits) => Value::Float32(f16::from_le_bytes(bits).to_f32()),
+                Err(_) => Value::Binary(bytes.to_vec()),
+            },
+            // 旧版写入端的字符串没有注解
+            None if column.physical_type() == PhysicalType::BYTE_ARRAY => {
+                match std::str::from_utf8(bytes) {
+                    Ok(text) => Value::Text(text.to_string()),
+                    Err(_) => Value::Binary(bytes.to_vec()),
+                }
+            },
+            _ => Value::Binary(bytes.to_vec()),
+        },
+    }
+}
+
+/// 十六进制 UUID 文本，长度不是 16 字节时不加连字符
+fn uuid(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(36);
+    for (i, b) in bytes.iter().enumerate() {
+        if bytes.len() == 16 && matches!(i, 4 | 6 | 8 | 10) {
+            text.push('-');
+        }
+        text.push_str(&format!("{:02x}", b));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::schema::types::{ColumnPath, Type};
+
+    fn column(physical: PhysicalType, logical: Option<LogicalType>, len: i32) -> ColumnDescriptor {
+        let mut builder = Type::primitive_type_builder("c", physical)
+            .with_length(len)
+            .with_logical_type(logical.clone());
+        if let Some(LogicalType::Decimal { scale, precision }) = logical {
+            builder = builder.with_precision(precision).with_scale(scale);
+        }
+        let primitive = Arc::new(builder.build().unwrap());
+        ColumnDescriptor::new(primitive, 0, 0, ColumnPath::from("c"))
+    }
+
+    fn text(column: &ColumnDescriptor, bytes: &[u8]) -> String {
+        bytes_value(column, bytes).to_string()
+    }
+
+    #[test]
+    fn decimal_bytes() {
+        let decimal = |precision| {
+            let logical = LogicalType::Decimal { scale: 2, precision };
+            column(PhysicalType::FIXED_LEN_BYTE_ARRAY, Some(logical), 32)
+        };
+        assert_eq!(text(&decimal(5), &[0x30, 0x39]), "123.45");
+        assert_eq!(text(&decimal(5), &[0xCF, 0xC7]), "-123.45");
+        assert_eq!(text(&decimal(5), &[0xFF]), "-0.01");
+
+        // 2^255 - 1
+        let mut max = [0xFF; 32];
+        max[0] = 0x7F;
+        assert_eq!(
+            text(&decimal(76), &max),
+            "578960446186580977117854925043439539266349923328202820197287920039565648199.67"
+        );
+    }
+
+    #[test]
+    fn uuid_and_float16() {
+        let uuid = column(
+            PhysicalType::FIXED_LEN_BYTE_ARRAY,
+            Some(LogicalType::Uuid),
+            16,
+        );
+        let bytes: Vec<u8> = (0..16).collect();
+        assert_eq!(text(&uuid, &bytes), "00010203-0405-0607-0809-0a0b0c0d0e0f");
+        assert_eq!(text(&uuid, &[0xAB, 0xCD]), "abcd");
+
+        let half = column(
+            PhysicalType::FIXED_LEN_BYTE_ARRAY,
+            Some(LogicalType::Float16),
+            2,
+        );
+        assert_eq!(text(&half, &0x3E00u16.to_le_bytes()), "1.5");
+        assert_eq!(text(&half, &0xFC00u16.to_le_bytes()), "-inf");
+        assert_eq!(text(&half, &0x7E00u16.to_le_bytes()), "NaN");
+        assert_eq!(text(&half, &[1, 2, 3]), "0x010203");
+    }
+}
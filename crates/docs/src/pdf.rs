@@ -0,0 +1,806 @@
+//! PDF：文档信息与 XMP 元数据、页数与页面尺寸、书签、加密状态与逐页文本
+
+use crate::{error::DocsError, metadata::split_keywords, metadata::DocumentMetadata};
+use lopdf::{
+    decode_text_string,
+    encryption::{decrypt_object, get_encryption_key, DecryptionError},
+    Dictionary, Document, Object, ObjectId,
+};
+use roxmltree::Node;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// 未指定 MediaBox 时的默认页面尺寸（US Letter，单位为点）
+const DEFAULT_PAGE_SIZE: (f32, f32) = (612.0, 792.0);
+/// 书签允许的最大嵌套层数
+const MAX_OUTLINE_DEPTH: usize = 32;
+/// 书签总数上限，防止循环链表
+const MAX_OUTLINE_ITEMS: usize = 100_000;
+
+/// PDF 概要
+#[derive(Debug, Clone, Serialize)]
+pub struct PdfInfo {
+    /// 文件头中的版本，如 `1.7`
+    pub version: String,
+    pub page_count: u32,
+    /// 各页尺寸，按页码顺序
+    pub pages: Vec<PageSize>,
+    pub encryption: Option<PdfEncryption>,
+    /// 文档信息字典与 XMP 合并后的元数据，无法解密时为空
+    pub metadata: DocumentMetadata,
+    pub outline: Vec<OutlineItem>,
+}
+
+/// 页面尺寸，单位为点（1/72 英寸），已按旋转交换宽高
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct PageSize {
+    pub width: f32,
+    pub height: f32,
+    /// 顺时针旋转角度
+    pub rotation: i64,
+}
+
+/// 加密信息
+#[derive(Debug, Clone, Serialize)]
+pub struct PdfEncryption {
+    /// 需要打开密码；只设置了权限密码时可以直接读取
+    pub password_required: bool,
+    /// 能否读取元数据、书签与文本；加密算法不受支持（如 AES-256）时为 `false`，页面仍可交给 pdf.js 渲染
+    pub readable: bool,
+    /// 安全处理器，通常为 `Standard`
+    pub filter: String,
+    /// 算法版本 V 与修订号 R
+    pub version: i64,
+    pub revision: i64,
+}
+
+/// 书签
+#[derive(Debug, Clone, Serialize)]
+pub struct OutlineItem {
+    pub title: String,
+    /// 目标页码（从 1 开始），目标不是本文档的页面时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<OutlineItem>,
+}
+
+/// 一页的文本
+#[derive(Debug, Clone, Serialize)]
+pub struct PageText {
+    /// 页码，从 1 开始
+    pub page: u32,
+    pub text: String,
+}
+
+/// 打开的 PDF，加密时已尝试用空密码解密
+pub struct Pdf {
+    document: Document,
+    encryption: Option<PdfEncryption>,
+}
+
+impl Pdf {
+    pub fn open(path: &str) -> Result<Self, DocsError> {
+        let mut document = Document::load(path)?;
+        let encryption = match document.get_encrypted() {
+            Ok(dict) => {
+                let name = |key: &[u8]| {
+                    dict.get(key)
+                        .and_then(Object::as_name)
+                        .map(|n| String::from_utf8_lossy(n).into_owned())
+                        .unwrap_or_default()
+                };
+                let number = |key: &[u8]| dict.get(key).and_then(Object::as_i64).unwrap_or(0);
+                let mut encryption = PdfEncryption {
+                    password_required: false,
+                    readable: true,
+                    filter: name(b"Filter"),
+                    version: number(b"V"),
+                    revision: number(b"R"),
+                };
+                // 只有权限密码的文档用空密码即可打开
+                match get_encryption_key(&document, "", true) {
+                    Ok(key) => decrypt(&mut document, &key),
+                    Err(DecryptionError::IncorrectPassword) => {
+                        log::info!("PDF 需要打开密码: {}", path);
+                        encryption.password_required = true;
+                        encryption.readable = false;
+                    },
+                    // lopdf 只支持修订号 2–4，缺少 /ID 时也无法计算密钥
+                    Err(e) => {
+                        log::warn!("PDF 的加密方式不受支持: {}: {}", path, e);
+                        encryption.readable = false;
+                    },
+                }
+                Some(encryption)
+            },
+            Err(_) => None,
+        };
+        log::info!("打开 PDF: {}, 版本 {}", path, document.version);
+        Ok(Pdf { document, encryption })
+    }
+
+    /// 页数、页面尺寸、加密状态、元数据与书签
+    pub fn info(&self) -> PdfInfo {
+        let pages = self.document.get_pages();
+        let sizes = pages.values().map(|&id| self.page_size(id)).collect();
+        let locked = self.is_locked();
+        PdfInfo {
+            version: self.document.version.clone(),
+            page_count: pages.len() as u32,
+            pages: sizes,
+            encryption: self.encryption.clone(),
+            metadata: self.metadata().unwrap_or_default(),
+            outline: if locked { Vec::new() } else { self.outline() },
+        }
+    }
+
+    /// 提取指定页（从 1 开始）的文本，`pages` 为空时提取全部页面
+    pub fn text(&self, pages: &[u32]) -> Result<Vec<PageText>, DocsError> {
+        if self.is_locked() {
+            return Err(DocsError::Encrypted);
+        }
+        let numbers: Vec<u32> = if pages.is_empty() {
+            self.document.get_pages().into_keys().collect()
+        } else {
+            pages.to_vec()
+        };
+        let mut texts = Vec::with_capacity(numbers.len());
+        for page in numbers {
+            let text = match self.document.extract_text(&[page]) {
+                Ok(text) => text,
+                Err(lopdf::Error::PageNumberNotFound(_)) => {
+                    return Err(DocsError::corrupt(format!("第 {} 页不存在", page)));
+                },
+                // 个别页面的字体或内容流无法解析时不影响其他页
+                Err(e) => {
+                    log::error!("提取第 {} 页文本失败: {}", page, e);
+                    String::new()
+                },
+            };
+            texts.push(PageText { page, text });
+        }
+        Ok(texts)
+    }
+
+    fn is_locked(&self) -> bool {
+        self.encryption.as_ref().is_some_and(|e| !e.readable)
+    }
+
+    /// CropBox 优先，其次是 MediaBox，均可从父节点继承
+    fn page_size(&self, id: ObjectId) -> PageSize {
+        let rect = |key: &[u8]| {
+            let values = self.inherited(id, key)?;
+            let values: Vec<f32> = self
+                .document
+                .dereference(values)
+                .ok()?
+                .1
+                .as_array()
+                .ok()?
+                .iter()
+                .filter_map(|v| self.document.dereference(v).ok()?.1.as_float().ok())
+                .collect();
+            match values[..] {
+                [x1, y1, x2, y2] => Some(((x2 - x1).abs(), (y2 - y1).abs())),
+                _ => None,
+            }
+        };
+        let (width, height) = rect(b"CropBox")
+            .or_else(|| rect(b"MediaBox"))
+            .unwrap_or(DEFAULT_PAGE_SIZE);
+        let rotation = self
+            .inherited(id, b"Rotate")
+            .and_then(|r| r.as_i64().ok())
+            .unwrap_or(0)
+            .rem_euclid(360);
+        if rotation == 90 || rotation == 270 {
+            PageSize { width: height, height: width, rotation }
+        } else {
+            PageSize { width, height, rotation }
+        }
+    }
+
+    /// 读取页面属性，沿 /Parent 向上查找可继承的值
+    fn inherited(&self, mut id: ObjectId, key: &[u8]) -> Option<&Object> {
+        let mut visited = HashSet::new();
+        while visited.insert(id) {
+            let dict = self.document.get_dictionary(id).ok()?;
+            if let Ok(value) = dict.get(key) {
+                return Some(value);
+            }
+            id = dict.get(b"Parent").and_then(Object::as_reference).ok()?;
+        }
+        None
+    }
+
+    /// 文档信息字典优先，缺少的字段由 XMP 补充；无法解密时返回 `None`
+    pub fn metadata(&self) -> Option<DocumentMetadata> {
+        if self.is_locked() {
+            return None;
+        }
+        let mut metadata = DocumentMetadata::default();
+        if let Some(info) = self
+            .document
+            .trailer
+            .get(b"Info")
+            .ok()
+            .and_then(|o| self.document.dereference(o).ok())
+            .and_then(|(_, o)| o.as_dict().ok())
+        {
+            self.info_dictionary(info, &mut metadata);
+        }
+        if let Some(xml) = self.xmp() {
+            if let Err(e) = merge_xmp(&xml, &mut metadata) {
+                log::error!("解析 XMP 元数据失败: {}", e);
+            }
+        }
+        metadata.page_count = Some(self.document.get_pages().len() as u32);
+        Some(metadata)
+    }
+
+    fn info_dictionary(&self, info: &Dictionary, metadata: &mut DocumentMetadata) {
+        for (key, value) in info.iter() {
+            let Some(text) = self
+                .document
+                .dereference(value)
+                .ok()
+                .and_then(|(_, o)| decode_text_string(o).ok())
+                .map(|t| t.trim_end_matches('\0').trim().to_string())
+                .filter(|t| !t.is_empty())
+            else {
+                continue;
+            };
+            match key.as_slice() {
+                b"Title" => metadata.title = Some(text),
+                b"Author" => metadata.author = Some(text),
+                b"Subject" => metadata.subject = Some(text),
+                b"Keywords" => metadata.keywords = split_keywords(&text),
+                b"Creator" => metadata.application = Some(text),
+                b"CreationDate" => metadata.created = pdf_date(&text).or(Some(text)),
+                b"ModDate" => metadata.modified = pdf_date(&text).or(Some(text)),
+                // Producer、Trapped 与自定义键
+                key => {
+                    let key = String::from_utf8_lossy(key).into_owned();
+                    metadata.custom.insert(key, text);
+                },
+            }
+        }
+    }
+
+    /// 文档目录中的 XMP 元数据流
+    fn xmp(&self) -> Option<String> {
+        let catalog = self.document.catalog().ok()?;
+        let (_, object) = self
+            .document
+            .dereference(catalog.get(b"Metadata").ok()?)
+            .ok()?;
+        let stream = object.as_stream().ok()?;
+        let data = stream
+            .decompressed_content()
+            .unwrap_or_else(|_| stream.content.clone());
+        Some(String::from_utf8_lossy(&data).into_owned())
+    }
+
+    fn outline(&self) -> Vec<OutlineItem> {
+        let Some(first) = self
+            .document
+            .catalog()
+            .ok()
+            .and_then(|c| c.get(b"Outlines").ok())
+            .and_then(|o| self.document.dereference(o).ok())
+            .and_then(|(_, o)| o.as_dict().ok())
+            .and_then(|d| d.get(b"First").and_then(Object::as_reference).ok())
+        else {
+            return Vec::new();
+        };
+        let pages: HashMap<ObjectId, u32> = self
+            .document
+            .get_pages()
+            .into_iter()
+            .map(|(number, id)| (id, number))
+            .collect();
+        let mut walker = OutlineWalker {
+            pdf: self,
+            pages,
+            names: None,
+            visited: HashSet::new(),
+        };
+        walker.items(first, 0)
+    }
+}
+
+/// 按 First/Next 链表遍历书签树
+struct OutlineWalker<'a> {
+    pdf: &'a Pdf,
+    /// 页面对象 -> 页码
+    pages: HashMap<ObjectId, u32>,
+    /// 命名目标，首次用到时读取
+    names: Option<HashMap<Vec<u8>, Object>>,
+    visited: HashSet<ObjectId>,
+}
+
+impl OutlineWalker<'_> {
+    fn items(&mut self, first: ObjectId, depth: usize) -> Vec<OutlineItem> {
+        let mut items = Vec::new();
+        let mut next = Some(first);
+        while let Some(id) = next {
+            if depth >= MAX_OUTLINE_DEPTH
+                || self.visited.len() >= MAX_OUTLINE_ITEMS
+                || !self.visited.insert(id)
+            {
+                break;
+            }
+            let Ok(dict) = self.pdf.document.get_dictionary(id) else {
+                break;
+            };
+            let title = dict
+                .get(b"Title")
+                .ok()
+                .and_then(|t| self.pdf.document.dereference(t).ok())
+                .and_then(|(_, t)| decode_text_string(t).ok())
+                .unwrap_or_default();
+            let page = self.target_page(dict);
+            let children = match dict.get(b"First").and_then(Object::as_reference) {
+                Ok(child) => self.items(child, depth + 1),
+                Err(_) => Vec::new(),
+            };
+            items.push(OutlineItem {
+                title: title.trim().to_string(),
+                page,
+                children,
+            });
+            next = dict.get(b"Next").and_then(Object::as_reference).ok();
+        }
+        items
+    }
+
+    /// /Dest 或 /A 中 GoTo 动作的 /D，可以是显式目标数组或命名目标
+    fn target_page(&mut self, dict: &Dictionary) -> Option<u32> {
+        let document = &self.pdf.document;
+        let dest = match dict.get(b"Dest") {
+            Ok(dest) => dest.clone(),
+            Err(_) => {
+                let (_, action) = document.dereference(dict.get(b"A").ok()?).ok()?;
+                let action = action.as_dict().ok()?;
+                if action.get(b"S").and_then(Object::as_name).ok()? != b"GoTo" {
+                    return None;
+                }
+                action.get(b"D").ok()?.clone()
+            },
+        };
+        self.dest_page(&dest, 0)
+    }
+
+    fn dest_page(&mut self, dest: &Object, depth: usize) -> Option<u32> {
+        if depth > 4 {
+            return None;
+        }
+        let pdf = self.pdf;
+        let (_, dest) = pdf.document.dereference(dest).ok()?;
+        match dest {
+            Object::Array(array) => {
+                let page = array.first()?.as_reference().ok()?;
+                self.pages.get(&page).copied()
+            },
+            // 命名目标可能是数组，也可能是带 /D 的字典
+            Object::Dictionary(dict) => {
+                let d = dict.get(b"D").ok()?.clone();
+                self.dest_page(&d, depth + 1)
+            },
+            Object::Name(name) | Object::String(name, _) => {
+                let name = name.clone();
+                let target = self.named_destinations().get(&name)?.clone();
+                self.dest_page(&target, depth + 1)
+            },
+            _ => None,
+        }
+    }
+
+    /// PDF 1.1 的 /Dests 字典与 PDF 1.2 起的 /Names /Dests 名称树
+    fn named_destinations(&mut self) -> &HashMap<Vec<u8>, Object> {
+        if self.names.is_none() {
+            let pdf = self.pdf;
+            let document = &pdf.document;
+            let mut names = HashMap::new();
+            if let Ok(catalog) = document.catalog() {
+                let dests = catalog
+                    .get(b"Dests")
+                    .ok()
+                    .and_then(|d| document.dereference(d).ok())
+                    .and_then(|(_, d)| d.as_dict().ok());
+                for (key, value) in dests.into_iter().flat_map(Dictionary::iter) {
+                    names.insert(key.clone(), value.clone());
+                }
+                let tree = catalog
+                    .get(b"Names")
+                    .ok()
+                    .and_then(|n| document.dereference(n).ok())
+                    .and_then(|(_, n)| n.as_dict().ok())
+                    .and_then(|n| n.get(b"Dests").ok());
+                if let Some(tree) = tree {
+                    name_tree(document, tree, &mut names, &mut HashSet::new(), 0);
+                }
+            }
+            self.names = Some(names);
+        }
+        self.names.get_or_insert_with(HashMap::new)
+    }
+}
+
+/// 解密所有对象中的字符串与流，包括嵌套在字典和数组中的字符串
+///
+/// lopdf 自带的 `decrypt` 只识别 V4 的加密过滤器，且不处理嵌套的字符串（如书签标题）
+fn decrypt(document: &mut Document, key: &[u8]) {
+    let Ok(encrypt_id) = document
+        .trailer
+        .get(b"Encrypt")
+        .and_then(Object::as_reference)
+    else {
+        return;
+    };
+    let (aes, metadata_encrypted) = match document.get_encrypted() {
+        Ok(dict) => {
+            let method = dict
+                .get(b"CF")
+                .and_then(Object::as_dict)
+                .and_then(|cf| cf.get(b"StdCF"))
+                .and_then(Object::as_dict)
+                .and_then(|std| std.get(b"CFM"))
+                .and_then(Object::as_name)
+                .unwrap_or_default();
+            let metadata = dict
+                .get(b"EncryptMetadata")
+                .and_then(Object::as_bool)
+                .unwrap_or(true);
+            (matches!(method, b"AESV2" | b"AESV3"), metadata)
+        },
+        Err(_) => return,
+    };
+
+    fn walk(key: &[u8], id: ObjectId, object: &mut Object, aes: bool, depth: usize) {
+        match object {
+            Object::String(..) | Object::Stream(_) => {
+                if let Ok(data) = decrypt_object(key, id, object, aes) {
+                    match object {
+                        Object::String(content, _) => *content = data,
+                        Object::Stream(stream) => stream.set_content(data),
+                        _ => {},
+                    }
+                }
+            },
+            Object::Array(items) if depth < MAX_OUTLINE_DEPTH => {
+                for item in items {
+                    walk(key, id, item, aes, depth + 1);
+                }
+            },
+            Object::Dictionary(dict) if depth < MAX_OUTLINE_DEPTH => {
+                for (_, value) in dict.iter_mut() {
+                    walk(key, id, value, aes, depth + 1);
+                }
+            },
+            _ => {},
+        }
+    }
+    for (&id, object) in document.objects.iter_mut() {
+        let kind = object.type_name().ok();
+        if id == encrypt_id
+            || kind == Some(b"XRef".as_slice())
+            || (kind == Some(b"Metadata".as_slice()) && !metadata_encrypted)
+        {
+            continue;
+        }
+        walk(key, id, object, aes, 0);
+    }
+    document.trailer.remove(b"Encrypt");
+}
+
+/// 读取名称树的叶子节点
+fn name_tree(
+    document: &Document,
+    node: &Object,
+    names: &mut HashMap<Vec<u8>, Object>,
+    visited: &mut HashSet<ObjectId>,
+    depth: usize,
+) {
+    let Ok((id, node)) = document.dereference(node) else {
+        return;
+    };
+    if depth > MAX_OUTLINE_DEPTH || id.is_some_and(|id| !visited.insert(id)) {
+        return;
+    }
+    let Ok(node) = node.as_dict() else {
+        return;
+    };
+    if let Ok(pairs) = node.get(b"Names").and_then(Object::as_array) {
+        for pair in pairs.chunks(2) {
+            if let [key, value] = pair {
+                if let Ok(key) = key.as_str() {
+                    names.insert(key.to_vec(), value.clone());
+                }
+            }
+        }
+    }
+    if let Ok(kids) = node.get(b"Kids").and_then(Object::as_array) {
+        for kid in kids {
+            name_tree(document, kid, names, visited, depth + 1);
+        }
+    }
+}
+
+/// 补充 XMP 中的 dc、xmp 与 pdf 属性，已有的字段不覆盖
+fn merge_xmp(xml: &str, metadata: &mut DocumentMetadata) -> Result<(), DocsError> {
+    // XMP 包前后可能有 xpacket 处理指令与填充空白
+    let start = xml.find("<x:xmpmeta").or_else(|| xml.find("<rdf:RDF"));
+    let xml = start.map_or(xml, |start| &xml[start..]);
+    let end = xml
+        .rfind("</x:xmpmeta>")
+        .map(|e| e + "</x:xmpmeta>".len())
+        .or_else(|| xml.rfind("</rdf:RDF>").map(|e| e + "</rdf:RDF>".len()));
+    let xml = end.map_or(xml, |end| &xml[..end]);
+    let doc = roxmltree::Document::parse(xml)?;
+
+    let mut values: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for description in doc
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "Description")
+    {
+        // 简单属性可以写成 rdf:Description 的属性
+        for attribute in description.attributes() {
+            if attribute
+                .namespace()
+                .is_some_and(|ns| !ns.contains("rdf-syntax"))
+            {
+                values
+                    .entry(attribute.name())
+                    .or_default()
+                    .push(attribute.value().trim().to_string());
+            }
+        }
+        for property in description.children().filter(Node::is_element) {
+            let items: Vec<String> = match property
+                .descendants()
+                .filter(|n| n.is_element() && n.tag_name().name() == "li")
+                .map(|li| li.text().unwrap_or_default().trim().to_string())
+                .collect::<Vec<_>>()
+            {
+                items if !items.is_empty() => items,
+                _ => vec![property.text().unwrap_or_default().trim().to_string()],
+            };
+            values
+                .entry(property.tag_name().name())
+                .or_default()
+                .extend(items.into_iter().filter(|v| !v.is_empty()));
+        }
+    }
+
+    let first = |name: &str| values.get(name).and_then(|v| v.first()).cloned();
+    metadata.title = metadata.title.take().or_else(|| first("title"));
+    metadata.author = metadata
+        .author
+        .take()
+        .or_else(|| values.get("creator").map(|c| c.join(", ")));
+    metadata.description = metadata.description.take().or_else(|| first("description"));
+    if metadata.keywords.is_empty() {
+        metadata.keywords = match values.get("subject") {
+            Some(subjects) => subjects.clone(),
+            None => first("Keywords")
+                .map(|k| split_keywords(&k))
+                .unwrap_or_default(),
+        };
+    }
+    metadata.application = metadata.application.take().or_else(|| first("CreatorTool"));
+    metadata.created = metadata.created.take().or_else(|| first("CreateDate"));
+    metadata.modified = metadata.modified.take().or_else(|| first("ModifyDate"));
+    if let Some(producer) = first("Producer") {
+        metadata
+            .custom
+            .entry("Producer".to_string())
+            .or_insert(producer);
+    }
+    Ok(())
+}
+
+/// PDF 日期 `D:YYYYMMDDHHmmSSOHH'mm'` 转为 ISO 8601，各部分从月份起均可省略
+fn pdf_date(text: &str) -> Option<String> {
+    let text = text.trim();
+    let text = text.strip_prefix("D:").unwrap_or(text);
+    let digits: String = text.chars().take_while(char::is_ascii_digit).collect();
+    if digits.len() < 4 {
+        return None;
+    }
+    let part = |start: usize, default: &str| -> String {
+        digits.get(start..start + 2).unwrap_or(default).to_string()
+    };
+    let mut date = format!(
+        "{}-{}-{}T{}:{}:{}",
+        &digits[..4],
+        part(4, "01"),
+        part(6, "01"),
+        part(8, "00"),
+        part(10, "00"),
+        part(12, "00"),
+    );
+    let zone = &text[digits.len()..];
+    match zone.chars().next() {
+        Some('Z') => date.push('Z'),
+        Some(sign @ ('+' | '-')) => {
+            let offset: String = zone[1..].chars().filter(char::is_ascii_digit).collect();
+            let hours = offset.get(..2).unwrap_or("00");
+            let minutes = offset.get(2..4).unwrap_or("00");
+            date.push_str(&format!("{}{}:{}", sign, hours, minutes));
+        },
+        _ => {},
+    }
+    Some(date)
+}
+
+/// 读取 PDF 概要
+pub fn parse(path: &str) -> Result<PdfInfo, DocsError> {
+    Ok(Pdf::open(path)?.info())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::{dictionary, StringFormat};
+
+    /// 一页空白文档，带有指定的加密字典
+    fn encrypted_pdf(name: &str, encrypt: Dictionary) -> String {
+        let mut document = Document::with_version("1.7");
+        let pages_id = document.new_object_id();
+        let page_id = document.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+        });
+        document.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page_id.into()],
+                "Count" => 1,
+            }),
+        );
+        let catalog_id = document.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        let encrypt_id = document.add_object(encrypt);
+        document.trailer.set("Root", catalog_id);
+        document.trailer.set("Encrypt", encrypt_id);
+        let id = Object::String(vec![7; 16], StringFormat::Hexadecimal);
+        document.trailer.set("ID", vec![id.clone(), id]);
+
+        let path =
+            std::env::temp_dir().join(format!("quicklook-pdf-{}-{}", std::process::id(), name));
+        document.save(&path).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn standard(version: i64, revision: i64) -> Dictionary {
+        dictionary! {
+            "Filter" => "Standard",
+            "V" => version,
+            "R" => revision,
+            "Length" => 128,
+            "O" => Object::String(vec![1; 32], StringFormat::Hexadecimal),
+            "U" => Object::String(vec![2; 32], StringFormat::Hexadecimal),
+            "P" => -4,
+        }
+    }
+
+    #[test]
+    fn unsupported_revision_is_encrypted_but_not_password_protected() {
+        let path = encrypted_pdf("aes256.pdf", standard(5, 6));
+        let info = Pdf::open(&path).unwrap().info();
+        std::fs::remove_file(&path).ok();
+
+        let encryption = info.encryption.unwrap();
+        assert!(!encryption.password_required);
+        assert!(!encryption.readable);
+        assert_eq!((encryption.version, encryption.revision), (5, 6));
+        assert_eq!(info.page_count, 1);
+    }
+
+    #[test]
+    fn wrong_empty_password_requires_password() {
+        let path = encrypted_pdf("rc4.pdf", standard(2, 3));
+        let pdf = Pdf::open(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let encryption = pdf.info().encryption.unwrap();
+        assert!(encryption.password_required);
+        assert!(!encryption.readable);
+        assert!(matches!(pdf.text(&[]), Err(DocsError::Encrypted)));
+    }
+
+    #[test]
+    fn pdf_dates() {
+        let date = |text| pdf_date(text);
+        assert_eq!(
+            date("D:20230315143000+08'00'").as_deref(),
+            Some("2023-03-15T14:30:00+08:00")
+        );
+        assert_eq!(
+            date("D:20230315143000-05'30").as_deref(),
+            Some("2023-03-15T14:30:00-05:30")
+        );
+        assert_eq!(
+            date("D:20230315143000Z").as_deref(),
+            Some("2023-03-15T14:30:00Z")
+        );
+        assert_eq!(date(" 20230315 ").as_deref(), Some("2023-03-15T00:00:00"));
+        assert_eq!(date("D:2023").as_deref(), Some("2023-01-01T00:00:00"));
+        assert_eq!(
+            date("D:202303151430+01").as_deref(),
+            Some("2023-03-15T14:30:00+01:00")
+        );
+        assert_eq!(date("D:20"), None);
+        assert_eq!(date("yesterday"), None);
+    }
+
+    const XMP: &str = r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about="" xmlns:xmp="http://ns.adobe.com/xap/1.0/"
+    xmp:CreatorTool="Writer" xmp:CreateDate="2023-03-15T14:30:00+08:00">
+   <xmp:ModifyDate>2023-03-16T09:00:00Z</xmp:ModifyDate>
+  </rdf:Description>
+  <rdf:Description rdf:about="" xmlns:dc="http://purl.org/dc/elements/1.1/"
+    xmlns:pdf="http://ns.adobe.com/pdf/1.3/">
+   <dc:title><rdf:Alt><rdf:li xml:lang="x-default"> XMP title </rdf:li></rdf:Alt></dc:title>
+   <dc:creator><rdf:Seq><rdf:li>Alice</rdf:li><rdf:li>Bob</rdf:li></rdf:Seq></dc:creator>
+   <dc:description><rdf:Alt><rdf:li xml:lang="x-default">About</rdf:li></rdf:Alt></dc:description>
+   <pdf:Producer>Printer</pdf:Producer>
+   <pdf:Keywords>alpha; beta</pdf:Keywords>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#;
+
+    #[test]
+    fn xmp_fills_missing_fields() {
+        let mut metadata = DocumentMetadata::default();
+        merge_xmp(XMP, &mut metadata).unwrap();
+        assert_eq!(metadata.title.as_deref(), Some("XMP title"));
+        assert_eq!(metadata.author.as_deref(), Some("Alice, Bob"));
+        assert_eq!(metadata.description.as_deref(), Some("About"));
+        assert_eq!(metadata.keywords, ["alpha", "beta"]);
+        assert_eq!(metadata.application.as_deref(), Some("Writer"));
+        assert_eq!(
+            metadata.created.as_deref(),
+            Some("2023-03-15T14:30:00+08:00")
+        );
+        assert_eq!(metadata.modified.as_deref(), Some("2023-03-16T09:00:00Z"));
+        assert_eq!(
+            metadata.custom.get("Producer").map(String::as_str),
+            Some("Printer")
+        );
+    }
+
+    #[test]
+    fn info_dictionary_takes_precedence() {
+        let mut metadata = DocumentMetadata {
+            title: Some("Info title".to_string()),
+            keywords: vec!["info".to_string()],
+            ..Default::default()
+        };
+        metadata
+            .custom
+            .insert("Producer".to_string(), "Info producer".to_string());
+        let subjects = XMP.replace(
+            "<pdf:Producer>",
+            "<dc:subject><rdf:Bag><rdf:li>x</rdf:li></rdf:Bag></dc:subject><pdf:Producer>",
+        );
+        merge_xmp(&subjects, &mut metadata).unwrap();
+        assert_eq!(metadata.title.as_deref(), Some("Info title"));
+        assert_eq!(metadata.keywords, ["info"]);
+        assert_eq!(metadata.custom["Producer"], "Info producer");
+
+        let mut metadata = DocumentMetadata::default();
+        merge_xmp(&subjects, &mut metadata).unwrap();
+        assert_eq!(metadata.keywords, ["x"]);
+        assert!(merge_xmp("<x:xmpmeta>", &mut metadata).is_err());
+    }
+}
@@ -0,0 +1,472 @@
+//! PowerPoint 97-2003（.ppt）：沿用户编辑链定位各幻灯片，提取文本框的文字与位置
+
+use crate::{
+    document::{Block, Inline, ListInfo, Paragraph, Run},
+    error::DocsError,
+    metadata::DocumentMetadata,
+    ole::{self, i32_at, u16_at, u32_at, utf16_text},
+    slides::{Frame, Presentation, Shape, ShapeContent, Slide},
+};
+use encoding_rs::WINDOWS_1252;
+use std::collections::{HashMap, HashSet};
+
+const DOCUMENT_STREAM: &str = "PowerPoint Document";
+const CURRENT_USER_STREAM: &str = "Current User";
+/// 加密文档的 CurrentUserAtom.headerToken
+const ENCRYPTED_TOKEN: u32 = 0xF3D1_C4DF;
+/// 每像素的主单位数（576 / 英寸）
+const MASTER_UNITS_PER_PX: f32 = 6.0;
+const DEFAULT_SIZE: (f32, f32) = (960.0, 720.0);
+/// 容器的最大嵌套层数，防止构造的文件耗尽栈
+const MAX_DEPTH: usize = 64;
+
+/// 用到的记录类型
+const DOCUMENT: u16 = 0x03E8;
+const DOCUMENT_ATOM: u16 = 0x03E9;
+const SLIDE: u16 = 0x03EE;
+const NOTES: u16 = 0x03F0;
+const NOTES_ATOM: u16 = 0x03F1;
+const SLIDE_PERSIST_ATOM: u16 = 0x03F3;
+const SLIDE_LIST_WITH_TEXT: u16 = 0x0FF0;
+const USER_EDIT_ATOM: u16 = 0x0FF5;
+const PERSIST_DIRECTORY_ATOM: u16 = 0x1772;
+const OUTLINE_TEXT_REF_ATOM: u16 = 0x0F9E;
+const TEXT_HEADER_ATOM: u16 = 0x0F9F;
+const TEXT_CHARS_ATOM: u16 = 0x0FA0;
+const TEXT_BYTES_ATOM: u16 = 0x0FA8;
+const SP_CONTAINER: u16 = 0xF004;
+const CLIENT_ANCHOR: u16 = 0xF010;
+const CLIENT_TEXTBOX: u16 = 0xF00D;
+
+/// SlideListWithText 的 instance
+const SLIDE_LIST: u16 = 0;
+const NOTES_LIST: u16 = 2;
+
+/// TextHeaderAtom 中的文本类型
+const TEXT_TITLE: u32 = 0;
+const TEXT_BODY: u32 = 1;
+const TEXT_CENTER_TITLE: u32 = 6;
+
+/// 记录：8 字节的头与数据
+#[derive(Clone, Copy)]
+struct Record<'a> {
+    /// recVer 为 0xF 的是容器
+    container: bool,
+    instance: u16,
+    kind: u16,
+    data: &'a [u8],
+}
+
+impl<'a> Record<'a> {
+    /// 读取 `offset` 处的记录
+    fn at(stream: &'a [u8], offset: usize) -> Option<Self> {
+        let header = u16_at(stream, offset)?;
+        let kind = u16_at(stream, offset + 2)?;
+        let len = u32_at(stream, offset + 4)? as usize;
+        let start = offset.checked_add(8)?;
+        let data = stream.get(start..start.checked_add(len)?)?;
+        Some(Record {
+            container: header & 0xF == 0xF,
+            instance: header >> 4,
+            kind,
+            data,
+        })
+    }
+
+    /// 依次读取子记录
+    fn children(&self) -> impl Iterator<Item = Record<'a>> {
+        let data = if self.container { self.data } else { &[] };
+        let mut offset = 0;
+        std::iter::from_fn(move || {
+            let record = Record::at(data, offset)?;
+            offset += 8 + record.data.len();
+            Some(record)
+        })
+    }
+
+    fn child(&self, kind: u16) -> Option<Record<'a>> {
+        self.children().find(|r| r.kind == kind)
+    }
+
+    /// 文本原子中的文字
+    fn text(&self) -> Option<String> {
+        match self.kind {
+            TEXT_CHARS_ATOM => Some(utf16_text(self.data)),
+            TEXT_BYTES_ATOM => Some(WINDOWS_1252.decode_without_bom_handling(self.data).0.into()),
+            _ => None,
+        }
+    }
+}
+
+/// 带类型的一段文本
+struct Text {
+    kind: u32,
+    text: String,
+}
+
+/// SlideListWithText 中的一项
+struct SlideEntry {
+    persist_id: u32,
+    slide_id: u32,
+    texts: Vec<Text>,
+}
+
+/// 解析 ppt 文件
+pub fn parse(path: &str) -> Result<Presentation, DocsError> {
+    let mut cfb = ole::open(path)?;
+    let stream = ole::read_stream(&mut cfb, DOCUMENT_STREAM)?
+        .ok_or_else(|| DocsError::missing_part(DOCUMENT_STREAM))?;
+    let current_edit = match ole::read_stream(&mut cfb, CURRENT_USER_STREAM)? {
+        Some(user) => {
+            if u32_at(&user, 12) == Some(ENCRYPTED_TOKEN) {
+                return Err(DocsError::Encrypted);
+            }
+            u32_at(&user, 16)
+        },
+        None => None,
+    }
+    .filter(|&offset| {
+        Record::at(&stream, offset as usize).is_some_and(|r| r.kind == USER_EDIT_ATOM)
+    })
+    .or_else(|| last_user_edit(&stream))
+    .ok_or_else(|| corrupt("找不到用户编辑记录"))?;
+
+    let (directory, document_ref) = persist_directory(&stream, current_edit);
+    let lookup = |id: u32| -> Option<Record> { Record::at(&stream, *directory.get(&id)? as usize) };
+    let document = lookup(document_ref)
+        .filter(|r| r.kind == DOCUMENT)
+        .ok_or_else(|| corrupt("找不到文档记录"))?;
+
+    let (width, height) = document
+        .child(DOCUMENT_ATOM)
+        .and_then(|atom| Some((i32_at(atom.data, 0)?, i32_at(atom.data, 4)?)))
+        .map(|(x, y)| {
+            (
+                x as f32 / MASTER_UNITS_PER_PX,
+                y as f32 / MASTER_UNITS_PER_PX,
+            )
+        })
+        .unwrap_or(DEFAULT_SIZE);
+
+    let list = |instance: u16| -> Vec<SlideEntry> {
+        document
+            .children()
+            .find(|r| r.kind == SLIDE_LIST_WITH_TEXT && r.instance == instance)
+            .map(|r| slide_list(&r))
+            .unwrap_or_default()
+    };
+
+    // 备注按所属幻灯片的 id 索引
+    let mut notes: HashMap<u32, Vec<Block>> = HashMap::new();
+    for entry in list(NOTES_LIST) {
+        let Some(record) = lookup(entry.persist_id).filter(|r| r.kind == NOTES) else {
+            continue;
+        };
+        let Some(slide_id) = record.child(NOTES_ATOM).and_then(|a| u32_at(a.data, 0)) else {
+            continue;
+        };
+        let mut texts = Vec::new();
+        collect_texts(&record, &mut texts, 0);
+        let blocks = texts
+            .iter()
+            .flat_map(|t| text_blocks(&t.text, false))
+            .collect();
+        notes.insert(slide_id, blocks);
+    }
+
+    let mut slides = Vec::new();
+    for (index, entry) in list(SLIDE_LIST).into_iter().enumerate() {
+        let mut slide = match lookup(entry.persist_id).filter(|r| r.kind == SLIDE) {
+            Some(record) => parse_slide(&record, &entry.texts),
+            None => {
+                log::error!("幻灯片记录不存在: {}", entry.persist_id);
+                Slide::default()
+            },
+        };
+        slide.index = index + 1;
+        slide.notes = notes.remove(&entry.slide_id).unwrap_or_default();
+        slides.push(slide);
+    }
+
+    let mut metadata = DocumentMetadata::from_ole(&mut cfb)
+        .map_err(|e| log::error!("读取文档属性失败: {}", e))
+        .ok()
+        .flatten();
+    if let Some(metadata) = metadata.as_mut() {
+        metadata.slide_count.get_or_insert(slides.len() as u32);
+    }
+    Ok(Presentation { width, height, slides, metadata })
+}
+
+fn corrupt(reason: &str) -> DocsError {
+    DocsError::corrupt(reason).in_part(DOCUMENT_STREAM)
+}
+
+/// 缺少 Current User 流时，取流中最后一个用户编辑记录
+fn last_user_edit(stream: &[u8]) -> Option<u32> {
+    let mut offset = 0;
+    let mut last = None;
+    while let Some(record) = Record::at(stream, offset) {
+        if record.kind == USER_EDIT_ATOM {
+            last = Some(offset as u32);
+        }
+        offset += 8 + record.data.len();
+    }
+    last
+}
+
+/// 沿编辑链合并持久化目录，较新的编辑优先；返回目录与文档记录的持久化 id
+fn persist_directory(stream: &[u8], current_edit: u32) -> (HashMap<u32, u32>, u32) {
+    let mut directory = HashMap::new();
+    let mut document_ref = None;
+    let mut visited = HashSet::new();
+    let mut edit = current_edit;
+    while visited.insert(edit) {
+        let Some(atom) = Record::at(stream, edit as usize).filter(|r| r.kind == USER_EDIT_ATOM)
+        else {
+            break;
+        };
+        let (Some(last_edit), Some(persist_offset)) = (u32_at(atom.data, 8), u32_at(atom.data, 12))
+        else {
+            break;
+        };
+        if document_ref.is_none() {
+            document_ref = u32_at(atom.data, 16);
+        }
+        if let Some(persist) =
+            Record::at(stream, persist_offset as usize).filter(|r| r.kind == PERSIST_DIRECTORY_ATOM)
+        {
+            let data = persist.data;
+            let mut offset = 0;
+            while let Some(entry) = u32_at(data, offset) {
+                let (base, count) = (entry & 0x000F_FFFF, entry >> 20);
+                for i in 0..count {
+                    let Some(target) = u32_at(data, offset + 4 + i as usize * 4) else {
+                        break;
+                    };
+                    directory.entry(base + i).or_insert(target);
+                }
+                offset += 4 + count as usize * 4;
+            }
+        }
+        if last_edit == 0 {
+            break;
+        }
+        edit = last_edit;
+    }
+    (directory, document_ref.unwrap_or(1))
+}
+
+/// SlideListWithText：每个 SlidePersistAtom 后跟随该幻灯片的大纲文本
+fn slide_list(list: &Record) -> Vec<SlideEntry> {
+    let mut entries: Vec<SlideEntry> = Vec::new();
+    let mut kind = TEXT_BODY;
+    for record in list.children() {
+        match record.kind {
+            SLIDE_PERSIST_ATOM => entries.push(SlideEntry {
+                persist_id: u32_at(record.data, 0).unwrap_or(0),
+                slide_id: u32_at(record.data, 12).unwrap_or(0),
+                texts: Vec::new(),
+            }),
+            TEXT_HEADER_ATOM => kind = u32_at(record.data, 0).unwrap_or(TEXT_BODY),
+            _ => {
+                if let (Some(text), Some(entry)) = (record.text(), entries.last_mut()) {
+                    entry.texts.push(Text { kind, text });
+                }
+            },
+        }
+    }
+    entries
+}
+
+fn parse_slide(record: &Record, outline: &[Text]) -> Slide {
+    let mut slide = Slide::default();
+    let mut used = HashSet::new();
+    let mut containers = Vec::new();
+    collect_shapes(record, &mut containers, 0);
+
+    for sp in containers {
+        let Some(textbox) = sp.child(CLIENT_TEXTBOX) else {
+            continue;
+        };
+        // 文本框引用大纲中的文本，或者自带文本原子
+        let mut texts = Vec::new();
+        let mut kind = TEXT_BODY;
+        for child in textbox.children() {
+            match child.kind {
+                OUTLINE_TEXT_REF_ATOM => {
+                    let index = u32_at(child.data, 0).unwrap_or(u32::MAX) as usize;
+                    if let Some(text) = outline.get(index) {
+                        used.insert(index);
+                        texts.push((text.kind, text.text.clone()));
+                    }
+                },
+                TEXT_HEADER_ATOM => kind = u32_at(child.data, 0).unwrap_or(TEXT_BODY),
+                _ => {
+                    if let Some(text) = child.text() {
+                        texts.push((kind, text));
+                    }
+                },
+            }
+        }
+        if texts.is_empty() {
+            continue;
+        }
+        let frame = sp
+            .child(CLIENT_ANCHOR)
+            .and_then(|anchor| anchor_frame(anchor.data));
+        push_texts(&mut slide, frame, texts);
+    }
+
+    // 未被任何文本框引用的大纲文本
+    for (index, text) in outline.iter().enumerate() {
+        if !used.contains(&index) {
+            push_texts(&mut slide, None, vec![(text.kind, text.text.clone())]);
+        }
+    }
+    slide
+}
+
+fn push_texts(slide: &mut Slide, frame: Option<Frame>, texts: Vec<(u32, String)>) {
+    let mut blocks = Vec::new();
+    for (kind, text) in texts {
+        if slide.title.is_none() && matches!(kind, TEXT_TITLE | TEXT_CENTER_TITLE) {
+            let title = text.replace(['\r', '\u{b}'], " ").trim().to_string();
+            slide.title = (!title.is_empty()).then_some(title);
+        }
+        blocks.extend(text_blocks(&text, kind == TEXT_BODY));
+    }
+    slide.shapes.push(Shape {
+        name: None,
+        frame,
+        content: ShapeContent::Text { blocks },
+    });
+}
+
+/// 递归收集绘图中的形状容器（含组合中的形状）
+fn collect_shapes<'a>(record: &Record<'a>, shapes: &mut Vec<Record<'a>>, depth: usize) {
+    if depth > MAX_DEPTH {
+        return;
+    }
+    for child in record.children() {
+        if child.kind == SP_CONTAINER {
+            shapes.push(child);
+        }
+        if child.container {
+            collect_shapes(&child, shapes, depth + 1);
+        }
+    }
+}
+
+/// 递归收集所有文本原子
+fn collect_texts(record: &Record, texts: &mut Vec<Text>, depth: usize) {
+    if depth > MAX_DEPTH {
+        return;
+    }
+    let mut kind = TEXT_BODY;
+    for child in record.children() {
+        match child.kind {
+            TEXT_HEADER_ATOM => kind = u32_at(child.data, 0).unwrap_or(TEXT_BODY),
+            _ if child.container => collect_texts(&child, texts, depth + 1),
+            _ => {
+                if let Some(text) = child.text() {
+                    texts.push(Text { kind, text });
+                }
+            },
+        }
+    }
+}
+
+/// ClientAnchor：8 字节（i16）或 16 字节（i32）的 top/left/right/bottom
+fn anchor_frame(data: &[u8]) -> Option<Frame> {
+    let [top, left, right, bottom] = match data.len() {
+        8 => [0, 2, 4, 6].map(|i| u16_at(data, i).map(|v| v as i16 as f32)),
+        16 => [0, 4, 8, 12].map(|i| i32_at(data, i).map(|v| v as f32)),
+        _ => return None,
+    };
+    let (top, left, right, bottom) = (top?, left?, right?, bottom?);
+    Some(Frame {
+        x: left / MASTER_UNITS_PER_PX,
+        y: top / MASTER_UNITS_PER_PX,
+        width: (right - left) / MASTER_UNITS_PER_PX,
+        height: (bottom - top) / MASTER_UNITS_PER_PX,
+    })
+}
+
+/// 段落以 `\r` 分隔，`\v` 是段内换行
+fn text_blocks(text: &str, bulleted: bool) -> Vec<Block> {
+    let mut blocks: Vec<Block> = text
+        .split('\r')
+        .map(|line| {
+            let mut inlines = Vec::new();
+            for (i, part) in line.split('\u{b}').enumerate() {
+                if i > 0 {
+                    inlines.push(Inline::Break);
+                }
+                if !part.is_empty() {
+                    inlines.push(Inline::Text(Run {
+                        text: part.to_string(),
+                        ..Default::default()
+                    }));
+                }
+            }
+            Block::Paragraph(Paragraph {
+                list: (bulleted && !inlines.is_empty())
+                    .then_some(ListInfo { level: 0, ordered: false }),
+                inlines,
+                ..Default::default()
+            })
+        })
+        .collect();
+    while matches!(blocks.last(), Some(Block::Paragraph(p)) if p.inlines.is_empty()) {
+        blocks.pop();
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(container: bool, kind: u16, data: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(if container { 0xFu16 } else { 0 }).to_le_bytes());
+        bytes.extend_from_slice(&kind.to_le_bytes());
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn truncated_record() {
+        let mut bytes = record(false, TEXT_BYTES_ATOM, b"abc");
+        bytes.truncate(9);
+        assert!(Record::at(&bytes, 0).is_none());
+        assert!(Record::at(&bytes, usize::MAX - 4).is_none());
+    }
+
+    #[test]
+    fn deep_nesting_is_bounded() {
+        let mut bytes = record(false, TEXT_BYTES_ATOM, b"deep");
+        for _ in 0..10_000 {
+            bytes = record(true, SP_CONTAINER, &bytes);
+        }
+        let root = Record::at(&bytes, 0).unwrap();
+        let mut texts = Vec::new();
+        collect_texts(&root, &mut texts, 0);
+        assert!(texts.is_empty());
+        let mut shapes = Vec::new();
+        collect_shapes(&root, &mut shapes, 0);
+        assert_eq!(shapes.len(), MAX_DEPTH + 1);
+    }
+
+    #[test]
+    fn nested_text() {
+        let atom = record(false, TEXT_BYTES_ATOM, b"caf\xe9");
+        let root = record(true, SLIDE, &record(true, SP_CONTAINER, &atom));
+        let mut texts = Vec::new();
+        collect_texts(&Record::at(&root, 0).unwrap(), &mut texts, 0);
+        assert_eq!(texts.len(), 1);
+        assert_eq!(texts[0].text, "café");
+    }
+}
@@ -0,0 +1,500 @@
+use crate::{
+    document::{
+        plain_text, Block, Image, Inline, ListInfo, Paragraph, Run, Table, TableCell, TableRow,
+    },
+    error::DocsError,
+    metadata::DocumentMetadata,
+    ooxml::{attr, child, emu_to_px, is, rel_attr, Package, Relationship},
+    slides::{Frame, Presentation, Shape, ShapeContent, Slide},
+};
+use roxmltree::Node;
+use std::collections::HashMap;
+
+const PRESENTATION_PART: &str = "ppt/presentation.xml";
+
+/// 默认幻灯片尺寸 10in x 7.5in
+const DEFAULT_SIZE: (f32, f32) = (960.0, 720.0);
+
+/// 版式/母版中占位符的位置，键为 `idx:<n>` 或 `type:<t>`
+type Placeholders = HashMap<String, Frame>;
+
+/// 组合形状内子形状坐标到幻灯片坐标的变换
+#[derive(Debug, Clone, Copy)]
+struct Transform {
+    scale_x: f32,
+    scale_y: f32,
+    offset_x: f32,
+    offset_y: f32,
+}
+
+impl Transform {
+    const IDENTITY: Transform = Transform {
+        scale_x: 1.0,
+        scale_y: 1.0,
+        offset_x: 0.0,
+        offset_y: 0.0,
+    };
+
+    fn apply(&self, frame: Frame) -> Frame {
+        Frame {
+            x: frame.x * self.scale_x + self.offset_x,
+            y: frame.y * self.scale_y + self.offset_y,
+            width: frame.width * self.scale_x,
+            height: frame.height * self.scale_y,
+        }
+    }
+}
+
+/// 占位符信息
+struct Placeholder {
+    kind: String,
+    idx: Option<String>,
+}
+
+struct Parser {
+    package: Package,
+    /// 已解析的版式/母版，键为部件路径
+    layouts: HashMap<String, (Placeholders, Option<String>)>,
+}
+
+/// 解析 pptx 文件
+pub fn parse(path: &str) -> Result<Presentation, DocsError> {
+    let mut package = Package::open(path)?;
+    let xml = package
+        .read_string(PRESENTATION_PART)?
+        .ok_or_else(|| DocsError::missing_part(PRESENTATION_PART))?;
+    let doc = roxmltree::Document::parse(&xml)?;
+    let rels = package.relationships(PRESENTATION_PART)?;
+    let root = doc.root_element();
+
+    let (width, height) = child(&root, "sldSz")
+        .and_then(|n| Some((emu_to_px(attr(&n, "cx")?)?, emu_to_px(attr(&n, "cy")?)?)))
+        .unwrap_or(DEFAULT_SIZE);
+
+    let targets: Vec<String> = child(&root, "sldIdLst")
+        .map(|list| {
+            list.children()
+                .filter(|n| is(n, "sldId"))
+                .filter_map(|n| rel_attr(&n, "id").and_then(|id| rels.get(id)))
+                .map(|r| r.target.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut parser = Parser { package, layouts: HashMap::new() };
+    let mut slides = Vec::with_capacity(targets.len());
+    for (index, target) in targets.iter().enumerate() {
+        match parser.slide(target, index + 1) {
+            Ok(slide) => slides.push(slide),
+            Err(e) => {
+                log::error!("解析幻灯片失败: {}: {}", target, e);
+                slides.push(Slide { index: index + 1, ..Default::default() });
+            },
+        }
+    }
+
+    let mut metadata = DocumentMetadata::from_ooxml(&mut parser.package)
+        .map_err(|e| log::error!("读取文档属性失败: {}", e))
+        .ok()
+        .flatten();
+    if let Some(metadata) = metadata.as_mut() {
+        metadata.slide_count.get_or_insert(slides.len() as u32);
+    }
+    Ok(Presentation { width, height, slides, metadata })
+}
+
+impl Parser {
+    fn slide(&mut self, part: &str, index: usize) -> Result<Slide, DocsError> {
+        let xml = self.package.read_string(part)?.unwrap_or_default();
+        let doc = roxmltree::Document::parse(&xml)?;
+        let rels = self.package.relationships(part)?;
+        let root = doc.root_element();
+
+        let layout = rels
+            .values()
+            .find(|r| r.kind == "slideLayout")
+            .map(|r| r.target.clone());
+        let placeholders = match layout {
+            Some(layout) => self.placeholders(&layout)?,
+            None => Vec::new(),
+        };
+
+        let mut slide = Slide {
+            index,
+            hidden: attr(&root, "show") == Some("0"),
+            ..Default::default()
+        };
+        if let Some(tree) = child(&root, "cSld").and_then(|n| child(&n, "spTree")) {
+            self.shapes(tree, &rels, &placeholders, Transform::IDENTITY, &mut slide);
+        }
+
+        if let Some(notes) = rels.values().find(|r| r.kind == "notesSlide") {
+            let target = notes.target.clone();
+            slide.notes = self.notes(&target)?;
+        }
+        Ok(slide)
+    }
+
+    /// 读取版式及其母版的占位符位置，版式优先
+    fn placeholders(&mut self, layout: &str) -> Result<Vec<Placeholders>, DocsError> {
+        let mut chain = Vec::new();
+        let mut next = Some(layout.to_string());
+        // 版式 -> 母版，最多两层
+        while let Some(part) = next.take().filter(|_| chain.len() < 2) {
+            if !self.layouts.contains_key(&part) {
+                let loaded = self.load_placeholders(&part)?;
+                self.layouts.insert(part.clone(), loaded);
+            }
+            let (placeholders, parent) = &self.layouts[&part];
+            chain.push(placeholders.clone());
+            next = parent.clone();
+        }
+        Ok(chain)
+    }
+
+    fn load_placeholders(
+        &mut self,
+        part: &str,
+    ) -> Result<(Placeholders, Option<String>), DocsError> {
+        let Some(xml) = self.package.read_string(part)? else {
+            return Ok((HashMap::new(), None));
+        };
+        let doc = roxmltree::Document::parse(&xml)?;
+        let rels = self.package.relationships(part)?;
+        let master = rels
+            .values()
+            .find(|r| r.kind == "slideMaster")
+            .map(|r| r.target.clone());
+
+        let mut placeholders = HashMap::new();
+        for sp in doc.descendants().filter(|n| is(n, "sp")) {
+            let (Some(ph), Some(frame)) = (placeholder(&sp), shape_frame(&sp, "spPr")) else {
+                continue;
+            };
+            if let Some(idx) = ph.idx {
+                placeholders.entry(format!("idx:{}", idx)).or_insert(frame);
+            }
+            placeholders
+                .entry(format!("type:{}", ph.kind))
+                .or_insert(frame);
+        }
+        Ok((placeholders, master))
+    }
+
+    fn shapes(
+        &mut self,
+        tree: Node,
+        rels: &HashMap<String, Relationship>,
+        placeholders: &[Placeholders],
+        transform: Transform,
+        slide: &mut Slide,
+    ) {
+        for node in tree.children().filter(Node::is_element) {
+            match node.tag_name().name() {
+                "sp" => {
+                    let ph = placeholder(&node);
+                    let frame = shape_frame(&node, "spPr")
+                        .map(|f| transform.apply(f))
+                        .or_else(|| ph.as_ref().and_then(|ph| inherited_frame(ph, placeholders)));
+                    let Some(body) = child(&node, "txBody") else {
+                        continue;
+                    };
+                    let is_title = ph
+                        .as_ref()
+                        .is_some_and(|ph| matches!(ph.kind.as_str(), "title" | "ctrTitle"));
+                    // 正文/内容占位符默认带项目符号
+                    let bulleted = ph
+                        .as_ref()
+                        .is_some_and(|ph| matches!(ph.kind.as_str(), "body" | "obj"));
+                    let blocks = text_body(&body, rels, bulleted);
+                    if blocks.is_empty() {
+                        continue;
+                    }
+                    if is_title && slide.title.is_none() {
+                        let title = plain_text(&blocks);
+                        slide.title = Some(title).filter(|t| !t.is_empty());
+                    }
+                    slide.shapes.push(Shape {
+                        name: shape_name(&node, "nvSpPr"),
+                        frame,
+                        content: ShapeContent::Text { blocks },
+                    });
+                },
+                "pic" => {
+                    let Some(mut image) = self.picture(&node, rels) else {
+                        continue;
+                    };
+                    let frame = shape_frame(&node, "spPr").map(|f| transform.apply(f));
+                    image.width = frame.map(|f| f.width);
+                    image.height = frame.map(|f| f.height);
+                    slide.shapes.push(Shape {
+                        name: shape_name(&node, "nvPicPr"),
+                        frame,
+                        content: ShapeContent::Image(image),
+                    });
+                },
+                "graphicFrame" => {
+                    let Some(tbl) = node.descendants().find(|n| is(n, "tbl")) else {
+                        continue;
+                    };
+                    slide.shapes.push(Shape {
+                        name: shape_name(&node, "nvGraphicFramePr"),
+                        frame: xfrm_frame(child(&node, "xfrm")).map(|f| transform.apply(f)),
+                        content: ShapeContent::Table(table(&tbl, rels)),
+                    });
+                },
+                "grpSp" => {
+                    let inner = child(&node, "grpSpPr")
+                        .and_then(|p| child(&p, "xfrm"))
+                        .and_then(|x| group_transform(&x))
+                        .map(|t| Transform {
+                            scale_x: t.scale_x * transform.scale_x,
+                            scale_y: t.scale_y * transform.scale_y,
+                            offset_x: t.offset_x * transform.scale_x + transform.offset_x,
+                            offset_y: t.offset_y * transform.scale_y + transform.offset_y,
+                        })
+                        .unwrap_or(transform);
+                    self.shapes(node, rels, placeholders, inner, slide);
+                },
+                "AlternateContent" => {
+                    if let Some(choice) =
+                        child(&node, "Choice").or_else(|| child(&node, "Fallback"))
+                    {
+                        self.shapes(choice, rels, placeholders, transform, slide);
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+
+    fn picture(&mut self, node: &Node, rels: &HashMap<String, Relationship>) -> Option<Image> {
+        let blip = node.descendants().find(|n| is(n, "blip"))?;
+        let rel = rels
+            .get(rel_attr(&blip, "embed")?)
+            .filter(|r| !r.external)?;
+        let src = self.package.image_data_uri(&rel.target)?;
+        let alt = child(node, "nvPicPr")
+            .and_then(|n| child(&n, "cNvPr"))
+            .and_then(|n| attr(&n, "descr"))
+            .filter(|s| !s.is_empty())
+            .map(str::to_string);
+        Some(Image { src, alt, ..Default::default() })
+    }
+
+    /// 读取备注页中正文占位符的文字
+    fn notes(&mut self, part: &str) -> Result<Vec<Block>, DocsError> {
+        let Some(xml) = self.package.read_string(part)? else {
+            return Ok(Vec::new());
+        };
+        let doc = roxmltree::Document::parse(&xml)?;
+        let rels = self.package.relationships(part)?;
+        let blocks = doc
+            .descendants()
+            .filter(|n| is(n, "sp"))
+            .filter(|n| placeholder(n).is_some_and(|ph| ph.kind == "body"))
+            .filter_map(|n| child(&n, "txBody"))
+            .flat_map(|body| text_body(&body, &rels, false))
+            .collect();
+        Ok(blocks)
+    }
+}
+
+fn placeholder(sp: &Node) -> Option<Placeholder> {
+    let ph = sp
+        .children()
+        .find(|n| n.tag_name().name().starts_with("nv"))
+        .and_then(|nv| child(&nv, "nvPr"))
+        .and_then(|nv| child(&nv, "ph"))?;
+    Some(Placeholder {
+        // 未指定类型的占位符为内容占位符
+        kind: attr(&ph, "type").unwrap_or("obj").to_string(),
+        idx: attr(&ph, "idx").map(str::to_string),
+    })
+}
+
+fn inherited_frame(ph: &Placeholder, chain: &[Placeholders]) -> Option<Frame> {
+    // 内容占位符在母版中对应正文占位符
+    let kind = if ph.kind == "obj" {
+        "body"
+    } else {
+        ph.kind.as_str()
+    };
+    let kind = if kind == "ctrTitle" { "title" } else { kind };
+    chain.iter().find_map(|placeholders| {
+        ph.idx
+            .as_ref()
+            .and_then(|idx| placeholders.get(&format!("idx:{}", idx)))
+            .or_else(|| placeholders.get(&format!("type:{}", ph.kind)))
+            .or_else(|| placeholders.get(&format!("type:{}", kind)))
+            .copied()
+    })
+}
+
+fn shape_name(node: &Node, nv: &str) -> Option<String> {
+    child(node, nv)
+        .and_then(|n| child(&n, "cNvPr"))
+        .and_then(|n| attr(&n, "name"))
+        .map(str::to_string)
+}
+
+fn shape_frame(node: &Node, properties: &str) -> Option<Frame> {
+    xfrm_frame(child(node, properties).and_then(|p| child(&p, "xfrm")))
+}
+
+fn xfrm_frame(xfrm: Option<Node>) -> Option<Frame> {
+    let xfrm = xfrm?;
+    let off = child(&xfrm, "off")?;
+    let ext = child(&xfrm, "ext")?;
+    Some(Frame {
+        x: emu_to_px(attr(&off, "x")?)?,
+        y: emu_to_px(attr(&off, "y")?)?,
+        width: emu_to_px(attr(&ext, "cx")?)?,
+        height: emu_to_px(attr(&ext, "cy")?)?,
+    })
+}
+
+/// 组合形状：子坐标 c 映射为 off + (c - chOff) * ext / chExt
+fn group_transform(xfrm: &Node) -> Option<Transform> {
+    let frame = xfrm_frame(Some(*xfrm))?;
+    let ch_off = child(xfrm, "chOff")?;
+    let ch_ext = child(xfrm, "chExt")?;
+    let ch_x = emu_to_px(attr(&ch_off, "x")?)?;
+    let ch_y = emu_to_px(attr(&ch_off, "y")?)?;
+    let ch_w = emu_to_px(attr(&ch_ext, "cx")?)?;
+    let ch_h = emu_to_px(attr(&ch_ext, "cy")?)?;
+    let scale_x = if ch_w > 0.0 { frame.width / ch_w } else { 1.0 };
+    let scale_y = if ch_h > 0.0 { frame.height / ch_h } else { 1.0 };
+    Some(Transform {
+        scale_x,
+        scale_y,
+        offset_x: frame.x - ch_x * scale_x,
+        offset_y: frame.y - ch_y * scale_y,
+    })
+}
+
+/// 解析 DrawingML 文本体（`a:txBody`/`p:txBody`）
+fn text_body(body: &Node, rels: &HashMap<String, Relationship>, bulleted: bool) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    for p in body.children().filter(|n| is(n, "p")) {
+        let mut paragraph = Paragraph::default();
+        let ppr = child(&p, "pPr");
+        if let Some(ppr) = ppr {
+            paragraph.align = attr(&ppr, "algn").and_then(|a| match a {
+                "l" => Some("left".to_string()),
+                "ctr" => Some("center".to_string()),
+                "r" => Some("right".to_string()),
+                "just" | "dist" => Some("justify".to_string()),
+                _ => None,
+            });
+        }
+        let level = ppr
+            .and_then(|n| attr(&n, "lvl"))
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        let bullet = ppr.and_then(|n| {
+            n.children()
+                .find(|c| is(c, "buChar") || is(c, "buAutoNum") || is(c, "buNone"))
+        });
+        paragraph.list = match bullet.map(|b| b.tag_name().name()) {
+            Some("buChar") => Some(ListInfo { level, ordered: false }),
+            Some("buAutoNum") => Some(ListInfo { level, ordered: true }),
+            Some(_) => None,
+            None => bulleted.then_some(ListInfo { level, ordered: false }),
+        };
+
+        for node in p.children().filter(Node::is_element) {
+            match node.tag_name().name() {
+                "r" | "fld" => {
+                    let mut run = Run {
+                        text: child(&node, "t")
+                            .and_then(|t| t.text())
+                            .unwrap_or_default()
+                            .to_string(),
+                        ..Default::default()
+                    };
+                    if let Some(rpr) = child(&node, "rPr") {
+                        run_properties(&rpr, rels, &mut run);
+                    }
+                    if !run.text.is_empty() {
+                        paragraph.inlines.push(Inline::Text(run));
+                    }
+                },
+                "br" => paragraph.inlines.push(Inline::Break),
+                _ => {},
+            }
+        }
+
+        // 空段落不显示项目符号
+        if paragraph.inlines.is_empty() {
+            paragraph.list = None;
+        }
+        blocks.push(Block::Paragraph(paragraph));
+    }
+
+    // 去掉末尾的空段落
+    while matches!(blocks.last(), Some(Block::Paragraph(p)) if p.inlines.is_empty()) {
+        blocks.pop();
+    }
+    blocks
+}
+
+fn run_properties(rpr: &Node, rels: &HashMap<String, Relationship>, run: &mut Run) {
+    let flag = |name: &str| matches!(attr(rpr, name), Some("1" | "true"));
+    run.bold = flag("b");
+    run.italic = flag("i");
+    run.underline = attr(rpr, "u").is_some_and(|u| u != "none");
+    run.strike = attr(rpr, "strike").is_some_and(|s| s != "noStrike");
+    match attr(rpr, "baseline").and_then(|b| b.parse::<i32>().ok()) {
+        Some(b) if b > 0 => run.superscript = true,
+        Some(b) if b < 0 => run.subscript = true,
+        _ => {},
+    }
+    // sz 以百分之一磅为单位
+    run.size = attr(rpr, "sz")
+        .and_then(|s| s.parse::<f32>().ok())
+        .map(|s| s / 100.0);
+    run.color = child(rpr, "solidFill")
+        .and_then(|f| child(&f, "srgbClr"))
+        .and_then(|c| attr(&c, "val"))
+        .filter(|v| v.len() == 6 && v.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(|v| format!("#{}", v.to_ascii_uppercase()));
+    run.font = child(rpr, "latin")
+        .or_else(|| child(rpr, "ea"))
+        .and_then(|n| attr(&n, "typeface"))
+        .filter(|t| !t.starts_with('+'))
+        .map(str::to_string);
+    run.href = child(rpr, "hlinkClick")
+        .and_then(|h| rel_attr(&h, "id"))
+        .and_then(|id| rels.get(id))
+        .filter(|r| r.external)
+        .map(|r| r.target.clone());
+}
+
+fn table(tbl: &Node, rels: &HashMap<String, Relationship>) -> Table {
+    let mut table = Table::default();
+    for tr in tbl.children().filter(|n| is(n, "tr")) {
+        let mut row = TableRow::default();
+        for tc in tr.children().filter(|n| is(n, "tc")) {
+            // 被合并的单元格
+            if attr(&tc, "hMerge") == Some("1") || attr(&tc, "vMerge") == Some("1") {
+                continue;
+            }
+            let span = |name: &str| {
+                attr(&tc, name)
+                    .and_then(|v| v.parse::<u32>().ok())
+                    .unwrap_or(1)
+                    .max(1)
+            };
+            row.cells.push(TableCell {
+                blocks: child(&tc, "txBody")
+                    .map(|body| text_body(&body, rels, false))
+                    .unwrap_or_default(),
+                col_span: span("gridSpan"),
+                row_span: span("rowSpan"),
+            });
+        }
+        table.rows.push(row);
+    }
+    table
+}
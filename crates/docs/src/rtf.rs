@@ -0,0 +1,1204 @@
+//! RTF：按控制字与分组解析，输出与 docx 相同的文档模型
+
+use crate::{
+    document::{
+        Block, Document, Image, Inline, ListInfo, Note, Paragraph, Run, Table, TableCell, TableRow,
+    },
+    error::DocsError,
+    metadata::{split_keywords, DocumentMetadata},
+    ole::codepage_encoding,
+    ooxml::MAX_PART_SIZE,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use encoding_rs::{Encoding, WINDOWS_1252};
+use std::{collections::HashMap, fs};
+
+/// 分组的最大嵌套深度
+const MAX_DEPTH: usize = 1024;
+/// 控制字名与参数的最大长度
+const MAX_WORD_LEN: usize = 32;
+const TWIPS_PER_PX: f32 = 15.0;
+
+/// 不输出内容的目标，`\*` 开头的未知目标同样跳过
+const SKIPPED_DESTINATIONS: &[&str] = &[
+    "filetbl",
+    "listtable",
+    "listoverridetable",
+    "revtbl",
+    "rsidtbl",
+    "xe",
+    "tc",
+    "txe",
+    "objdata",
+    "themedata",
+    "colorschememapping",
+    "datastore",
+    "latentstyles",
+    "nonshppict",
+    "pgdsctbl",
+    "xmlnstbl",
+    "generator",
+    "annotation",
+    "atnid",
+    "atnauthor",
+    "bkmkstart",
+    "bkmkend",
+    "template",
+    "private",
+    "userprops",
+    "docvar",
+];
+
+/// 解析 rtf 文件
+pub fn parse(path: &str) -> Result<Document, DocsError> {
+    let size = fs::metadata(path)?.len();
+    if size > MAX_PART_SIZE {
+        return Err(DocsError::LimitExceeded {
+            limit: format!("文件大小 {} 字节", size),
+            part: None,
+        });
+    }
+    let data = fs::read(path)?;
+    let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(&data);
+    let start = data
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(0);
+    if !data[start..].starts_with(b"{\\rtf") {
+        return Err(DocsError::corrupt("不是 RTF 文档"));
+    }
+    Parser::default().parse(data)
+}
+
+/// 词法单元
+enum Token<'a> {
+    Open,
+    Close,
+    /// 控制字及其参数
+    Word(&'a str, Option<i32>),
+    /// 控制符号，如 `\~`、`\{`
+    Symbol(u8),
+    /// `\'hh`
+    Hex(u8),
+    /// `\binN` 之后的二进制数据
+    Binary(&'a [u8]),
+    Text(&'a [u8]),
+}
+
+struct Lexer<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let data = self.data;
+        loop {
+            let byte = *data.get(self.pos)?;
+            self.pos += 1;
+            return Some(match byte {
+                b'{' => Token::Open,
+                b'}' => Token::Close,
+                // 源码中的换行没有意义
+                b'\r' | b'\n' => continue,
+                b'\\' => self.control(),
+                _ => {
+                    let start = self.pos - 1;
+                    while data
+                        .get(self.pos)
+                        .is_some_and(|b| !matches!(b, b'{' | b'}' | b'\\' | b'\r' | b'\n'))
+                    {
+                        self.pos += 1;
+                    }
+                    Token::Text(&data[start..self.pos])
+                },
+            });
+        }
+    }
+}
+
+impl<'a> Lexer<'a> {
+    fn control(&mut self) -> Token<'a> {
+        let data = self.data;
+        let Some(&byte) = data.get(self.pos) else {
+            return Token::Symbol(b'\\');
+        };
+        self.pos += 1;
+        if byte == b'\'' {
+            let hex = data.get(self.pos..self.pos + 2).unwrap_or_default();
+            self.pos += hex.len();
+            let value = std::str::from_utf8(hex)
+                .ok()
+                .and_then(|h| u8::from_str_radix(h, 16).ok());
+            return value.map_or(Token::Text(&[]), Token::Hex);
+        }
+        // `\` 加换行等同于 `\par`
+        if matches!(byte, b'\r' | b'\n') {
+            return Token::Word("par", None);
+        }
+        if !byte.is_ascii_alphabetic() {
+            return Token::Symbol(byte);
+        }
+
+        let start = self.pos - 1;
+        while data.get(self.pos).is_some_and(u8::is_ascii_alphabetic)
+            && self.pos - start < MAX_WORD_LEN
+        {
+            self.pos += 1;
+        }
+        let name = std::str::from_utf8(&data[start..self.pos]).unwrap_or_default();
+        let param_start = self.pos;
+        if data.get(self.pos) == Some(&b'-') {
+            self.pos += 1;
+        }
+        while data.get(self.pos).is_some_and(u8::is_ascii_digit)
+            && self.pos - param_start < MAX_WORD_LEN
+        {
+            self.pos += 1;
+        }
+        let param = std::str::from_utf8(&data[param_start..self.pos])
+            .ok()
+            .and_then(|p| p.parse::<i64>().ok())
+            .map(|p| p.clamp(i32::MIN as i64, i32::MAX as i64) as i32);
+        // 控制字后的一个空格是分隔符
+        if data.get(self.pos) == Some(&b' ') {
+            self.pos += 1;
+        }
+        if name == "bin" {
+            let len = param.unwrap_or(0).max(0) as usize;
+            let end = self.pos.saturating_add(len).min(data.len());
+            let binary = &data[self.pos..end];
+            self.pos = end;
+            return Token::Binary(binary);
+        }
+        Token::Word(name, param)
+    }
+}
+
+/// 分组的输出目标
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Destination {
+    Text,
+    Skip,
+    FontTable,
+    ColorTable,
+    StyleSheet,
+    Info,
+    InfoField(InfoField),
+    Picture,
+    FieldInstruction,
+    /// 列表编号或项目符号的文本
+    ListText,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InfoField {
+    Title,
+    Subject,
+    Author,
+    Keywords,
+    Comment,
+    Operator,
+    Created,
+    Modified,
+}
+
+/// 文字格式
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Format {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    strike: bool,
+    superscript: bool,
+    subscript: bool,
+    /// 字号（pt）
+    size: Option<f32>,
+    /// 颜色表索引
+    color: Option<usize>,
+    /// 字体表索引
+    font: Option<i32>,
+    href: Option<String>,
+}
+
+/// 段落格式
+#[derive(Debug, Clone, Default)]
+struct ParagraphFormat {
+    align: Option<&'static str>,
+    style: Option<i32>,
+    /// 大纲级别，0 对应标题 1
+    outline: Option<u8>,
+    in_table: bool,
+    /// `\lsN` 列表
+    list: bool,
+    level: u8,
+}
+
+/// 随分组嵌套保存与恢复的状态
+#[derive(Debug, Clone)]
+struct State {
+    destination: Destination,
+    format: Format,
+    paragraph: ParagraphFormat,
+    /// `\u` 之后跳过的替代字符数
+    uc: usize,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            destination: Destination::Text,
+            format: Format::default(),
+            paragraph: ParagraphFormat::default(),
+            uc: 1,
+        }
+    }
+}
+
+struct Font {
+    name: String,
+    /// 由 `\fcharset` 决定，未指定时使用文档的代码页
+    encoding: Option<&'static Encoding>,
+}
+
+/// 表格定义中的合并标记
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Merge {
+    #[default]
+    None,
+    First,
+    Continue,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct CellDef {
+    horizontal: Merge,
+    vertical: Merge,
+}
+
+/// 图片数据
+#[derive(Default)]
+struct Picture {
+    mime: Option<&'static str>,
+    data: Vec<u8>,
+    /// 未配对的十六进制高位
+    nibble: Option<u8>,
+    /// 目标尺寸（缇）与缩放百分比
+    goal: (Option<i32>, Option<i32>),
+    scale: (Option<i32>, Option<i32>),
+}
+
+/// 页眉、页脚与脚注等子文档
+enum SubKind {
+    Header,
+    Footer,
+    Footnote(String),
+}
+
+struct SubDocument {
+    /// 开始子文档的分组深度，该分组结束时子文档结束
+    depth: usize,
+    kind: SubKind,
+    builder: Builder,
+}
+
+struct Parser {
+    stack: Vec<State>,
+    /// 上一个记号是 `\*`
+    ignorable: bool,
+    /// `\u` 之后尚待跳过的字符数
+    skip: usize,
+    /// 尚未解码的 `\'hh` 字节，多字节编码需要连在一起解码
+    pending: Vec<u8>,
+    high_surrogate: Option<u16>,
+    encoding: &'static Encoding,
+    default_font: Option<i32>,
+    fonts: HashMap<i32, Font>,
+    colors: Vec<Option<String>>,
+    /// 段落样式 -> 标题级别
+    headings: HashMap<i32, u8>,
+    /// 段落样式 -> 样式定义中的文字格式
+    styles: HashMap<i32, Format>,
+
+    /// 正在解析的字体表、颜色表与样式表条目
+    font_entry: (i32, Option<&'static Encoding>, String),
+    color_entry: Option<(u8, u8, u8)>,
+    style_entry: (i32, Option<u8>, String, Format),
+
+    field_instruction: String,
+    info_text: String,
+    /// 年、月、日、时、分
+    time: [i32; 5],
+    metadata: Option<DocumentMetadata>,
+    picture: Option<Picture>,
+    row_defs: Vec<CellDef>,
+    cell_def: CellDef,
+    /// 当前段落的列表编号文本，`Some(true)` 表示有序
+    list_marker: Option<bool>,
+
+    builder: Builder,
+    subs: Vec<SubDocument>,
+    headers: Vec<Vec<Block>>,
+    footers: Vec<Vec<Block>>,
+    footnotes: Vec<Note>,
+    /// 已出现的脚注数，用作脚注编号
+    notes: usize,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Parser {
+            stack: vec![State::default()],
+            ignorable: false,
+            skip: 0,
+            pending: Vec::new(),
+            high_surrogate: None,
+            encoding: WINDOWS_1252,
+            default_font: None,
+            fonts: HashMap::new(),
+            colors: Vec::new(),
+            headings: HashMap::new(),
+            styles: HashMap::new(),
+            font_entry: (0, None, String::new()),
+            color_entry: None,
+            style_entry: (0, None, String::new(), Format::default()),
+            field_instruction: String::new(),
+            info_text: String::new(),
+            time: [0; 5],
+            metadata: None,
+            picture: None,
+            row_defs: Vec::new(),
+            cell_def: CellDef::default(),
+            list_marker: None,
+            builder: Builder::default(),
+            subs: Vec::new(),
+            headers: Vec::new(),
+            footers: Vec::new(),
+            footnotes: Vec::new(),
+            notes: 0,
+        }
+    }
+}
+
+impl Parser {
+    fn parse(mut self, data: &[u8]) -> Result<Document, DocsError> {
+        for token in (Lexer { data, pos: 0 }) {
+            if matches!(token, Token::Open | Token::Close) {
+                self.skip = 0;
+            } else if self.skip > 0 {
+                match token {
+                    Token::Text(text) if text.len() > self.skip => {
+                        let rest = &text[self.skip..];
+                        self.skip = 0;
+                        self.text(rest);
+                    },
+                    Token::Text(text) => self.skip -= text.len(),
+                    _ => self.skip -= 1,
+                }
+                continue;
+            }
+
+            match token {
+                Token::Open => {
+                    self.flush();
+                    if self.stack.len() >= MAX_DEPTH {
+                        return Err(DocsError::corrupt("分组嵌套过深"));
+                    }
+                    let state = self.state().clone();
+                    self.stack.push(state);
+                },
+                Token::Close => {
+                    self.flush();
+                    self.ignorable = false;
+                    // 多余的右括号忽略
+                    if self.stack.len() > 1 {
+                        let ended = self.stack.pop().map(|s| s.destination);
+                        if let Some(ended) = ended.filter(|&d| d != self.state().destination) {
+                            self.end_destination(ended);
+                        }
+                        if self.subs.last().is_some_and(|s| self.stack.len() < s.depth) {
+                            self.end_sub();
+                        }
+                    }
+                },
+                Token::Word(name, param) => {
+                    self.flush();
+                    let ignorable = std::mem::take(&mut self.ignorable);
+                    self.word(name, param, ignorable);
+                },
+                Token::Symbol(b'*') => self.ignorable = true,
+                Token::Symbol(symbol) => {
+                    self.flush();
+                    match symbol {
+                        b'\\' | b'{' | b'}' => self.push_str(&(symbol as char).to_string()),
+                        b'~' => self.push_str("\u{a0}"),
+                        b'_' => self.push_str("-"),
+                        _ => {},
+                    }
+                },
+                Token::Hex(byte) => match self.state().destination {
+                    Destination::Picture => self.picture_data(&[byte]),
+                    _ => self.pending.push(byte),
+                },
+                Token::Binary(binary) => {
+                    if let Some(picture) = self.picture.as_mut() {
+                        picture.data.extend_from_slice(binary);
+                    }
+                },
+                Token::Text(text) => self.text(text),
+            }
+        }
+        self.flush();
+        while !self.subs.is_empty() {
+            self.end_sub();
+        }
+        self.end_paragraph_if_pending();
+
+        Ok(Document {
+            blocks: std::mem::take(&mut self.builder).finish(),
+            headers: self.headers,
+            footers: self.footers,
+            footnotes: self.footnotes,
+            metadata: self.metadata,
+        })
+    }
+
+    fn state(&self) -> &State {
+        self.stack.last().expect("状态栈不为空")
+    }
+
+    fn state_mut(&mut self) -> &mut State {
+        self.stack.last_mut().expect("状态栈不为空")
+    }
+
+    fn builder(&mut self) -> &mut Builder {
+        match self.subs.last_mut() {
+            Some(sub) => &mut sub.builder,
+            None => &mut self.builder,
+        }
+    }
+
+    /// 当前字体的编码
+    fn current_encoding(&self) -> &'static Encoding {
+        if self.state().destination == Destination::FontTable {
+            return self.font_entry.1.unwrap_or(self.encoding);
+        }
+        self.state()
+            .format
+            .font
+            .or(self.default_font)
+            .and_then(|f| self.fonts.get(&f))
+            .and_then(|f| f.encoding)
+            .unwrap_or(self.encoding)
+    }
+
+    /// 解码积累的 `\'hh` 字节
+    fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let bytes = std::mem::take(&mut self.pending);
+        let (text, _) = self.current_encoding().decode_without_bom_handling(&bytes);
+        self.push_str(&text);
+    }
+
+    fn text(&mut self, text: &[u8]) {
+        if self.state().destination == Destination::Picture {
+            return self.picture_data(text);
+        }
+        self.pending.extend_from_slice(text);
+    }
+
+    /// 按当前目标输出已解码的文本
+    fn push_str(&mut self, text: &str) {
+        match self.state().destination {
+            Destination::Text => self.push_run(text),
+            Destination::FontTable => {
+                for (i, part) in text.split(';').enumerate() {
+                    if i > 0 {
+                        let (id, encoding, name) = std::mem::take(&mut self.font_entry);
+                        let name = name.trim().to_string();
+                        self.fonts.insert(id, Font { name, encoding });
+                    }
+                    self.font_entry.2.push_str(part);
+                }
+            },
+            Destination::ColorTable => {
+                for _ in text.matches(';') {
+                    let color = self
+                        .color_entry
+                        .take()
+                        .map(|(r, g, b)| format!("#{:02X}{:02X}{:02X}", r, g, b));
+                    self.colors.push(color);
+                }
+            },
+            Destination::StyleSheet => {
+                for (i, part) in text.split(';').enumerate() {
+                    if i > 0 {
+                        let (id, outline, name, format) = std::mem::take(&mut self.style_entry);
+                        if let Some(level) = outline.or_else(|| heading_level(&name)) {
+                            self.headings.insert(id, level);
+                        }
+                        self.styles.insert(id, format);
+                    }
+                    self.style_entry.2.push_str(part);
+                }
+            },
+            Destination::InfoField(_) => self.info_text.push_str(text),
+            Destination::FieldInstruction => self.field_instruction.push_str(text),
+            Destination::ListText => {
+                let marker = text.trim();
+                if !marker.is_empty() {
+                    let ordered = marker.starts_with(|c: char| c.is_alphanumeric());
+                    self.list_marker = Some(ordered);
+                }
+            },
+            Destination::Skip | Destination::Info | Destination::Picture => {},
+        }
+    }
+
+    fn push_run(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let format = self.state().format.clone();
+        let builder = self.builder();
+        if builder.format.as_ref() == Some(&format) {
+            if let Some(Inline::Text(run)) = builder.inlines.last_mut() {
+                run.text.push_str(text);
+                return;
+            }
+        }
+        let run = Run {
+            text: text.to_string(),
+            bold: format.bold,
+            italic: format.italic,
+            underline: format.underline,
+            strike: format.strike,
+            superscript: format.superscript,
+            subscript: format.subscript,
+            color: format
+                .color
+                .and_then(|i| self.colors.get(i).cloned().flatten()),
+            size: format.size,
+            font: format
+                .font
+                .and_then(|f| self.fonts.get(&f))
+                .map(|f| f.name.clone())
+                .filter(|name| !name.is_empty()),
+            href: format.href.clone(),
+        };
+        let builder = self.builder();
+        builder.inlines.push(Inline::Text(run));
+        builder.format = Some(format);
+    }
+
+    fn push_inline(&mut self, inline: Inline) {
+        let builder = self.builder();
+        builder.inlines.push(inline);
+        builder.format = None;
+    }
+
+    fn word(&mut self, name: &str, param: Option<i32>, ignorable: bool) {
+        let destination = self.state().destination;
+        if destination == Destination::Skip {
+            return;
+        }
+        if self.destination(name, ignorable) {
+            return;
+        }
+        // `\*` 开头的未知目标整体跳过
+        if ignorable {
+            self.state_mut().destination = Destination::Skip;
+            return;
+        }
+        match destination {
+            Destination::Text => self.text_word(name, param),
+            Destination::FontTable => match name {
+                "f" => self.font_entry = (param.unwrap_or(0), None, String::new()),
+                "fcharset" => self.font_entry.1 = param.and_then(charset_encoding),
+                _ => {},
+            },
+            Destination::ColorTable => {
+                let value = param.unwrap_or(0).clamp(0, 255) as u8;
+                let entry = self.color_entry.get_or_insert((0, 0, 0));
+                match name {
+                    "red" => entry.0 = value,
+                    "green" => entry.1 = value,
+                    "blue" => entry.2 = value,
+                    _ => {},
+                }
+            },
+            Destination::StyleSheet => match name {
+                "s" => self.style_entry.0 = param.unwrap_or(0),
+                "outlinelevel" => {
+                    self.style_entry.1 = param
+                        .filter(|p| (0..9).contains(p))
+                        .map(|p| (p as u8 + 1).min(6))
+                },
+                _ => {
+                    format_word(&mut self.style_entry.3, name, param);
+                },
+            },
+            Destination::Info => {
+                let count = param.filter(|&p| p > 0).map(|p| p as u32);
+                let metadata = self.metadata.get_or_insert_with(Default::default);
+                match name {
+                    "nofpages" => metadata.page_count = count,
+                    "nofwords" => metadata.word_count = count,
+                    "version" => metadata.revision = param.map(|p| p.to_string()),
+                    _ => {},
+                }
+            },
+            Destination::InfoField(InfoField::Created | InfoField::Modified) => {
+                let index = match name {
+                    "yr" => 0,
+                    "mo" => 1,
+                    "dy" => 2,
+                    "hr" => 3,
+                    "min" => 4,
+                    _ => return,
+                };
+                self.time[index] = param.unwrap_or(0);
+            },
+            Destination::Picture => {
+                let picture = self.picture.get_or_insert_with(Default::default);
+                match name {
+                    "pngblip" => picture.mime = Some("image/png"),
+                    "jpegblip" => picture.mime = Some("image/jpeg"),
+                    "picwgoal" => picture.goal.0 = param,
+                    "pichgoal" => picture.goal.1 = param,
+                    "picscalex" => picture.scale.0 = param,
+                    "picscaley" => picture.scale.1 = param,
+                    _ => {},
+                }
+            },
+            _ => {},
+        }
+    }
+
+    /// 开始新目标的控制字，返回是否已处理
+    fn destination(&mut self, name: &str, ignorable: bool) -> bool {
+        let destination = match name {
+            "fonttbl" => Destination::FontTable,
+            "colortbl" => Destination::ColorTable,
+            "stylesheet" => Destination::StyleSheet,
+            "info" => {
+                self.metadata.get_or_insert_with(Default::default);
+                Destination::Info
+            },
+            "title" => Destination::InfoField(InfoField::Title),
+            "subject" => Destination::InfoField(InfoField::Subject),
+            "author" => Destination::InfoField(InfoField::Author),
+            "keywords" => Destination::InfoField(InfoField::Keywords),
+            "doccomm" => Destination::InfoField(InfoField::Comment),
+            "operator" => Destination::InfoField(InfoField::Operator),
+            "creatim" => Destination::InfoField(InfoField::Created),
+            "revtim" => Destination::InfoField(InfoField::Modified),
+            "pict" => {
+                self.picture = Some(Picture::default());
+                Destination::Picture
+            },
+            "fldinst" => {
+                self.field_instruction.clear();
+                Destination::FieldInstruction
+            },
+            "listtext" | "pntext" => Destination::ListText,
+            // 以下目标的内容按正文处理
+            "shppict" | "fldrslt" | "field" => return true,
+            "header" | "headerl" | "headerr" | "headerf" => {
+                self.start_sub(SubKind::Header);
+                return true;
+            },
+            "footer" | "footerl" | "footerr" | "footerf" => {
+                self.start_sub(SubKind::Footer);
+                return true;
+            },
+            "footnote" => {
+                self.notes += 1;
+                let id = self.notes.to_string();
+                self.push_inline(Inline::Note { id: id.clone() });
+                self.start_sub(SubKind::Footnote(id));
+                return true;
+            },
+            _ if SKIPPED_DESTINATIONS.contains(&name) => Destination::Skip,
+            _ => return false,
+        };
+        // info 中的字段只在 info 内有效，其余目标只在正文中有效
+        let valid = match (self.state().destination, destination) {
+            (Destination::Info, Destination::InfoField(_)) => true,
+            (_, Destination::InfoField(_)) => false,
+            _ => true,
+        };
+        if valid {
+            if let Destination::InfoField(_) = destination {
+                self.info_text.clear();
+                self.time = [0; 5];
+            }
+            self.state_mut().destination = destination;
+        }
+        valid || !ignorable
+    }
+
+    /// 正文中的控制字
+    fn text_word(&mut self, name: &str, param: Option<i32>) {
+        // 选用段落样式时套用样式定义中的文字格式，之后的控制字再逐项覆盖
+        if name == "s" {
+            if let Some(style) = param.and_then(|s| self.styles.get(&s)).cloned() {
+                let format = &mut self.state_mut().format;
+                let href = format.href.take();
+                *format = Format { href, ..style };
+            }
+        }
+        if format_word(&mut self.state_mut().format, name, param)
+            || paragraph_word(&mut self.state_mut().paragraph, name, param)
+        {
+            return;
+        }
+        match name {
+            "ansicpg" => {
+                if let Some(codepage) = param.and_then(|p| u16::try_from(p).ok()) {
+                    self.encoding = codepage_encoding(codepage);
+                }
+            },
+            "deff" => self.default_font = param,
+            "uc" => self.state_mut().uc = param.unwrap_or(1).max(0) as usize,
+            "u" => {
+                let unit = param.unwrap_or(0) as i16 as u16;
+                self.skip = self.state().uc;
+                self.unicode(unit);
+            },
+
+            // 表格
+            "trowd" => {
+                self.row_defs.clear();
+                self.cell_def = CellDef::default();
+            },
+            "clmgf" => self.cell_def.horizontal = Merge::First,
+            "clmrg" => self.cell_def.horizontal = Merge::Continue,
+            "clvmgf" => self.cell_def.vertical = Merge::First,
+            "clvmrg" => self.cell_def.vertical = Merge::Continue,
+            "cellx" => self.row_defs.push(std::mem::take(&mut self.cell_def)),
+            "cell" => {
+                let paragraph = self.paragraph();
+                self.builder().end_cell(paragraph);
+            },
+            "row" => {
+                let defs = self.row_defs.clone();
+                self.builder().end_row(&defs);
+            },
+            // 嵌套表格按单元格内的段落处理
+            "nestcell" => self.push_str("\t"),
+            "nestrow" => self.end_paragraph(),
+
+            // 特殊字符
+            "par" | "sect" => self.end_paragraph(),
+            "line" => self.push_inline(Inline::Break),
+            "tab" => self.push_str("\t"),
+            "emdash" => self.push_str("\u{2014}"),
+            "endash" => self.push_str("\u{2013}"),
+            "emspace" | "enspace" | "qmspace" => self.push_str(" "),
+            "bullet" => self.push_str("\u{2022}"),
+            "lquote" => self.push_str("\u{2018}"),
+            "rquote" => self.push_str("\u{2019}"),
+            "ldblquote" => self.push_str("\u{201C}"),
+            "rdblquote" => self.push_str("\u{201D}"),
+            _ => {},
+        }
+    }
+
+    /// `\uN`，代理对分两个控制字给出
+    fn unicode(&mut self, unit: u16) {
+        let text = match unit {
+            0xD800..=0xDBFF => {
+                self.high_surrogate = Some(unit);
+                return;
+            },
+            0xDC00..=0xDFFF => match self.high_surrogate.take() {
+                Some(high) => String::from_utf16_lossy(&[high, unit]),
+                None => char::REPLACEMENT_CHARACTER.to_string(),
+            },
+            _ => char::from_u32(unit as u32)
+                .unwrap_or(char::REPLACEMENT_CHARACTER)
+                .to_string(),
+        };
+        self.push_str(&text);
+    }
+
+    fn picture_data(&mut self, text: &[u8]) {
+        let Some(picture) = self.picture.as_mut() else {
+            return;
+        };
+        for &byte in text {
+            let Some(value) = (byte as char).to_digit(16) else {
+                continue;
+            };
+            match picture.nibble.take() {
+                Some(high) => picture.data.push(high << 4 | value as u8),
+                None => picture.nibble = Some(value as u8),
+            }
+        }
+    }
+
+    /// 目标所在的分组结束
+    fn end_destination(&mut self, destination: Destination) {
+        match destination {
+            Destination::InfoField(field) => {
+                let text = std::mem::take(&mut self.info_text).trim().to_string();
+                let time = self.time;
+                let metadata = self.metadata.get_or_insert_with(Default::default);
+                let text = (!text.is_empty()).then_some(text);
+                match field {
+                    InfoField::Title => metadata.title = text,
+                    InfoField::Subject => metadata.subject = text,
+                    InfoField::Author => metadata.author = text,
+                    InfoField::Keywords => {
+                        metadata.keywords = text.map(|k| split_keywords(&k)).unwrap_or_default()
+                    },
+                    InfoField::Comment => metadata.description = text,
+                    InfoField::Operator => metadata.last_modified_by = text,
+                    InfoField::Created => metadata.created = format_time(time),
+                    InfoField::Modified => metadata.modified = format_time(time),
+                }
+            },
+            Destination::Picture => {
+                let Some(picture) = self.picture.take() else {
+                    return;
+                };
+                // 只保留浏览器能显示的 PNG/JPEG，WMF/EMF 等忽略
+                let Some(mime) = picture.mime.filter(|_| !picture.data.is_empty()) else {
+                    return;
+                };
+                let size = |goal: Option<i32>, scale: Option<i32>| {
+                    let goal = goal.filter(|&g| g > 0)? as f32;
+                    let scale = scale.filter(|&s| s > 0).unwrap_or(100) as f32;
+                    Some(goal * scale / 100.0 / TWIPS_PER_PX)
+                };
+                self.push_inline(Inline::Image(Image {
+                    src: format!("data:{};base64,{}", mime, STANDARD.encode(&picture.data)),
+                    alt: None,
+                    width: size(picture.goal.0, picture.scale.0),
+                    height: size(picture.goal.1, picture.scale.1),
+                }));
+            },
+            Destination::FieldInstruction => {
+                let href = hyperlink(&std::mem::take(&mut self.field_instruction));
+                // 域结果与域指令同属 `\field` 分组
+                self.state_mut().format.href = href;
+            },
+            _ => {},
+        }
+    }
+
+    fn start_sub(&mut self, kind: SubKind) {
+        let depth = self.stack.len();
+        self.subs
+            .push(SubDocument { depth, kind, builder: Builder::default() });
+    }
+
+    fn end_sub(&mut self) {
+        self.end_paragraph_if_pending();
+        let Some(sub) = self.subs.pop() else {
+            return;
+        };
+        let blocks = sub.builder.finish();
+        match sub.kind {
+            SubKind::Header => self.headers.push(blocks),
+            SubKind::Footer => self.footers.push(blocks),
+            SubKind::Footnote(id) => self.footnotes.push(Note { id, blocks }),
+        }
+    }
+
+    /// 当前段落格式对应的段落（不含内容）
+    fn paragraph(&mut self) -> (Paragraph, bool) {
+        let marker = self.list_marker.take();
+        let format = &self.state().paragraph;
+        let heading = format
+            .outline
+            .filter(|&o| o < 9)
+            .map(|o| (o + 1).min(6))
+            .or_else(|| format.style.and_then(|s| self.headings.get(&s).copied()));
+        let list = match marker {
+            Some(ordered) => Some(ListInfo { level: format.level, ordered }),
+            None => format
+                .list
+                .then_some(ListInfo { level: format.level, ordered: false }),
+        };
+        let paragraph = Paragraph {
+            heading,
+            list,
+            align: format.align.map(str::to_string),
+            ..Default::default()
+        };
+        (paragraph, format.in_table)
+    }
+
+    fn end_paragraph(&mut self) {
+        let paragraph = self.paragraph();
+        self.builder().end_paragraph(paragraph);
+    }
+
+    fn end_paragraph_if_pending(&mut self) {
+        if !self.builder().inlines.is_empty() {
+            self.end_paragraph();
+        }
+    }
+}
+
+/// 按 `\par`、`\cell`、`\row` 组装段落与表格
+#[derive(Default)]
+struct Builder {
+    blocks: Vec<Block>,
+    inlines: Vec<Inline>,
+    /// 最后一个文本片段的格式，相同格式的文字合并为一段
+    format: Option<Format>,
+    rows: Vec<TableRow>,
+    cells: Vec<TableCell>,
+    cell_blocks: Vec<Block>,
+    /// 网格列 -> 正在纵向合并的起始单元格 (行, 单元格)
+    merges: HashMap<u32, (usize, usize)>,
+}
+
+impl Builder {
+    fn take_paragraph(&mut self, mut paragraph: Paragraph) -> Block {
+        paragraph.inlines = std::mem::take(&mut self.inlines);
+        self.format = None;
+        if paragraph.inlines.is_empty() {
+            paragraph.list = None;
+        }
+        Block::Paragraph(paragraph)
+    }
+
+    fn end_paragraph(&mut self, (paragraph, in_table): (Paragraph, bool)) {
+        let block = self.take_paragraph(paragraph);
+        if in_table {
+            self.cell_blocks.push(block);
+        } else {
+            self.end_table();
+            self.blocks.push(block);
+        }
+    }
+
+    fn end_cell(&mut self, (paragraph, _): (Paragraph, bool)) {
+        if !self.inlines.is_empty() || self.cell_blocks.is_empty() {
+            let block = self.take_paragraph(paragraph);
+            self.cell_blocks.push(block);
+        }
+        let blocks = std::mem::take(&mut self.cell_blocks);
+        self.cells.push(TableCell { blocks, ..Default::default() });
+    }
+
+    fn end_row(&mut self, defs: &[CellDef]) {
+        let row_index = self.rows.len();
+        let mut row = TableRow::default();
+        let mut column = 0;
+        for (i, cell) in std::mem::take(&mut self.cells).into_iter().enumerate() {
+            let def = defs.get(i).copied().unwrap_or_default();
+            if def.horizontal == Merge::Continue {
+                if let Some(previous) = row.cells.last_mut() {
+                    previous.col_span += 1;
+                    column += 1;
+                    continue;
+                }
+            }
+            match def.vertical {
+                Merge::Continue => {
+                    if let Some(&(r, c)) = self.merges.get(&column) {
+                        self.rows[r].cells[c].row_span += 1;
+                        column += 1;
+                        continue;
+                    }
+                },
+                Merge::First => {
+                    self.merges.insert(column, (row_index, row.cells.len()));
+                },
+                Merge::None => {
+                    self.merges.remove(&column);
+                },
+            }
+            row.cells.push(cell);
+            column += 1;
+        }
+        self.rows.push(row);
+    }
+
+    fn end_table(&mut self) {
+        if !self.cells.is_empty() || !self.cell_blocks.is_empty() {
+            if !self.cell_blocks.is_empty() {
+                let blocks = std::mem::take(&mut self.cell_blocks);
+                self.cells.push(TableCell { blocks, ..Default::default() });
+            }
+            self.end_row(&[]);
+        }
+        if !self.rows.is_empty() {
+            let rows = std::mem::take(&mut self.rows);
+            self.blocks.push(Block::Table(Table { rows }));
+        }
+        self.merges.clear();
+    }
+
+    fn finish(mut self) -> Vec<Block> {
+        if !self.inlines.is_empty() {
+            let block = self.take_paragraph(Paragraph::default());
+            self.end_table();
+            self.blocks.push(block);
+        }
+        self.end_table();
+        self.blocks
+    }
+}
+
+/// 文字格式控制字，返回是否已处理
+fn format_word(format: &mut Format, name: &str, param: Option<i32>) -> bool {
+    // 开关控制字不带参数或参数非 0 时打开
+    let on = param != Some(0);
+    match name {
+        "plain" => {
+            let href = format.href.take();
+            *format = Format { href, ..Default::default() };
+        },
+        "b" => format.bold = on,
+        "i" => format.italic = on,
+        "ul" | "uld" | "uldb" | "uldash" | "ulw" | "ulwave" | "ulth" => format.underline = on,
+        "ulnone" => format.underline = false,
+        "strike" | "striked" => format.strike = on,
+        "super" => (format.superscript, format.subscript) = (true, false),
+        "sub" => (format.superscript, format.subscript) = (false, true),
+        "nosupersub" => (format.superscript, format.subscript) = (false, false),
+        "fs" => format.size = param.filter(|&p| p > 0).map(|p| p as f32 / 2.0),
+        "cf" => format.color = param.and_then(|p| usize::try_from(p).ok()),
+        "f" => format.font = param,
+        _ => return false,
+    }
+    true
+}
+
+/// 段落格式控制字，返回是否已处理
+fn paragraph_word(paragraph: &mut ParagraphFormat, name: &str, param: Option<i32>) -> bool {
+    match name {
+        "pard" => *paragraph = ParagraphFormat::default(),
+        "ql" => paragraph.align = Some("left"),
+        "qc" => paragraph.align = Some("center"),
+        "qr" => paragraph.align = Some("right"),
+        "qj" | "qd" => paragraph.align = Some("justify"),
+        "s" => paragraph.style = param,
+        "outlinelevel" => paragraph.outline = param.and_then(|p| u8::try_from(p).ok()),
+        "intbl" => paragraph.in_table = true,
+        "ls" => paragraph.list = true,
+        "ilvl" => paragraph.level = param.unwrap_or(0).clamp(0, 8) as u8,
+        _ => return false,
+    }
+    true
+}
+
+/// `\fcharset` 对应的编码，ANSI 与默认字符集使用文档的代码页
+fn charset_encoding(charset: i32) -> Option<&'static Encoding> {
+    let codepage = match charset {
+        2 => 1252,
+        77 => 10000,
+        128 => 932,
+        129 => 949,
+        134 => 936,
+        136 => 950,
+        161 => 1253,
+        162 => 1254,
+        163 => 1258,
+        177 => 1255,
+        178 => 1256,
+        186 => 1257,
+        204 => 1251,
+        222 => 874,
+        238 => 1250,
+        _ => return None,
+    };
+    Some(match codepage {
+        10000 => encoding_rs::MACINTOSH,
+        874 => encoding_rs::WINDOWS_874,
+        _ => codepage_encoding(codepage),
+    })
+}
+
+/// 样式名为“heading N”或“标题 N”时的标题级别
+fn heading_level(name: &str) -> Option<u8> {
+    let name = name.trim().to_lowercase();
+    let level = name
+        .strip_prefix("heading")
+        .or_else(|| name.strip_prefix("标题"))?
+        .trim()
+        .parse::<u8>()
+        .ok()?;
+    (1..=9).contains(&level).then_some(level.min(6))
+}
+
+/// `HYPERLINK "url"` 域中的地址，书签链接（`\l`）忽略
+fn hyperlink(instruction: &str) -> Option<String> {
+    let rest = instruction.trim().strip_prefix("HYPERLINK")?.trim_start();
+    if rest.starts_with("\\l") {
+        return None;
+    }
+    let url = match rest.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next()?,
+        None => rest.split_whitespace().next()?,
+    };
+    (!url.is_empty()).then(|| url.to_string())
+}
+
+fn format_time([year, month, day, hour, minute]: [i32; 5]) -> Option<String> {
+    if year <= 0 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some(format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:00",
+        year, month, day, hour, minute
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 各段落的文字，以 `|` 结尾
+    fn text(rtf: &str) -> String {
+        let document = Parser::default().parse(rtf.as_bytes()).unwrap();
+        let mut out = String::new();
+        for block in &document.blocks {
+            if let Block::Paragraph(paragraph) = block {
+                for inline in &paragraph.inlines {
+                    if let Inline::Text(run) = inline {
+                        out.push_str(&run.text);
+                    }
+                }
+                out.push('|');
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn hex_escapes_follow_code_page() {
+        assert_eq!(text(r"{\rtf1\ansi\ansicpg1252 caf\'e9}"), "café|");
+        assert_eq!(text(r"{\rtf1\ansi\ansicpg936 \'d6\'d0\'ce\'c4}"), "中文|");
+        // 尾字节 0x5c 不是控制符
+        assert_eq!(text(r"{\rtf1\ansi\ansicpg932 \'83\'5c}"), "ソ|");
+    }
+
+    #[test]
+    fn font_charset_overrides_code_page() {
+        let rtf = r"{\rtf1\ansi\ansicpg1252{\fonttbl{\f0\fcharset128 MS Mincho;}{\f1\fcharset0 Arial;}}\f0 \'82\'a0\f1 \'e9}";
+        assert_eq!(text(rtf), "あé|");
+    }
+
+    #[test]
+    fn unicode_escapes_skip_fallback() {
+        assert_eq!(text(r"{\rtf1 \u20013?\u25991?}"), "中文|");
+        assert_eq!(text(r"{\rtf1\uc2 \u20013\'3f\'3fx}"), "中x|");
+        assert_eq!(text(r"{\rtf1\uc0 \u20013 x}"), "中x|");
+        // 负数参数与代理对
+        assert_eq!(text(r"{\rtf1 \u-28647?}"), "這|");
+        assert_eq!(text(r"{\rtf1 \u-10179?\u-8704?}"), "😀|");
+        // \uc 只在所在分组内有效
+        assert_eq!(text(r"{\rtf1 {\uc2 \u20013}ab}"), "中ab|");
+    }
+}
@@ -0,0 +1,322 @@
+//! 全文搜索：表格按单元格、文档按段落、演示文稿按幻灯片、PDF 按页匹配，命中结果逐个回调
+
+use crate::{
+    document::{plain_text, Block, Paragraph},
+    error::DocsError,
+    format::DocsFormat,
+    pdf::Pdf,
+    slides::Presentation,
+    workbook::LazyWorkbook,
+    Docs,
+};
+use serde::{Deserialize, Serialize};
+use std::ops::ControlFlow;
+
+/// 表格每次读取的行数
+const WINDOW_ROWS: u32 = 1000;
+/// 命中片段前后保留的字符数
+const CONTEXT_CHARS: usize = 30;
+
+/// 搜索选项
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SearchOptions {
+    /// 区分大小写
+    pub case_sensitive: bool,
+    /// 全字匹配，命中两侧不能是字母、数字或下划线
+    pub whole_word: bool,
+    /// 最多返回的命中数
+    pub limit: Option<usize>,
+}
+
+/// 命中位置
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SearchLocation {
+    /// 单元格，行列相对于已使用区域的左上角，与 `SheetWindow` 的行号一致
+    Cell { sheet: String, row: u32, col: u32 },
+    /// 正文段落的序号（从 0 开始，表格中的段落按出现顺序计入）
+    Paragraph { index: usize },
+    /// 幻灯片序号（从 1 开始），`notes` 表示命中在演讲者备注中
+    Slide { slide: usize, notes: bool },
+    /// PDF 页码（从 1 开始）
+    Page { page: u32 },
+}
+
+/// 一次命中：位置与上下文片段
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub location: SearchLocation,
+    pub before: String,
+    pub matched: String,
+    pub after: String,
+}
+
+/// 按选项在文本中查找关键字
+struct Matcher {
+    query: Vec<char>,
+    options: SearchOptions,
+}
+
+impl Matcher {
+    fn new(query: &str, options: &SearchOptions) -> Self {
+        let query = query
+            .chars()
+            .map(|c| fold(c, options.case_sensitive))
+            .collect();
+        Matcher { query, options: options.clone() }
+    }
+
+    /// 所有不重叠的命中，按字符下标返回
+    fn find(&self, text: &str) -> Vec<(usize, usize)> {
+        let case_sensitive = self.options.case_sensitive;
+        let chars: Vec<char> = text.chars().map(|c| fold(c, case_sensitive)).collect();
+        let len = self.query.len();
+        let is_word = |c: &char| c.is_alphanumeric() || *c == '_';
+        let mut hits = Vec::new();
+        let mut start = 0;
+        while len > 0 && start + len <= chars.len() {
+            let end = start + len;
+            let found = chars[start..end] == self.query[..]
+                && (!self.options.whole_word
+                    || (!chars[..start].last().is_some_and(is_word)
+                        && !chars.get(end).is_some_and(is_word)));
+            if found {
+                hits.push((start, end));
+                start = end;
+            } else {
+                start += 1;
+            }
+        }
+        hits
+    }
+}
+
+/// 搜索状态：计数并在达到上限或回调要求时停止
+struct Search<'a, F> {
+    matcher: Matcher,
+    on_hit: &'a mut F,
+    count: usize,
+}
+
+impl<F: FnMut(SearchHit) -> ControlFlow<()>> Search<'_, F> {
+    fn text(&mut self, text: &str, location: impl Fn() -> SearchLocation) -> ControlFlow<()> {
+        let hits = self.matcher.find(text);
+        if hits.is_empty() {
+            return ControlFlow::Continue(());
+        }
+        let chars: Vec<char> = text.chars().collect();
+        let slice = |from: usize, to: usize| -> String {
+            chars[from..to]
+                .iter()
+                .map(|&c| if c.is_control() { ' ' } else { c })
+                .collect()
+        };
+        for (start, end) in hits {
+            self.count += 1;
+            (self.on_hit)(SearchHit {
+                location: location(),
+                before: slice(start.saturating_sub(CONTEXT_CHARS), start),
+                matched: slice(start, end),
+                after: slice(end, (end + CONTEXT_CHARS).min(chars.len())),
+            })?;
+            if self
+                .matcher
+                .options
+                .limit
+                .is_some_and(|limit| self.count >= limit)
+            {
+                return ControlFlow::Break(());
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn workbook(&mut self, book: &mut LazyWorkbook) -> Result<(), DocsError> {
+        let sheets = book.sheets().to_vec();
+        for info in sheets {
+            let mut offset = 0;
+            while offset < info.row_count {
+                let window = book.window(&info.name, offset, WINDOW_ROWS)?;
+                for (i, row) in window.rows.iter().enumerate() {
+                    for (col, cell) in row.iter().enumerate() {
+                        let location = || SearchLocation::Cell {
+                            sheet: info.name.clone(),
+                            row: offset + i as u32,
+                            col: col as u32,
+                        };
+                        if self.text(&cell.display, location).is_break() {
+                            return Ok(());
+                        }
+                    }
+                }
+                offset += WINDOW_ROWS;
+            }
+        }
+        Ok(())
+    }
+
+    fn blocks(&mut self, blocks: &[Block], index: &mut usize) -> ControlFlow<()> {
+        for block in blocks {
+            match block {
+                Block::Paragraph(paragraph) => self.paragraph(paragraph, index)?,
+                Block::Table(table) => {
+                    for cell in table.rows.iter().flat_map(|r| &r.cells) {
+                        self.blocks(&cell.blocks, index)?;
+                    }
+                },
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn paragraph(&mut self, paragraph: &Paragraph, index: &mut usize) -> ControlFlow<()> {
+        let location = *index;
+        *index += 1;
+        self.text(&paragraph.text(), || SearchLocation::Paragraph {
+            index: location,
+        })
+    }
+
+    fn presentation(&mut self, presentation: &Presentation) -> ControlFlow<()> {
+        for slide in &presentation.slides {
+            let blocks: Vec<Block> = slide.text_blocks().cloned().collect();
+            let location = |notes| move || SearchLocation::Slide { slide: slide.index, notes };
+            self.text(&plain_text(&blocks), location(false))?;
+            self.text(&plain_text(&slide.notes), location(true))?;
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// 不区分大小写时逐字符转为小写，保持字符下标不变
+fn fold(c: char, case_sensitive: bool) -> char {
+    if case_sensitive {
+        c
+    } else {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+}
+
+/// 在文档中搜索 `query`，每个命中调用一次 `on_hit`，返回 `Break` 时停止；返回命中总数
+pub fn search(
+    path: &str,
+    query: &str,
+    options: &SearchOptions,
+    mut on_hit: impl FnMut(SearchHit) -> ControlFlow<()>,
+) -> Result<usize, DocsError> {
+    if query.is_empty() {
+        return Ok(0);
+    }
+    let mut search = Search {
+        matcher: Matcher::new(query, options),
+        on_hit: &mut on_hit,
+        count: 0,
+    };
+
+    let format = DocsFormat::detect(path)?;
+    if format == DocsFormat::Sqlite {
+        // 数据库按表分页浏览，不做全文搜索
+        return Err(DocsError::Unsupported(format.name().to_string()));
+    }
+    if format.is_sheet() {
+        search.workbook(&mut LazyWorkbook::open(path)?)?;
+    } else if format == DocsFormat::Pdf {
+        for page in Pdf::open(path)?.text(&[])? {
+            if search
+                .text(&page.text, || SearchLocation::Page { page: page.page })
+                .is_break()
+            {
+                break;
+            }
+        }
+    } else {
+        match Docs::open(path)?.docs {
+            Docs::Docx(document)
+            | Docs::Odt(document)
+            | Docs::Doc(document)
+            | Docs::Rtf(document) => {
+                let _ = search.blocks(&document.blocks, &mut 0);
+            },
+            Docs::Pptx(presentation) | Docs::Odp(presentation) | Docs::Ppt(presentation) => {
+                let _ = search.presentation(&presentation);
+            },
+            // 表格与 PDF 已在上面搜索；电子书章节按需读取，暂不搜索
+            Docs::Excel(_)
+            | Docs::Pdf(_)
+            | Docs::Epub(_)
+            | Docs::Notebook(_)
+            | Docs::Sqlite(_)
+            | Docs::Columnar(_) => {},
+        }
+    }
+    log::info!("搜索完成: {}, 共 {} 处", path, search.count);
+    Ok(search.count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(
+        query: &str,
+        text: &str,
+        case_sensitive: bool,
+        whole_word: bool,
+    ) -> Vec<(usize, usize)> {
+        let options = SearchOptions { case_sensitive, whole_word, limit: None };
+        Matcher::new(query, &options).find(text)
+    }
+
+    #[test]
+    fn case_folding() {
+        assert_eq!(
+            find("rust", "Rust and RUST", false, false),
+            [(0, 4), (9, 13)]
+        );
+        assert_eq!(find("rust", "Rust and RUST", true, false), []);
+        assert_eq!(find("RUST", "Rust and RUST", true, false), [(9, 13)]);
+        assert_eq!(find("école", "ÉCOLE", false, false), [(0, 5)]);
+    }
+
+    #[test]
+    fn whole_word() {
+        let text = "cat concat cat_ cat.";
+        assert_eq!(find("cat", text, false, false).len(), 4);
+        assert_eq!(find("cat", text, false, true), [(0, 3), (16, 19)]);
+        assert_eq!(find("Cat", "the cat, CAT!", false, true), [(4, 7), (9, 12)]);
+    }
+
+    #[test]
+    fn char_indices_and_overlaps() {
+        // 下标按字符而不是字节
+        assert_eq!(find("中文", "学中文", false, false), [(1, 3)]);
+        assert_eq!(find("aa", "aaaa", false, false), [(0, 2), (2, 4)]);
+        assert_eq!(find("", "text", false, false), []);
+        assert_eq!(find("longer", "long", false, false), []);
+    }
+
+    #[test]
+    fn hits_with_context_and_limit() {
+        let options = SearchOptions { limit: Some(2), ..Default::default() };
+        let mut hits = Vec::new();
+        let mut on_hit = |hit: SearchHit| {
+            hits.push(hit);
+            ControlFlow::Continue(())
+        };
+        let mut search = Search {
+            matcher: Matcher::new("x", &options),
+            on_hit: &mut on_hit,
+            count: 0,
+        };
+        let location = || SearchLocation::Paragraph { index: 0 };
+        assert!(search.text("a\tx b x c x", location).is_break());
+        assert_eq!(hits.len(), 2);
+        assert_eq!(
+            (hits[0].before.as_str(), hits[0].matched.as_str()),
+            ("a ", "x")
+        );
+        assert_eq!(hits[0].after, " b x c x");
+    }
+}
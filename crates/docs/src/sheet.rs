@@ -0,0 +1,423 @@
+use crate::{
+    delimited::Dialect,
+    error::DocsError,
+    format::DocsFormat,
+    html, numfmt,
+    xlsx::{cell_reference, XlsxWorkbook},
+};
+use calamine::{Data, Ods, Reader, SheetVisible, Sheets, Xls, Xlsb, Xlsx};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use csv::ByteRecord;
+use serde::Serialize;
+use std::{collections::BTreeMap, fs::File, io::BufReader};
+
+/// 工作表
+#[derive(Debug, Clone, Serialize)]
+pub struct DSheet {
+    pub name: String,
+    /// 隐藏的工作表
+    pub hidden: bool,
+    /// 已使用区域左上角的 (行, 列)，`rows` 从这里开始
+    pub origin: (u32, u32),
+    /// 分隔符文本中识别出的表头，不包含在 `rows` 中
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<Vec<String>>,
+    pub rows: Vec<Vec<Cell>>,
+    pub layout: SheetLayout,
+    /// A1 引用 -> 公式，以 `=` 开头
+    pub formulas: BTreeMap<String, String>,
+    /// A1 引用 -> 批注
+    pub comments: BTreeMap<String, Comment>,
+    /// A1 引用 -> 超链接
+    pub hyperlinks: BTreeMap<String, Hyperlink>,
+}
+
+/// 单元格批注
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Comment {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub text: String,
+}
+
+/// 单元格超链接，工作簿内的位置以 `#` 开头，如 `#Sheet2!A1`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Hyperlink {
+    pub target: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tooltip: Option<String>,
+}
+
+/// 工作表布局，行列为从 0 开始的绝对坐标，尺寸单位为 px
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SheetLayout {
+    pub merges: Vec<MergedRange>,
+    pub col_widths: BTreeMap<u32, f64>,
+    pub row_heights: BTreeMap<u32, f64>,
+    pub hidden_rows: Vec<u32>,
+    pub hidden_cols: Vec<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub freeze: Option<FreezePane>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_col_width: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_row_height: Option<f64>,
+}
+
+/// 合并单元格区域
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MergedRange {
+    pub row: u32,
+    pub col: u32,
+    pub row_span: u32,
+    pub col_span: u32,
+}
+
+impl MergedRange {
+    /// 由左上角与右下角（含）构建
+    pub fn new(start: (u32, u32), end: (u32, u32)) -> Self {
+        MergedRange {
+            row: start.0.min(end.0),
+            col: start.1.min(end.1),
+            row_span: start.0.abs_diff(end.0) + 1,
+            col_span: start.1.abs_diff(end.1) + 1,
+        }
+    }
+}
+
+/// 冻结窗格：顶部冻结的行数与左侧冻结的列数
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FreezePane {
+    pub rows: u32,
+    pub cols: u32,
+}
+
+/// 单元格的原始值
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum CellValue {
+    Empty,
+    String(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    /// ISO 8601 日期时间
+    DateTime(String),
+    /// 时长（秒）
+    Duration(f64),
+    /// 错误值，如 `#DIV/0!`
+    Error(String),
+}
+
+/// 单元格：原始值与按数字格式格式化后的显示文本
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Cell {
+    #[serde(flatten)]
+    pub value: CellValue,
+    pub display: String,
+}
+
+impl Cell {
+    pub fn empty() -> Self {
+        Cell {
+            value: CellValue::Empty,
+            display: String::new(),
+        }
+    }
+
+    /// 由 calamine 的单元格数据构建，`format` 为数字格式代码
+    pub fn from_data(data: &Data, format: Option<&str>) -> Self {
+        let number = |value: f64| match format {
+            Some(code) => numfmt::format_number(value, code),
+            None => numfmt::general(value),
+        };
+
+        match data {
+            Data::Empty => Cell::empty(),
+            Data::String(s) => Cell {
+                value: CellValue::String(s.clone()),
+                display: s.clone(),
+            },
+            Data::Bool(b) => Cell {
+                value: CellValue::Bool(*b),
+                display: if *b { "TRUE" } else { "FALSE" }.to_string(),
+            },
+            Data::Error(e) => Cell {
+                value: CellValue::Error(e.to_string()),
+                display: e.to_string(),
+            },
+            Data::Int(i) => Cell {
+                value: CellValue::Int(*i),
+                display: number(*i as f64),
+            },
+            Data::Float(f) => Cell {
+                value: float_value(*f),
+                display: number(*f),
+            },
+            Data::DateTime(dt) if dt.is_duration() => {
+                let serial = dt.as_f64();
+                Cell {
+                    value: CellValue::Duration(serial * 86_400.0),
+                    display: match format {
+                        Some(code) => numfmt::format_number(serial, code),
+                        None => numfmt::format_duration(serial * 86_400.0),
+                    },
+                }
+            },
+            Data::DateTime(dt) => match dt.as_datetime() {
+                Some(datetime) => Cell {
+                    value: CellValue::DateTime(datetime.format("%Y-%m-%dT%H:%M:%S").to_string()),
+                    display: numfmt::format_datetime(datetime, dt.as_f64(), format),
+                },
+                None => Cell {
+                    value: float_value(dt.as_f64()),
+                    display: numfmt::general(dt.as_f64()),
+                },
+            },
+            Data::DateTimeIso(s) => Cell {
+                value: CellValue::DateTime(s.clone()),
+                display: iso_display(s),
+            },
+            Data::DurationIso(s) => match parse_iso_duration(s) {
+                Some(seconds) => Cell {
+                    value: CellValue::Duration(seconds),
+                    display: numfmt::format_duration(seconds),
+                },
+                None => Cell {
+                    value: CellValue::String(s.clone()),
+                    display: s.clone(),
+                },
+            },
+        }
+    }
+
+    /// 由 CSV 文本推断类型，显示文本保持原样
+    pub fn from_text(text: &str) -> Self {
+        let trimmed = text.trim();
+        let value = if trimmed.is_empty() {
+            CellValue::Empty
+        } else if let Ok(i) = trimmed.parse::<i64>() {
+            CellValue::Int(i)
+        } else if let Some(f) = parse_float(trimmed) {
+            CellValue::Float(f)
+        } else if trimmed.eq_ignore_ascii_case("true") {
+            CellValue::Bool(true)
+        } else if trimmed.eq_ignore_ascii_case("false") {
+            CellValue::Bool(false)
+        } else {
+            CellValue::String(text.to_string())
+        };
+        Cell { value, display: text.to_string() }
+    }
+}
+
+/// 整数值的浮点数按整数处理
+fn float_value(f: f64) -> CellValue {
+    if f.fract() == 0.0 && f.abs() < 9_007_199_254_740_992.0 {
+        CellValue::Int(f as i64)
+    } else {
+        CellValue::Float(f)
+    }
+}
+
+/// 只接受普通的十进制/科学计数写法，排除 `inf`、`NaN` 等
+fn parse_float(text: &str) -> Option<f64> {
+    let valid = text
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'))
+        && text.chars().any(|c| c.is_ascii_digit());
+    valid.then(|| text.parse().ok()).flatten()
+}
+
+fn iso_display(iso: &str) -> String {
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(iso, "%Y-%m-%dT%H:%M:%S%.f") {
+        let serial = if datetime.time() == NaiveTime::MIN {
+            1.0
+        } else {
+            1.5
+        };
+        return numfmt::default_datetime(datetime, serial);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(iso, "%Y-%m-%d") {
+        return date.format("%Y-%m-%d").to_string();
+    }
+    iso.to_string()
+}
+
+/// 解析 `PT12H30M15S`、`P1DT2H` 形式的时长
+fn parse_iso_duration(iso: &str) -> Option<f64> {
+    let negative = iso.starts_with('-');
+    let rest = iso.trim_start_matches('-').strip_prefix('P')?;
+    let mut seconds = 0.0;
+    let mut in_time = false;
+    let mut number = String::new();
+    for c in rest.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' | '.' | ',' => number.push(if c == ',' { '.' } else { c }),
+            unit => {
+                let value: f64 = number.parse().ok()?;
+                number.clear();
+                seconds += value
+                    * match (unit, in_time) {
+                        ('D', false) => 86_400.0,
+                        ('W', false) => 604_800.0,
+                        ('H', true) => 3_600.0,
+                        ('M', true) => 60.0,
+                        ('S', true) => 1.0,
+                        _ => return None,
+                    };
+            },
+        }
+    }
+    Some(if negative { -seconds } else { seconds })
+}
+
+/// 公式区域中位于 `rows`（绝对行号）内的公式，以 A1 引用为键
+pub(crate) fn formula_map(
+    formulas: &calamine::Range<String>,
+    rows: std::ops::Range<u32>,
+) -> BTreeMap<String, String> {
+    let (top, left) = formulas.start().unwrap_or((0, 0));
+    formulas
+        .used_cells()
+        .map(|(r, c, f)| (top + r as u32, left + c as u32, f))
+        .filter(|(row, _, f)| rows.contains(row) && !f.is_empty())
+        .map(|(row, col, f)| {
+            let formula = format!("={}", f.trim_start_matches('='));
+            (cell_reference(row, col), formula)
+        })
+        .collect()
+}
+
+/// xls 的合并单元格由 calamine 提供，其他格式返回 None
+pub(crate) fn xls_merges<RS: std::io::Read + std::io::Seek>(
+    workbook: &Sheets<RS>,
+    name: &str,
+) -> Option<Vec<MergedRange>> {
+    let Sheets::Xls(xls) = workbook else {
+        return None;
+    };
+    let merges = xls.worksheet_merge_cells(name).unwrap_or_default();
+    Some(
+        merges
+            .iter()
+            .map(|d| MergedRange::new(d.start, d.end))
+            .collect(),
+    )
+}
+
+/// 按识别出的格式打开工作簿，不依赖扩展名
+pub(crate) fn open_workbook(
+    path: &str,
+    format: DocsFormat,
+) -> Result<Sheets<BufReader<File>>, DocsError> {
+    use calamine::{open_workbook, Error};
+
+    Ok(match format {
+        DocsFormat::Xlsx => Sheets::Xlsx(open_workbook::<Xlsx<_>, _>(path)?),
+        DocsFormat::Xlsb => Sheets::Xlsb(open_workbook::<Xlsb<_>, _>(path).map_err(Error::Xlsb)?),
+        DocsFormat::Xls => Sheets::Xls(open_workbook::<Xls<_>, _>(path).map_err(Error::Xls)?),
+        DocsFormat::Ods => Sheets::Ods(open_workbook::<Ods<_>, _>(path).map_err(Error::Ods)?),
+        format => return Err(DocsError::Unsupported(format.name().to_string())),
+    })
+}
+
+/// 读取工作簿的全部工作表；导出为 .xls 的 HTML 与分隔符文本按实际格式读取
+pub(crate) fn excel(file_path: &str) -> Result<Vec<DSheet>, DocsError> {
+    let format = DocsFormat::detect(file_path)?;
+    match format {
+        DocsFormat::Csv => return csv(file_path),
+        DocsFormat::Html => return html::tables(file_path),
+        _ => {},
+    }
+    let mut workbook = open_workbook(file_path, format)?;
+    let sheets = workbook.sheets_metadata().to_owned();
+    // 只有 xlsx 能读取到数字格式与布局，其他格式按常规格式显示
+    let mut xlsx = XlsxWorkbook::open(file_path).ok();
+    let mut target: Vec<DSheet> = Vec::new();
+
+    for sheet in sheets {
+        let range = workbook.worksheet_range(&sheet.name)?;
+        let sheet_xml = match xlsx.as_mut() {
+            Some(xlsx) => xlsx.sheet(&sheet.name).unwrap_or_else(|e| {
+                log::error!("读取工作表格式失败: {}: {}", sheet.name, e);
+                Default::default()
+            }),
+            None => Default::default(),
+        };
+        let formulas = match workbook.worksheet_formula(&sheet.name) {
+            Ok(formulas) => formula_map(&formulas, 0..u32::MAX),
+            Err(e) => {
+                log::error!("读取工作表公式失败: {}: {}", sheet.name, e);
+                BTreeMap::new()
+            },
+        };
+        let origin = range.start().unwrap_or((0, 0));
+        let last_col = range.end().map(|(_, col)| col).unwrap_or(0);
+
+        let mut rows = Vec::new();
+        for (r, row) in range.rows().enumerate() {
+            let mut cell_list: Vec<Cell> = Vec::new();
+            for (c, cell) in row.iter().enumerate() {
+                let position = (origin.0 + r as u32, origin.1 + c as u32);
+                let format = xlsx
+                    .as_ref()
+                    .and_then(|x| x.cell_format(&sheet_xml, position));
+                cell_list.push(Cell::from_data(cell, format));
+            }
+            rows.push(cell_list);
+        }
+
+        let mut layout = sheet_xml.layout(last_col);
+        if let Some(merges) = xls_merges(&workbook, &sheet.name) {
+            layout.merges = merges;
+        }
+
+        let map = DSheet {
+            name: sheet.name,
+            hidden: sheet.visible != SheetVisible::Visible,
+            origin,
+            header: None,
+            rows,
+            layout,
+            formulas,
+            comments: sheet_xml.comments,
+            hyperlinks: sheet_xml.hyperlinks,
+        };
+
+        target.push(map);
+    }
+    Ok(target)
+}
+
+pub(crate) fn csv(file_path: &str) -> Result<Vec<DSheet>, DocsError> {
+    let dialect = Dialect::sniff(file_path)?;
+    let mut rdr = dialect
+        .reader_builder()
+        .from_reader(dialect.open(file_path)?);
+
+    let mut header = None;
+    let mut rows: Vec<Vec<Cell>> = vec![];
+    let mut record = ByteRecord::new();
+    while rdr.read_byte_record(&mut record)? {
+        let fields = dialect.fields(&record);
+        if dialect.has_header && header.is_none() {
+            header = Some(fields);
+            continue;
+        }
+        rows.push(fields.iter().map(|f| Cell::from_text(f)).collect());
+    }
+    let target: Vec<DSheet> = vec![DSheet {
+        name: "sheet1".to_string(),
+        hidden: false,
+        origin: (0, 0),
+        header,
+        rows,
+        layout: SheetLayout::default(),
+        formulas: BTreeMap::new(),
+        comments: BTreeMap::new(),
+        hyperlinks: BTreeMap::new(),
+    }];
+    Ok(target)
+}
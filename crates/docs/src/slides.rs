@@ -0,0 +1,73 @@
+use crate::{
+    document::{Block, Image, Table},
+    metadata::DocumentMetadata,
+};
+use serde::Serialize;
+
+/// 演示文稿（pptx/odp/ppt）的统一模型
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Presentation {
+    /// 幻灯片宽度（px）
+    pub width: f32,
+    /// 幻灯片高度（px）
+    pub height: f32,
+    pub slides: Vec<Slide>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<DocumentMetadata>,
+}
+
+/// 单张幻灯片
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Slide {
+    /// 序号（从 1 开始）
+    pub index: usize,
+    /// 标题占位符中的文字
+    pub title: Option<String>,
+    /// 是否在放映时隐藏
+    pub hidden: bool,
+    /// 按叠放顺序排列的形状
+    pub shapes: Vec<Shape>,
+    /// 演讲者备注
+    pub notes: Vec<Block>,
+}
+
+/// 幻灯片上的一个形状
+#[derive(Debug, Clone, Serialize)]
+pub struct Shape {
+    pub name: Option<String>,
+    /// 位置与大小，继承自版式且无法解析时为空
+    pub frame: Option<Frame>,
+    #[serde(flatten)]
+    pub content: ShapeContent,
+}
+
+/// 位置与大小（px）
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Frame {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// 形状内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ShapeContent {
+    /// 文本框
+    Text {
+        blocks: Vec<Block>,
+    },
+    Image(Image),
+    Table(Table),
+}
+
+impl Slide {
+    /// 幻灯片中所有文本框的段落
+    pub fn text_blocks(&self) -> impl Iterator<Item = &Block> {
+        self.shapes.iter().flat_map(|shape| match &shape.content {
+            ShapeContent::Text { blocks } => blocks.as_slice(),
+            _ => &[],
+        })
+    }
+}
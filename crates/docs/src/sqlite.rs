@@ -0,0 +1,247 @@
+//! SQLite：通过 SQLite 自身以只读方式打开，支持 WAL、视图与 WITHOUT ROWID 表
+//!
+//! 概要只读取 sqlite_master 与各项的列定义，不扫描数据页；行数在选中表时单独统计。
+
+use crate::error::DocsError;
+use rusqlite::{types::ValueRef, Connection, OpenFlags};
+use serde::Serialize;
+
+/// 文件头
+pub const SQLITE_MAGIC: &[u8; 16] = b"SQLite format 3\0";
+/// BLOB 只输出开头的字节
+const BLOB_PREVIEW: usize = 32;
+/// 单个文本值最多输出的字节数，超出部分被截断
+const MAX_TEXT: usize = 1 << 20;
+/// 每次最多读取的行数
+pub const MAX_PAGE_ROWS: u32 = 1000;
+
+/// 数据库概要
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseInfo {
+    pub page_size: u32,
+    pub page_count: u32,
+    /// 文本编码：`UTF-8`、`UTF-16le` 或 `UTF-16be`
+    pub encoding: String,
+    pub user_version: u32,
+    pub application_id: u32,
+    /// 是否为 WAL 日志模式
+    pub wal: bool,
+    /// sqlite_master 中的表、视图、索引与触发器
+    pub objects: Vec<SchemaObject>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ObjectKind {
+    Table,
+    View,
+    Index,
+    Trigger,
+}
+
+/// sqlite_master 中的一项
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaObject {
+    pub kind: ObjectKind,
+    pub name: String,
+    /// 所属的表，表与视图为自身
+    pub table: String,
+    /// 建表语句，自动创建的索引没有
+    pub sql: Option<String>,
+    /// 表与视图的列
+    pub columns: Vec<Column>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Column {
+    pub name: String,
+    /// 声明的类型，可以为空
+    pub declared_type: Option<String>,
+    pub primary_key: bool,
+    /// 默认值表达式
+    pub default: Option<String>,
+}
+
+/// 按存储类型区分的值
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum SqlValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    /// 总字节数与开头部分的十六进制
+    Blob {
+        size: usize,
+        hex: String,
+    },
+}
+
+impl From<ValueRef<'_>> for SqlValue {
+    fn from(value: ValueRef<'_>) -> Self {
+        match value {
+            ValueRef::Null => SqlValue::Null,
+            ValueRef::Integer(value) => SqlValue::Integer(value),
+            ValueRef::Real(value) => SqlValue::Real(value),
+            ValueRef::Text(text) => {
+                SqlValue::Text(String::from_utf8_lossy(&text[..text.len().min(MAX_TEXT)]).into())
+            },
+            ValueRef::Blob(blob) => SqlValue::Blob {
+                size: blob.len(),
+                hex: blob
+                    .iter()
+                    .take(BLOB_PREVIEW)
+                    .map(|b| format!("{:02x}", b))
+                    .collect(),
+            },
+        }
+    }
+}
+
+/// 表中的一段行
+#[derive(Debug, Clone, Serialize)]
+pub struct TableRows {
+    pub table: String,
+    pub offset: u64,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<SqlValue>>,
+}
+
+/// 只读打开的数据库
+pub struct Sqlite {
+    conn: Connection,
+}
+
+impl Sqlite {
+    pub fn open(path: &str) -> Result<Self, DocsError> {
+        let flags = OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+        let conn = Connection::open_with_flags(path, flags)?;
+        log::info!("打开 SQLite: {}", path);
+        Ok(Sqlite { conn })
+    }
+
+    /// 读取数据库参数与 sqlite_master，不统计行数
+    pub fn info(&self) -> Result<DatabaseInfo, DocsError> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT type, name, tbl_name, sql FROM sqlite_master ORDER BY rowid")?;
+        let mut rows = statement.query([])?;
+        let mut objects = Vec::new();
+        while let Some(row) = rows.next()? {
+            let kind = match row.get_ref(0)?.as_str().unwrap_or_default() {
+                "table" => ObjectKind::Table,
+                "view" => ObjectKind::View,
+                "index" => ObjectKind::Index,
+                "trigger" => ObjectKind::Trigger,
+                _ => continue,
+            };
+            let name: String = row.get(1)?;
+            let columns = match kind {
+                ObjectKind::Table | ObjectKind::View => self.columns(&name).unwrap_or_else(|e| {
+                    log::warn!("读取列定义失败: {}: {}", name, e);
+                    Vec::new()
+                }),
+                ObjectKind::Index | ObjectKind::Trigger => Vec::new(),
+            };
+            objects.push(SchemaObject {
+                kind,
+                name,
+                table: row.get(2)?,
+                sql: row.get(3)?,
+                columns,
+            });
+        }
+
+        let encoding: String = self.pragma("encoding")?;
+        let journal_mode: String = self.pragma("journal_mode")?;
+        Ok(DatabaseInfo {
+            page_size: self.pragma("page_size")?,
+            page_count: self.pragma("page_count")?,
+            encoding,
+            user_version: self.pragma::<i64>("user_version")? as u32,
+            application_id: self.pragma::<i64>("application_id")? as u32,
+            wal: journal_mode.eq_ignore_ascii_case("wal"),
+            objects,
+        })
+    }
+
+    /// 表或视图的行数
+    pub fn count(&self, table: &str) -> Result<u64, DocsError> {
+        let name = self.relation(table)?;
+        let sql = format!("SELECT count(*) FROM {}", quote(&name));
+        let count: i64 = self.conn.query_row(&sql, [], |row| row.get(0))?;
+        Ok(count.max(0) as u64)
+    }
+
+    /// 读取表或视图 `table` 从 `offset` 开始的 `limit` 行（按存储顺序）
+    pub fn rows(&self, table: &str, offset: u64, limit: u32) -> Result<TableRows, DocsError> {
+        let name = self.relation(table)?;
+        let sql = format!("SELECT * FROM {} LIMIT ?1 OFFSET ?2", quote(&name));
+        let mut statement = self.conn.prepare(&sql)?;
+        let columns: Vec<String> = statement
+            .column_names()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+
+        let limit = limit.min(MAX_PAGE_ROWS);
+        let offset_param = i64::try_from(offset).unwrap_or(i64::MAX);
+        let mut query = statement.query((limit, offset_param))?;
+        let mut rows = Vec::new();
+        while let Some(row) = query.next()? {
+            let values = (0..columns.len())
+                .map(|i| row.get_ref(i).map(SqlValue::from))
+                .collect::<Result<Vec<_>, _>>()?;
+            rows.push(values);
+        }
+        Ok(TableRows { table: name, offset, columns, rows })
+    }
+
+    /// 按名称（不区分大小写）查找表或视图，返回 sqlite_master 中记录的名称
+    fn relation(&self, table: &str) -> Result<String, DocsError> {
+        self.conn
+            .query_row(
+                "SELECT name FROM sqlite_master \
+                 WHERE type IN ('table', 'view') AND name = ?1 COLLATE NOCASE",
+                [table],
+                |row| row.get(0),
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => DocsError::SheetNotFound(table.to_string()),
+                e => e.into(),
+            })
+    }
+
+    /// 表或视图的列定义
+    fn columns(&self, table: &str) -> Result<Vec<Column>, DocsError> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT name, type, pk, dflt_value FROM pragma_table_info(?1)")?;
+        let columns = statement
+            .query_map([table], |row| {
+                Ok(Column {
+                    name: row.get(0)?,
+                    declared_type: row.get::<_, Option<String>>(1)?.filter(|t| !t.is_empty()),
+                    primary_key: row.get::<_, i64>(2)? > 0,
+                    default: row.get(3)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(columns)
+    }
+
+    fn pragma<T: rusqlite::types::FromSql>(&self, name: &str) -> Result<T, DocsError> {
+        let sql = format!("PRAGMA {}", name);
+        Ok(self.conn.query_row(&sql, [], |row| row.get(0))?)
+    }
+}
+
+/// 按 SQL 标识符加引号
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// 打开数据库并读取概要
+pub fn parse(path: &str) -> Result<DatabaseInfo, DocsError> {
+    Sqlite::open(path)?.info()
+}
@@ -0,0 +1,458 @@
+//! 大工作簿、CSV 与列式数据文件的分页读取：先返回各工作表的概要，再按需读取行窗口
+
+use crate::{
+    columnar::Columnar,
+    delimited::Dialect,
+    error::DocsError,
+    export::{export_rows, ExportFormat, SheetRange},
+    format::DocsFormat,
+    html,
+    sheet::{
+        formula_map, open_workbook, xls_merges, Cell, Comment, DSheet, Hyperlink, SheetLayout,
+    },
+    xlsx::{cell_reference, SheetXml, XlsxWorkbook},
+};
+use calamine::{Data, Range, Reader, SheetVisible, Sheets};
+use csv::ByteRecord;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::BufReader,
+    io::{Seek, SeekFrom},
+};
+
+/// CSV 每隔多少行记录一次字节偏移
+const CSV_CHECKPOINT: u32 = 1000;
+
+/// 工作表概要：尺寸、布局与批注/超链接，不含单元格数据
+#[derive(Debug, Clone, Serialize)]
+pub struct SheetInfo {
+    pub name: String,
+    pub hidden: bool,
+    /// 已使用区域左上角的 (行, 列)
+    pub origin: (u32, u32),
+    /// 分隔符文本中识别出的表头，不计入行数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<Vec<String>>,
+    /// 已使用区域的行数
+    pub row_count: u32,
+    /// 已使用区域的列数
+    pub col_count: u32,
+    pub layout: SheetLayout,
+    pub comments: BTreeMap<String, Comment>,
+    pub hyperlinks: BTreeMap<String, Hyperlink>,
+}
+
+/// 行窗口
+#[derive(Debug, Clone, Serialize)]
+pub struct SheetWindow {
+    pub sheet: String,
+    /// 相对于 origin 的起始行
+    pub row_offset: u32,
+    /// 每行都补齐到 `col_count` 列
+    pub rows: Vec<Vec<Cell>>,
+    /// 窗口内的公式，A1 引用为键
+    pub formulas: BTreeMap<String, String>,
+}
+
+/// 按需读取行窗口的工作簿
+pub struct LazyWorkbook {
+    source: Source,
+    sheets: Vec<SheetInfo>,
+}
+
+enum Source {
+    Excel(Box<ExcelSource>),
+    Csv(CsvIndex),
+    /// Parquet 与 Arrow IPC，按行组读取
+    Columnar(Columnar),
+    /// 打开时已全部读入的表格（HTML）
+    Loaded(Vec<DSheet>),
+}
+
+struct ExcelSource {
+    workbook: Sheets<BufReader<File>>,
+    /// 只有 xlsx 支持流式读取单元格
+    xlsx: Option<XlsxWorkbook>,
+    /// xlsx 工作表名 -> 扫描结果（样式等）
+    scans: HashMap<String, SheetXml>,
+    /// 其他格式在打开时读入的 (值, 公式)
+    ranges: HashMap<String, (Range<Data>, Range<String>)>,
+}
+
+impl SheetInfo {
+    /// 已全部读入的工作表的概要
+    fn from_sheet(sheet: &DSheet) -> Self {
+        SheetInfo {
+            name: sheet.name.clone(),
+            hidden: sheet.hidden,
+            origin: sheet.origin,
+            header: sheet.header.clone(),
+            row_count: sheet.rows.len() as u32,
+            col_count: sheet.rows.iter().map(Vec::len).max().unwrap_or(0) as u32,
+            layout: sheet.layout.clone(),
+            comments: sheet.comments.clone(),
+            hyperlinks: sheet.hyperlinks.clone(),
+        }
+    }
+
+    /// 列式数据文件只有一张表，列名作为表头
+    fn from_columnar(columnar: &Columnar) -> Self {
+        let info = columnar.info();
+        if info.row_count > u32::MAX as u64 {
+            log::warn!("列式文件行数超出上限，只显示前 {} 行", u32::MAX);
+        }
+        SheetInfo {
+            name: "sheet1".to_string(),
+            hidden: false,
+            origin: (0, 0),
+            header: Some(info.columns.iter().map(|c| c.name.clone()).collect()),
+            row_count: info.row_count.min(u32::MAX as u64) as u32,
+            col_count: info.columns.len() as u32,
+            layout: SheetLayout::default(),
+            comments: BTreeMap::new(),
+            hyperlinks: BTreeMap::new(),
+        }
+    }
+}
+
+impl LazyWorkbook {
+    /// 按内容识别格式，打开工作簿、CSV 或列式数据文件并计算各工作表的概要
+    pub fn open(path: &str) -> Result<Self, DocsError> {
+        let format = DocsFormat::detect(path)?;
+        match format {
+            DocsFormat::Csv => {
+                let index = CsvIndex::build(path)?;
+                let sheets = vec![index.info()];
+                return Ok(LazyWorkbook { source: Source::Csv(index), sheets });
+            },
+            DocsFormat::Html => {
+                let tables = html::tables(path)?;
+                let sheets = tables.iter().map(SheetInfo::from_sheet).collect();
+                return Ok(LazyWorkbook { source: Source::Loaded(tables), sheets });
+            },
+            DocsFormat::Parquet | DocsFormat::Arrow => {
+                let columnar = Columnar::open(path)?;
+                let sheets = vec![SheetInfo::from_columnar(&columnar)];
+                return Ok(LazyWorkbook {
+                    source: Source::Columnar(columnar),
+                    sheets,
+                });
+            },
+            _ => {},
+        }
+
+        let mut workbook = open_workbook(path, format)?;
+        let mut xlsx = match workbook {
+            Sheets::Xlsx(_) => XlsxWorkbook::open(path).ok(),
+            _ => None,
+        };
+        let mut scans = HashMap::new();
+        let mut ranges = HashMap::new();
+        let mut sheets = Vec::new();
+
+        for meta in workbook.sheets_metadata().to_owned() {
+            let (used, scan) = match xlsx.as_mut() {
+                Some(xlsx) => {
+                    let scan = xlsx.sheet(&meta.name).unwrap_or_else(|e| {
+                        log::error!("读取工作表格式失败: {}: {}", meta.name, e);
+                        Default::default()
+                    });
+                    (scan.used, scan)
+                },
+                None => {
+                    let range = workbook.worksheet_range(&meta.name)?;
+                    let formulas = workbook.worksheet_formula(&meta.name).unwrap_or_else(|e| {
+                        log::error!("读取工作表公式失败: {}: {}", meta.name, e);
+                        Range::default()
+                    });
+                    let used = range.start().zip(range.end());
+                    ranges.insert(meta.name.clone(), (range, formulas));
+                    (used, SheetXml::default())
+                },
+            };
+
+            let ((top, left), (bottom, right)) = used.unwrap_or_default();
+            let mut layout = scan.layout(right);
+            if let Some(merges) = xls_merges(&workbook, &meta.name) {
+                layout.merges = merges;
+            }
+            sheets.push(SheetInfo {
+                name: meta.name.clone(),
+                hidden: meta.visible != SheetVisible::Visible,
+                origin: (top, left),
+                header: None,
+                row_count: used.map_or(0, |_| bottom - top + 1),
+                col_count: used.map_or(0, |_| right - left + 1),
+                layout,
+                comments: scan.comments.clone(),
+                hyperlinks: scan.hyperlinks.clone(),
+            });
+            if xlsx.is_some() {
+                scans.insert(meta.name, scan);
+            }
+        }
+
+        let source = Source::Excel(Box::new(ExcelSource { workbook, xlsx, scans, ranges }));
+        Ok(LazyWorkbook { source, sheets })
+    }
+
+    /// 各工作表的概要
+    pub fn sheets(&self) -> &[SheetInfo] {
+        &self.sheets
+    }
+
+    /// 读取从 `row_offset`（相对于 origin）开始的至多 `row_count` 行
+    pub fn window(
+        &mut self,
+        sheet: &str,
+        row_offset: u32,
+        row_count: u32,
+    ) -> Result<SheetWindow, DocsError> {
+        let info = self
+            .sheets
+            .iter()
+            .find(|s| s.name == sheet)
+            .ok_or_else(|| DocsError::SheetNotFound(sheet.to_string()))?;
+        let count = row_count.min(info.row_count.saturating_sub(row_offset));
+
+        let mut window = SheetWindow {
+            sheet: sheet.to_string(),
+            row_offset,
+            rows: Vec::new(),
+            formulas: BTreeMap::new(),
+        };
+        match &mut self.source {
+            Source::Csv(index) => window.rows = index.window(row_offset, count)?,
+            Source::Columnar(columnar) => {
+                window.rows = columnar.rows(row_offset as u64, count as usize)?
+            },
+            Source::Excel(excel) => excel.fill(info, count, &mut window)?,
+            Source::Loaded(tables) => {
+                if let Some(table) = tables.iter().find(|t| t.name == sheet) {
+                    let start = row_offset as usize;
+                    let rows = table.rows.iter().skip(start).take(count as usize);
+                    window.rows = rows.cloned().collect();
+                }
+            },
+        }
+        Ok(window)
+    }
+
+    /// 导出工作表或其中的区域，只读取区域覆盖的行
+    pub fn export(
+        &mut self,
+        sheet: &str,
+        range: Option<SheetRange>,
+        format: ExportFormat,
+    ) -> Result<String, DocsError> {
+        let (offset, count) = match range {
+            Some(range) => range.rows()?,
+            None => (0, u32::MAX),
+        };
+        let window = self.window(sheet, offset, count)?;
+        let header = self
+            .sheets
+            .iter()
+            .find(|s| s.name == sheet)
+            .and_then(|s| s.header.clone());
+        let cols = range.map(|r| r.cols());
+        let text = export_rows(header.as_deref(), &window.rows, cols, format)?;
+        log::info!("导出工作表: {}, {} 行", sheet, window.rows.len());
+        Ok(text)
+    }
+}
+
+impl ExcelSource {
+    /// 读取 `count` 行填入窗口
+    fn fill(
+        &mut self,
+        info: &SheetInfo,
+        count: u32,
+        window: &mut SheetWindow,
+    ) -> Result<(), DocsError> {
+        let (top, left) = info.origin;
+        let first = top + window.row_offset;
+        let rows_range = first..first + count;
+        let cols_range = left..left + info.col_count;
+        let rows = &mut window.rows;
+        *rows = vec![vec![Cell::empty(); info.col_count as usize]; count as usize];
+
+        if let Some((range, formulas)) = self.ranges.get(&info.name) {
+            for row in rows_range.clone() {
+                for col in cols_range.clone() {
+                    if let Some(data) = range.get_value((row, col)) {
+                        rows[(row - first) as usize][(col - left) as usize] =
+                            Cell::from_data(data, None);
+                    }
+                }
+            }
+            window.formulas = formula_map(formulas, rows_range);
+            return Ok(());
+        }
+
+        let (Sheets::Xlsx(workbook), Some(xlsx)) = (&mut self.workbook, &self.xlsx) else {
+            return Ok(());
+        };
+        let scan = self.scans.get(&info.name);
+
+        // 单元格按行顺序排列，读过窗口末尾即可停止
+        {
+            let mut reader = workbook.worksheet_cells_reader(&info.name)?;
+            while let Some(cell) = reader.next_cell()? {
+                let (row, col) = cell.get_position();
+                if row >= rows_range.end {
+                    break;
+                }
+                if !rows_range.contains(&row) || !cols_range.contains(&col) {
+                    continue;
+                }
+                let format = scan.and_then(|s| xlsx.cell_format(s, (row, col)));
+                let data: Data = cell.get_value().clone().into();
+                rows[(row - first) as usize][(col - left) as usize] =
+                    Cell::from_data(&data, format);
+            }
+        }
+
+        // 共享公式需要从头读取才能展开
+        let mut reader = workbook.worksheet_cells_reader(&info.name)?;
+        while let Some(cell) = reader.next_formula()? {
+            let (row, col) = cell.get_position();
+            if row >= rows_range.end {
+                break;
+            }
+            let formula = cell.get_value();
+            if rows_range.contains(&row) && !formula.is_empty() {
+                let formula = format!("={}", formula.trim_start_matches('='));
+                window.formulas.insert(cell_reference(row, col), formula);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 分隔符文本的字节偏移索引
+struct CsvIndex {
+    path: String,
+    dialect: Dialect,
+    header: Option<Vec<String>>,
+    /// 第 `i * CSV_CHECKPOINT` 条数据记录相对于正文开头的字节偏移
+    checkpoints: Vec<u64>,
+    row_count: u32,
+    col_count: u32,
+}
+
+impl CsvIndex {
+    fn build(path: &str) -> Result<Self, DocsError> {
+        let dialect = Dialect::sniff(path)?;
+        let mut rdr = dialect.reader_builder().from_reader(dialect.open(path)?);
+
+        let mut record = ByteRecord::new();
+        let mut header = None;
+        if dialect.has_header && rdr.read_byte_record(&mut record)? {
+            header = Some(dialect.fields(&record));
+        }
+        let mut checkpoints = Vec::new();
+        let mut row_count: u32 = 0;
+        let mut col_count = header.as_ref().map_or(0, Vec::len);
+        loop {
+            let position = rdr.position().byte();
+            if !rdr.read_byte_record(&mut record)? {
+                break;
+            }
+            if row_count % CSV_CHECKPOINT == 0 {
+                checkpoints.push(position);
+            }
+            row_count += 1;
+            col_count = col_count.max(record.len());
+        }
+        Ok(CsvIndex {
+            path: path.to_string(),
+            dialect,
+            header,
+            checkpoints,
+            row_count,
+            col_count: col_count as u32,
+        })
+    }
+
+    fn info(&self) -> SheetInfo {
+        SheetInfo {
+            name: "sheet1".to_string(),
+            hidden: false,
+            origin: (0, 0),
+            header: self.header.clone(),
+            row_count: self.row_count,
+            col_count: self.col_count,
+            layout: SheetLayout::default(),
+            comments: BTreeMap::new(),
+            hyperlinks: BTreeMap::new(),
+        }
+    }
+
+    fn window(&self, offset: u32, count: u32) -> Result<Vec<Vec<Cell>>, DocsError> {
+        let Some(position) = self.checkpoints.get((offset / CSV_CHECKPOINT) as usize) else {
+            return Ok(Vec::new());
+        };
+        let mut source = self.dialect.open(&self.path)?;
+        source.seek(SeekFrom::Current(*position as i64))?;
+        let mut rdr = self.dialect.reader_builder().from_reader(source);
+
+        let mut record = ByteRecord::new();
+        for _ in 0..offset % CSV_CHECKPOINT {
+            rdr.read_byte_record(&mut record)?;
+        }
+        let mut rows = Vec::with_capacity(count as usize);
+        while rows.len() < count as usize && rdr.read_byte_record(&mut record)? {
+            let mut row: Vec<Cell> = self
+                .dialect
+                .fields(&record)
+                .iter()
+                .map(|f| Cell::from_text(f))
+                .collect();
+            row.resize(self.col_count as usize, Cell::empty());
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// CSV 按需读取，文件需在测试结束前保留
+    fn csv_file(name: &str, content: &str) -> String {
+        let path = std::env::temp_dir().join(format!("{}-{}.csv", name, std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn range(first_row: u32, last_row: u32) -> SheetRange {
+        SheetRange {
+            first_row,
+            first_col: 0,
+            last_row,
+            last_col: 1,
+        }
+    }
+
+    #[test]
+    fn export_range() {
+        let path = csv_file("export-range", "a,b\n1,2\n3,4\n5,6\n");
+        let mut workbook = LazyWorkbook::open(&path).unwrap();
+        let sheet = workbook.sheets()[0].name.clone();
+        let format = ExportFormat::Csv { delimiter: ',' };
+
+        let all = workbook
+            .export(&sheet, Some(range(0, u32::MAX)), format)
+            .unwrap();
+        assert_eq!(all, "a,b\n1,2\n3,4\n5,6\n");
+        let middle = workbook.export(&sheet, Some(range(1, 1)), format).unwrap();
+        assert_eq!(middle, "a,b\n3,4\n");
+        let inverted = workbook.export(&sheet, Some(range(2, 1)), format);
+        assert!(matches!(inverted, Err(DocsError::Unsupported(_))));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
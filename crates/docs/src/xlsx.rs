@@ -0,0 +1,394 @@
+//! 直接读取 xlsx 包中 calamine 未提供的信息（数字格式、布局等）
+
+use crate::{
+    error::DocsError,
+    numfmt::builtin_format,
+    ooxml::{attr, child, is, rel_attr, Package},
+    sheet::{Comment, FreezePane, Hyperlink, MergedRange, SheetLayout},
+};
+use quick_xml::{events::Event, Reader};
+use std::collections::{BTreeMap, HashMap};
+
+const WORKBOOK_PART: &str = "xl/workbook.xml";
+
+/// xlsx 工作簿中的格式信息
+pub struct XlsxWorkbook {
+    package: Package,
+    /// 工作表名 -> 部件路径
+    sheets: Vec<(String, String)>,
+    /// cellXfs 下标 -> 数字格式代码
+    formats: Vec<Option<String>>,
+}
+
+/// 从工作表 XML 中读取的信息
+#[derive(Debug, Default)]
+pub struct SheetXml {
+    /// (行, 列) -> cellXfs 下标，只记录非默认样式
+    pub styles: HashMap<(u32, u32), u32>,
+    /// `<col>` 定义的列区间
+    pub columns: Vec<ColumnRange>,
+    /// 行号 -> 行高（磅）
+    pub row_heights: BTreeMap<u32, f64>,
+    pub hidden_rows: Vec<u32>,
+    pub merges: Vec<MergedRange>,
+    pub freeze: Option<FreezePane>,
+    /// 默认列宽（字符数）
+    pub default_col_width: Option<f64>,
+    /// 默认行高（磅）
+    pub default_row_height: Option<f64>,
+    /// A1 引用 -> 超链接
+    pub hyperlinks: BTreeMap<String, Hyperlink>,
+    /// A1 引用 -> 批注
+    pub comments: BTreeMap<String, Comment>,
+    /// 有内容的单元格所占区域：(左上角, 右下角)
+    pub used: Option<((u32, u32), (u32, u32))>,
+    /// 未解析的超链接：(A1 引用, 关系 id)
+    links: Vec<(String, String)>,
+}
+
+/// 列区间 `min..=max`，从 0 开始
+#[derive(Debug)]
+pub struct ColumnRange {
+    pub first: u32,
+    pub last: u32,
+    /// 列宽（字符数）
+    pub width: Option<f64>,
+    pub hidden: bool,
+}
+
+impl SheetXml {
+    /// 转换为以 px 为单位的布局，列区间只展开到 `last_col`，避免整行设置时生成上万列
+    pub fn layout(&self, last_col: u32) -> SheetLayout {
+        let mut layout = SheetLayout {
+            merges: self.merges.clone(),
+            hidden_rows: self.hidden_rows.clone(),
+            freeze: self.freeze.clone(),
+            default_col_width: self.default_col_width.map(col_width_px),
+            default_row_height: self.default_row_height.map(points_to_px),
+            ..Default::default()
+        };
+        for range in &self.columns {
+            for col in range.first..=range.last.min(last_col) {
+                if let Some(width) = range.width {
+                    layout.col_widths.insert(col, col_width_px(width));
+                }
+                if range.hidden {
+                    layout.hidden_cols.push(col);
+                }
+            }
+        }
+        for (row, height) in &self.row_heights {
+            if Some(*height) != self.default_row_height {
+                layout.row_heights.insert(*row, points_to_px(*height));
+            }
+        }
+        layout
+    }
+}
+
+/// 列宽字符数换算为 px（默认字体最大数字宽度 7px）
+fn col_width_px(width: f64) -> f64 {
+    (width * 7.0).round()
+}
+
+fn points_to_px(points: f64) -> f64 {
+    (points * 4.0 / 3.0).round()
+}
+
+impl XlsxWorkbook {
+    /// 打开 xlsx/xlsm/xlam 文件，不是 OOXML 工作簿时返回错误
+    pub fn open(path: &str) -> Result<Self, DocsError> {
+        let mut package = Package::open(path)?;
+        let xml = package
+            .read_string(WORKBOOK_PART)?
+            .ok_or_else(|| DocsError::missing_part(WORKBOOK_PART))?;
+        let rels = package.relationships(WORKBOOK_PART)?;
+        let doc = roxmltree::Document::parse(&xml)?;
+        let sheets = doc
+            .descendants()
+            .filter(|n| is(n, "sheet"))
+            .filter_map(|n| {
+                let name = attr(&n, "name")?;
+                let rel = rels.get(rel_attr(&n, "id")?)?;
+                Some((name.to_string(), rel.target.clone()))
+            })
+            .collect();
+
+        let formats = match package.read_string("xl/styles.xml")? {
+            Some(xml) => parse_formats(&roxmltree::Document::parse(&xml)?),
+            None => Vec::new(),
+        };
+        Ok(XlsxWorkbook { package, sheets, formats })
+    }
+
+    /// cellXfs 下标对应的数字格式代码
+    pub fn format_code(&self, xf: u32) -> Option<&str> {
+        self.formats.get(xf as usize)?.as_deref()
+    }
+
+    /// 单元格的数字格式代码
+    pub fn cell_format(&self, sheet: &SheetXml, position: (u32, u32)) -> Option<&str> {
+        self.format_code(*sheet.styles.get(&position)?)
+    }
+
+    /// 读取并扫描工作表 XML
+    pub fn sheet(&mut self, name: &str) -> Result<SheetXml, DocsError> {
+        let Some((_, part)) = self.sheets.iter().find(|(n, _)| n == name) else {
+            return Ok(SheetXml::default());
+        };
+        let part = part.clone();
+        let Some(data) = self.package.read(&part)? else {
+            return Ok(SheetXml::default());
+        };
+        let mut sheet = scan_sheet(&data)?;
+
+        let rels = self.package.relationships(&part)?;
+        for (reference, id) in std::mem::take(&mut sheet.links) {
+            if let Some(rel) = rels.get(&id) {
+                let link = sheet.hyperlinks.entry(reference).or_default();
+                // location 作为锚点附加在外部地址后
+                link.target = format!("{}{}", rel.target, link.target);
+            }
+        }
+        sheet.hyperlinks.retain(|_, link| !link.target.is_empty());
+
+        for rel in rels
+            .values()
+            .filter(|r| r.kind == "comments" && !r.external)
+        {
+            if let Some(xml) = self.package.read_string(&rel.target)? {
+                sheet
+                    .comments
+                    .extend(parse_comments(&roxmltree::Document::parse(&xml)?));
+            }
+        }
+        Ok(sheet)
+    }
+}
+
+/// 解析 comments.xml（旧式批注，新版 Excel 的会话批注同样会写入一份）
+fn parse_comments(doc: &roxmltree::Document) -> BTreeMap<String, Comment> {
+    let root = doc.root_element();
+    let authors: Vec<String> = child(&root, "authors")
+        .map(|n| {
+            n.children()
+                .filter(|a| is(a, "author"))
+                .map(|a| a.text().unwrap_or_default().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let Some(list) = child(&root, "commentList") else {
+        return BTreeMap::new();
+    };
+    list.children()
+        .filter(|c| is(c, "comment"))
+        .filter_map(|c| {
+            let reference = attr(&c, "ref")?.to_string();
+            let author = attr(&c, "authorId")
+                .and_then(|id| authors.get(id.parse::<usize>().ok()?))
+                .filter(|a| !a.is_empty())
+                .cloned();
+            // 只取正文 t，跳过拼音注音 rPh 中的文字
+            let text: String = child(&c, "text")?
+                .descendants()
+                .filter(|t| is(t, "t") && !t.ancestors().any(|a| is(&a, "rPh")))
+                .filter_map(|t| t.text())
+                .collect();
+            Some((reference, Comment { author, text }))
+        })
+        .collect()
+}
+
+fn parse_formats(doc: &roxmltree::Document) -> Vec<Option<String>> {
+    let root = doc.root_element();
+    let custom: HashMap<u32, String> = child(&root, "numFmts")
+        .map(|n| {
+            n.children()
+                .filter(|f| is(f, "numFmt"))
+                .filter_map(|f| {
+                    let id = attr(&f, "numFmtId")?.parse().ok()?;
+                    Some((id, attr(&f, "formatCode")?.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    child(&root, "cellXfs")
+        .map(|n| {
+            n.children()
+                .filter(|xf| is(xf, "xf"))
+                .map(|xf| {
+                    let id: u32 = attr(&xf, "numFmtId")?.parse().ok()?;
+                    custom
+                        .get(&id)
+                        .cloned()
+                        .or_else(|| builtin_format(id).map(str::to_string))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 流式扫描工作表 XML，避免为大表构建完整的 DOM
+fn scan_sheet(data: &[u8]) -> Result<SheetXml, DocsError> {
+    let mut sheet = SheetXml::default();
+    let mut reader = Reader::from_reader(data);
+    let mut buf = Vec::new();
+    // 未写 r 属性的行/单元格按顺序递增
+    let mut row: u32 = 0;
+    let mut next_row: u32 = 0;
+    let mut col: u32 = 0;
+
+    loop {
+        let event = reader.read_event_into(&mut buf)?;
+        let has_content = matches!(event, Event::Start(_));
+        match event {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"sheetFormatPr" => {
+                    sheet.default_col_width = number(&e, b"defaultColWidth")
+                        .or_else(|| number(&e, b"baseColWidth").map(|w| w + 5.0 / 7.0));
+                    sheet.default_row_height = number(&e, b"defaultRowHeight");
+                },
+                b"pane" if sheet.freeze.is_none() => {
+                    let state = attribute(&e, b"state");
+                    if matches!(state.as_deref(), Some("frozen" | "frozenSplit")) {
+                        sheet.freeze = Some(FreezePane {
+                            rows: number(&e, b"ySplit").unwrap_or(0.0) as u32,
+                            cols: number(&e, b"xSplit").unwrap_or(0.0) as u32,
+                        });
+                    }
+                },
+                b"col" => {
+                    if let (Some(min), Some(max)) = (number(&e, b"min"), number(&e, b"max")) {
+                        sheet.columns.push(ColumnRange {
+                            first: (min as u32).saturating_sub(1),
+                            last: (max as u32).saturating_sub(1),
+                            width: number(&e, b"width"),
+                            hidden: flag(&e, b"hidden"),
+                        });
+                    }
+                },
+                b"row" => {
+                    row = match attribute(&e, b"r").and_then(|r| r.parse::<u32>().ok()) {
+                        Some(r) => r.saturating_sub(1),
+                        None => next_row,
+                    };
+                    next_row = row + 1;
+                    col = 0;
+                    if let Some(height) = number(&e, b"ht") {
+                        sheet.row_heights.insert(row, height);
+                    }
+                    if flag(&e, b"hidden") {
+                        sheet.hidden_rows.push(row);
+                    }
+                },
+                b"hyperlink" => {
+                    if let Some(reference) = attribute(&e, b"ref") {
+                        // 区域链接只记录在左上角单元格上
+                        let reference = reference.split(':').next().unwrap_or_default().to_string();
+                        if let Some(id) = attribute(&e, b"id") {
+                            sheet.links.push((reference.clone(), id));
+                        }
+                        sheet.hyperlinks.insert(
+                            reference,
+                            Hyperlink {
+                                target: attribute(&e, b"location")
+                                    .map(|l| format!("#{}", l))
+                                    .unwrap_or_default(),
+                                tooltip: attribute(&e, b"tooltip"),
+                            },
+                        );
+                    }
+                },
+                b"mergeCell" => {
+                    if let Some(range) = attribute(&e, b"ref").and_then(|r| parse_range(&r)) {
+                        sheet.merges.push(range);
+                    }
+                },
+                b"c" => {
+                    if let Some((r, c)) = attribute(&e, b"r").and_then(|r| parse_reference(&r)) {
+                        row = r;
+                        col = c;
+                    }
+                    if let Some(style) = attribute(&e, b"s").and_then(|s| s.parse::<u32>().ok()) {
+                        if style != 0 {
+                            sheet.styles.insert((row, col), style);
+                        }
+                    }
+                    if has_content {
+                        sheet.used = Some(match sheet.used {
+                            Some((start, end)) => (
+                                (start.0.min(row), start.1.min(col)),
+                                (end.0.max(row), end.1.max(col)),
+                            ),
+                            None => ((row, col), (row, col)),
+                        });
+                    }
+                    col += 1;
+                },
+                _ => {},
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+        buf.clear();
+    }
+    Ok(sheet)
+}
+
+fn number(e: &quick_xml::events::BytesStart, name: &[u8]) -> Option<f64> {
+    attribute(e, name)?.parse().ok()
+}
+
+fn flag(e: &quick_xml::events::BytesStart, name: &[u8]) -> bool {
+    matches!(attribute(e, name).as_deref(), Some("1" | "true"))
+}
+
+fn attribute(e: &quick_xml::events::BytesStart, name: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|a| a.key.local_name().as_ref() == name)
+        .and_then(|a| a.unescape_value().ok())
+        .map(|v| v.into_owned())
+}
+
+/// 解析 `AB12` 形式的单元格引用为从 0 开始的 (行, 列)
+pub fn parse_reference(reference: &str) -> Option<(u32, u32)> {
+    let reference = reference.trim().replace('$', "");
+    let split = reference.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = reference.split_at(split);
+    if letters.is_empty() {
+        return None;
+    }
+    let mut col: u32 = 0;
+    for c in letters.chars() {
+        if !c.is_ascii_alphabetic() {
+            return None;
+        }
+        col = col
+            .checked_mul(26)?
+            .checked_add(c.to_ascii_uppercase() as u32 - 'A' as u32 + 1)?;
+    }
+    let row: u32 = digits.parse().ok()?;
+    Some((row.checked_sub(1)?, col - 1))
+}
+
+/// 从 0 开始的 (行, 列) 转为 `AB12` 形式的单元格引用
+pub fn cell_reference(row: u32, col: u32) -> String {
+    let mut letters = Vec::new();
+    let mut n = col + 1;
+    while n > 0 {
+        letters.push((b'A' + ((n - 1) % 26) as u8) as char);
+        n = (n - 1) / 26;
+    }
+    letters.iter().rev().collect::<String>() + &(row + 1).to_string()
+}
+
+/// 解析 `A1:C3` 形式的区域
+fn parse_range(reference: &str) -> Option<MergedRange> {
+    let (start, end) = reference.split_once(':').unwrap_or((reference, reference));
+    let (row, col) = parse_reference(start)?;
+    let (end_row, end_col) = parse_reference(end)?;
+    Some(MergedRange::new((row, col), (end_row, end_col)))
+}
@@ -1,13 +1,20 @@
 use log::{set_max_level, LevelFilter};
-use quicklook_archive::{extractors, Extract};
-use quicklook_docs as docs;
-use std::path::PathBuf;
-use tauri::{command, AppHandle, Manager};
+use quicklook_archive::{
+    extractors, ArchiveError, Comic, CompressFormat, CompressOptions, Extract,
+};
+use quicklook_docs::{self as docs, DocsError};
+use std::{fs, io, ops::ControlFlow, path::PathBuf};
+use tauri::{
+    command,
+    http::{Request, Response},
+    ipc::Channel,
+    AppHandle, Emitter, Manager,
+};
 use windows::Win32::Foundation::HWND;
 
 #[path = "helper/mod.rs"]
 mod helper;
-use helper::{audio, monitor, win};
+use helper::{audio, comic as comic_helper, monitor, sheets, win};
 // use helper::{archives, docs, ffmp, monitor, win};
 
 #[command]
@@ -19,16 +26,17 @@ pub fn show_open_with_dialog(app: AppHandle, path: &str) {
 }
 
 #[command]
-pub fn archive(path: &str, mode: &str) -> Result<Vec<Extract>, String> {
+pub fn archive(path: &str, mode: &str) -> Result<Vec<Extract>, ArchiveError> {
     log::info!("开始处理压缩文件: {}, 扩展名: {}", path, mode);
     let result = match mode {
-        "zip" => extractors::zip::zip_extract(path).map_err(|e| e.to_string()),
-        "tar" => extractors::tar::list_tar_entries(path).map_err(|e| e.to_string()),
-        "gz" | "tgz" => extractors::tar::list_tar_gz_entries(path).map_err(|e| e.to_string()),
-        "bz2" | "tbz2" => extractors::tar::list_tar_bz2_entries(path).map_err(|e| e.to_string()),
-        "xz" | "txz" => extractors::tar::list_tar_xz_entries(path).map_err(|e| e.to_string()),
-        "7z" => extractors::sevenz::list_7z_entries(path).map_err(|e| e.to_string()),
-        _ => Err("不支持的压缩格式".to_string()),
+        "zip" => extractors::zip::zip_extract(path),
+        "tar" => extractors::tar::list_tar_entries(path),
+        "gz" | "tgz" => extractors::tar::list_tar_gz_entries(path),
+        "bz2" | "tbz2" => extractors::tar::list_tar_bz2_entries(path),
+        "xz" | "txz" => extractors::tar::list_tar_xz_entries(path),
+        "zst" | "tzst" => extractors::tar::list_tar_zst_entries(path),
+        "7z" => extractors::sevenz::list_7z_entries(path),
+        _ => Err(ArchiveError::UnsupportedFormat(mode.to_string())),
     };
 
     match &result {
@@ -44,15 +52,208 @@ pub fn archive(path: &str, mode: &str) -> Result<Vec<Extract>, String> {
 }
 
 #[command]
-pub fn document(path: &str, mode: &str) -> Result<docs::Docs, String> {
-    match mode {
-        "csv" => docs::Docs::csv(path).map_err(|e| e.to_string()),
-        "xlsx" | "xls" | "xlsm" | "xlsb" | "xla" | "xlam" | "ods" => {
-            docs::Docs::excel(path).map_err(|e| e.to_string())
-        },
-        "docx" => docs::Docs::docx(path).map_err(|e| e.to_string()),
-        _ => Err("Not Support".to_string()),
+pub fn comic(path: &str) -> Result<Comic, ArchiveError> {
+    log::info!("打开漫画: {}", path);
+    comic_helper::open_comic(path).inspect_err(|e| log::error!("漫画打开失败: {}", e))
+}
+
+/// `comic://` 协议处理，与 `comic` 命令共用同一份页面缓存
+pub fn comic_protocol(request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    comic_helper::protocol_response(request)
+}
+
+#[command]
+pub async fn compress(
+    app: AppHandle,
+    paths: Vec<String>,
+    output: String,
+    level: Option<i64>,
+) -> Result<(), String> {
+    log::info!("开始压缩: {:?} -> {}", paths, output);
+    let format = CompressFormat::from_path(&output).ok_or("不支持的压缩格式".to_string())?;
+    let options = CompressOptions { level, deterministic: false };
+
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        quicklook_archive::compress(&paths, &output, format, &options, |progress| {
+            let _ = app.emit("compress_progress", progress);
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if let Err(e) = &result {
+        log::error!("压缩失败: {}", e);
     }
+    result.map_err(|e| e.to_string())
+}
+
+/// 按文件内容识别文档格式，前端据此选择预览方式
+#[command]
+pub fn document_format(path: &str) -> Result<docs::DocsFormat, DocsError> {
+    docs::DocsFormat::detect(path).inspect_err(|e| log::error!("文档格式识别失败: {}: {}", path, e))
+}
+
+#[command]
+pub fn document(path: &str) -> Result<docs::DetectedDocs, DocsError> {
+    docs::Docs::open(path).inspect_err(|e| log::error!("文档解析失败: {}: {}", path, e))
+}
+
+/// 读取文档属性（docProps 或 ODF meta.xml），没有属性的格式返回 `None`
+#[command]
+pub fn document_metadata(path: &str) -> Result<Option<docs::DocumentMetadata>, DocsError> {
+    docs::DocumentMetadata::read(path)
+        .inspect_err(|e| log::error!("读取文档属性失败: {}: {}", path, e))
+}
+
+/// 电子书概要：元数据、封面、章节列表与目录
+#[command]
+pub fn book(path: &str) -> Result<docs::Book, DocsError> {
+    docs::epub::parse(path).inspect_err(|e| log::error!("电子书解析失败: {}: {}", path, e))
+}
+
+/// 读取电子书第 `index` 章（从 0 开始），返回清理后的 XHTML 与样式
+#[command]
+pub fn book_chapter(path: &str, index: usize) -> Result<docs::epub::ChapterContent, DocsError> {
+    docs::epub::Epub::open(path)
+        .and_then(|mut book| book.chapter(index))
+        .inspect_err(|e| log::error!("读取章节失败: {}: {}", path, e))
+}
+
+/// PDF 概要：页数、页面尺寸、加密状态、文档属性与书签，可在 pdf.js 加载前显示
+#[command]
+pub fn pdf_info(path: &str) -> Result<docs::PdfInfo, DocsError> {
+    docs::pdf::parse(path).inspect_err(|e| log::error!("PDF 解析失败: {}: {}", path, e))
+}
+
+/// 提取 PDF 指定页（从 1 开始）的文本，`pages` 为空时提取全部页
+#[command]
+pub async fn pdf_text(
+    path: String,
+    pages: Option<Vec<u32>>,
+) -> Result<Vec<docs::pdf::PageText>, DocsError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        docs::pdf::Pdf::open(&path)?.text(pages.as_deref().unwrap_or_default())
+    })
+    .await
+    .map_err(|e| DocsError::Io(io::Error::other(e.to_string())))?
+    .inspect_err(|e| log::error!("PDF 文本提取失败: {}", e))
+}
+
+/// Parquet 与 Arrow IPC 概要：列结构、行组统计与键值元数据，行通过 `read_window` 分页读取
+#[command]
+pub fn columnar_info(path: &str) -> Result<docs::ColumnarInfo, DocsError> {
+    docs::columnar::parse(path).inspect_err(|e| log::error!("列式文件解析失败: {}: {}", path, e))
+}
+
+/// SQLite 概要：表、视图、索引与触发器的定义，只读打开
+#[command]
+pub async fn database(path: String) -> Result<docs::DatabaseInfo, DocsError> {
+    tauri::async_runtime::spawn_blocking(move || docs::sqlite::parse(&path))
+        .await
+        .map_err(|e| DocsError::Io(io::Error::other(e.to_string())))?
+        .inspect_err(|e| log::error!("数据库打开失败: {}", e))
+}
+
+/// 表或视图 `table` 的行数
+#[command]
+pub async fn database_count(path: String, table: String) -> Result<u64, DocsError> {
+    tauri::async_runtime::spawn_blocking(move || docs::sqlite::Sqlite::open(&path)?.count(&table))
+        .await
+        .map_err(|e| DocsError::Io(io::Error::other(e.to_string())))?
+        .inspect_err(|e| log::error!("统计行数失败: {}", e))
+}
+
+/// 读取表或视图 `table` 从第 `offset` 行起的 `limit` 行
+#[command]
+pub async fn database_rows(
+    path: String,
+    table: String,
+    offset: u64,
+    limit: u32,
+) -> Result<docs::sqlite::TableRows, DocsError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        docs::sqlite::Sqlite::open(&path)?.rows(&table, offset, limit)
+    })
+    .await
+    .map_err(|e| DocsError::Io(io::Error::other(e.to_string())))?
+    .inspect_err(|e| log::error!("读取数据表失败: {}", e))
+}
+
+/// 全文搜索，命中结果通过 `on_hit` 逐个推送，返回命中总数
+#[command]
+pub async fn document_search(
+    path: String,
+    query: String,
+    options: Option<docs::SearchOptions>,
+    on_hit: Channel<docs::SearchHit>,
+) -> Result<usize, DocsError> {
+    log::info!("搜索文档: {}, 关键字: {}", path, query);
+    let options = options.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || {
+        docs::search::search(&path, &query, &options, |hit| match on_hit.send(hit) {
+            Ok(()) => ControlFlow::Continue(()),
+            // 前端已不再接收
+            Err(_) => ControlFlow::Break(()),
+        })
+    })
+    .await
+    .map_err(|e| DocsError::Io(io::Error::other(e.to_string())))?
+    .inspect_err(|e| log::error!("搜索文档失败: {}", e))
+}
+
+/// 大表格分页预览：先返回工作表概要
+#[command]
+pub async fn sheet_info(path: String) -> Result<Vec<docs::SheetInfo>, DocsError> {
+    log::info!("打开表格: {}", path);
+    tauri::async_runtime::spawn_blocking(move || sheets::open_sheets(&path))
+        .await
+        .map_err(|e| DocsError::Io(io::Error::other(e.to_string())))?
+        .inspect_err(|e| log::error!("表格打开失败: {}", e))
+}
+
+/// 读取 `row_offset` 起（相对于已使用区域）的 `row_count` 行
+#[command]
+pub async fn sheet_window(
+    path: String,
+    sheet: String,
+    row_offset: u32,
+    row_count: u32,
+) -> Result<docs::SheetWindow, DocsError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        sheets::read_window(&path, &sheet, row_offset, row_count)
+    })
+    .await
+    .map_err(|e| DocsError::Io(io::Error::other(e.to_string())))?
+    .inspect_err(|e| log::error!("读取表格行失败: {}", e))
+}
+
+/// 导出工作表或选中区域；给出 `output` 时写入该文件并返回 `None`，否则返回文本供复制到剪贴板
+#[command]
+pub async fn sheet_export(
+    path: String,
+    sheet: String,
+    range: Option<docs::SheetRange>,
+    format: docs::ExportFormat,
+    output: Option<String>,
+) -> Result<Option<String>, DocsError> {
+    log::info!("导出表格: {}, 工作表: {}", path, sheet);
+    tauri::async_runtime::spawn_blocking(move || {
+        let text = sheets::export_sheet(&path, &sheet, range, format)?;
+        let Some(output) = output else {
+            return Ok(Some(text));
+        };
+        // Excel 需要 BOM 才能按 UTF-8 打开 CSV
+        let bom = match format {
+            docs::ExportFormat::Csv { .. } => "\u{feff}",
+            _ => "",
+        };
+        fs::write(&output, format!("{}{}", bom, text))?;
+        log::info!("已导出到: {}", output);
+        Ok(None)
+    })
+    .await
+    .map_err(|e| DocsError::Io(io::Error::other(e.to_string())))?
+    .inspect_err(|e| log::error!("导出表格失败: {}", e))
 }
 
 #[command]
@@ -0,0 +1,119 @@
+use quicklook_archive::{comic::page_mime, ArchiveError, Comic};
+use std::sync::{LazyLock, Mutex};
+use tauri::http::{header, Request, Response, StatusCode};
+
+/// 最近打开的漫画
+struct CachedComic {
+    path: String,
+    comic: Comic,
+    /// 无法随机访问的格式一次解出的全部页面
+    pages: Option<Vec<Vec<u8>>>,
+}
+
+/// 整本缓存页面的大小上限，超过时退回逐页读取
+const PAGE_CACHE_LIMIT: u64 = 512 << 20;
+
+/// 最近打开的漫画，翻页时无需重新列举压缩包
+#[allow(unused)]
+static CURRENT: LazyLock<Mutex<Option<CachedComic>>> = LazyLock::new(|| Mutex::new(None));
+
+/// 打开漫画压缩包并缓存页面列表
+#[allow(unused)]
+pub fn open_comic(path: &str) -> Result<Comic, ArchiveError> {
+    let comic = Comic::open(path)?;
+    if let Ok(mut current) = CURRENT.lock() {
+        *current = Some(CachedComic {
+            path: path.to_string(),
+            comic: comic.clone(),
+            pages: None,
+        });
+    }
+    Ok(comic)
+}
+
+/// 读取指定页的图片数据，返回图片内容和 MIME 类型
+///
+/// ZIP 和未压缩的 TAR 按条目直接读取；7Z 与带外层压缩的 TAR 只能从头解码，
+/// 首次翻页时一次解出所有页面并缓存，避免每页都从头解码。
+#[allow(unused)]
+pub fn read_page(path: &str, index: usize) -> Result<(Vec<u8>, &'static str), ArchiveError> {
+    let mut current = CURRENT
+        .lock()
+        .map_err(|_| ArchiveError::Other("漫画缓存锁已失效".to_string()))?;
+    let cached = match current.take() {
+        Some(cached) if cached.path == path => current.insert(cached),
+        _ => current.insert(CachedComic {
+            path: path.to_string(),
+            comic: Comic::open(path)?,
+            pages: None,
+        }),
+    };
+
+    let page = cached
+        .comic
+        .pages
+        .get(index)
+        .ok_or_else(|| ArchiveError::EntryNotFound(format!("page {}", index)))?;
+    let mime = page_mime(&page.name);
+
+    let total: u64 = cached.comic.pages.iter().map(|page| page.size).sum();
+    if cached.comic.format.random_access() || total > PAGE_CACHE_LIMIT {
+        let (format, name) = (cached.comic.format, page.name.clone());
+        drop(current);
+        return Ok((format.read_entry(path, &name)?, mime));
+    }
+
+    if cached.pages.is_none() {
+        log::info!(
+            "解码漫画全部页面: {}, 共 {} 页",
+            path,
+            cached.comic.pages.len()
+        );
+        cached.pages = Some(cached.comic.read_pages(path)?);
+    }
+    let data = cached
+        .pages
+        .as_ref()
+        .and_then(|pages| pages.get(index))
+        .cloned()
+        .ok_or_else(|| ArchiveError::EntryNotFound(format!("page {}", index)))?;
+    Ok((data, mime))
+}
+
+/// `comic://` 协议处理：`comic://localhost/<编码后的文件路径>?page=<页码>`
+#[allow(unused)]
+pub fn protocol_response(request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let path = urlencoding::decode(request.uri().path().trim_start_matches('/'))
+        .map(|p| p.into_owned())
+        .unwrap_or_default();
+    let page = request
+        .uri()
+        .query()
+        .and_then(|q| q.split('&').find_map(|kv| kv.strip_prefix("page=")))
+        .and_then(|p| p.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    let result = match read_page(&path, page) {
+        Ok((data, mime)) => Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, mime)
+            .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+            .body(data),
+        Err(e) => {
+            log::error!("读取漫画页面失败: {}, 第 {} 页: {}", path, page, e);
+            let status = match e {
+                ArchiveError::EntryNotFound(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            Response::builder()
+                .status(status)
+                .body(e.to_string().into_bytes())
+        },
+    };
+
+    result.unwrap_or_else(|_| {
+        let mut response = Response::new(Vec::new());
+        *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+        response
+    })
+}
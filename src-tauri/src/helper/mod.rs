@@ -3,9 +3,11 @@ use tauri::{
 };
 
 pub mod audio;
+pub mod comic;
 pub mod config;
 pub mod monitor;
 pub mod selected_file;
+pub mod sheets;
 pub mod win;
 
 #[allow(unused)]
@@ -0,0 +1,56 @@
+use quicklook_docs::{DocsError, ExportFormat, LazyWorkbook, SheetInfo, SheetRange, SheetWindow};
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+/// 最近打开的工作簿，翻页时无需重新打开和扫描
+#[allow(unused)]
+static CURRENT: LazyLock<Mutex<Option<(String, LazyWorkbook)>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// 打开工作簿并缓存，返回各工作表的概要
+#[allow(unused)]
+pub fn open_sheets(path: &str) -> Result<Vec<SheetInfo>, DocsError> {
+    let book = LazyWorkbook::open(path)?;
+    let sheets = book.sheets().to_vec();
+    let mut current = CURRENT.lock().unwrap_or_else(PoisonError::into_inner);
+    *current = Some((path.to_string(), book));
+    Ok(sheets)
+}
+
+/// 在缓存的工作簿上执行操作，缓存的不是该文件时重新打开
+fn with_book<T>(
+    path: &str,
+    f: impl FnOnce(&mut LazyWorkbook) -> Result<T, DocsError>,
+) -> Result<T, DocsError> {
+    let mut current = CURRENT.lock().unwrap_or_else(PoisonError::into_inner);
+    let book = match current.as_mut() {
+        Some((p, book)) if p == path => book,
+        _ => {
+            &mut current
+                .insert((path.to_string(), LazyWorkbook::open(path)?))
+                .1
+        },
+    };
+    f(book)
+}
+
+/// 读取行窗口
+#[allow(unused)]
+pub fn read_window(
+    path: &str,
+    sheet: &str,
+    row_offset: u32,
+    row_count: u32,
+) -> Result<SheetWindow, DocsError> {
+    with_book(path, |book| book.window(sheet, row_offset, row_count))
+}
+
+/// 导出工作表或其中的区域为文本
+#[allow(unused)]
+pub fn export_sheet(
+    path: &str,
+    sheet: &str,
+    range: Option<SheetRange>,
+    format: ExportFormat,
+) -> Result<String, DocsError> {
+    with_book(path, |book| book.export(sheet, range, format))
+}
@@ -12,8 +12,10 @@ mod tray;
 #[path = "./command.rs"]
 mod command;
 use command::{
-    archive, document, get_default_program_name, get_monitor_info, parse_lrc, psd_to_png,
-    read_audio_info, set_log_level, show_open_with_dialog,
+    archive, book, book_chapter, columnar_info, comic, compress, database, database_count,
+    database_rows, document, document_format, document_metadata, document_search,
+    get_default_program_name, get_monitor_info, parse_lrc, pdf_info, pdf_text, psd_to_png,
+    read_audio_info, set_log_level, sheet_export, sheet_info, sheet_window, show_open_with_dialog,
 };
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -43,6 +45,14 @@ pub fn run() {
                 .build(),
         );
 
+    // 漫画页面协议：comic://localhost/<文件路径>?page=<页码>
+    builder =
+        builder.register_asynchronous_uri_scheme_protocol("comic", |_ctx, request, responder| {
+            tauri::async_runtime::spawn_blocking(move || {
+                responder.respond(command::comic_protocol(&request));
+            });
+        });
+
     // 初始化
     let app = builder
         .setup(|app| {
@@ -114,7 +124,23 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             show_open_with_dialog,
             archive,
+            comic,
+            compress,
             document,
+            document_format,
+            document_metadata,
+            document_search,
+            book,
+            book_chapter,
+            pdf_info,
+            pdf_text,
+            database,
+            database_count,
+            database_rows,
+            columnar_info,
+            sheet_info,
+            sheet_window,
+            sheet_export,
             get_monitor_info,
             get_default_program_name,
             set_log_level,
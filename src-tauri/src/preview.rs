@@ -60,6 +60,8 @@ impl WebRoute {
             "Book" => WebRoute::new("/preview/book".to_string(), file_info.clone()),
             "Archive" => WebRoute::new("/preview/archive".to_string(), file_info.clone()),
             "Doc" => WebRoute::new("/preview/document".to_string(), file_info.clone()),
+            "Comic" => WebRoute::new("/preview/comic".to_string(), file_info.clone()),
+            "Database" => WebRoute::new("/preview/database".to_string(), file_info.clone()),
             _ => WebRoute::new("/preview/not-support".to_string(), file_info.clone()),
         }
     }
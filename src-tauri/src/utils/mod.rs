@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
 use std::os::windows::fs::MetadataExt;
 use std::path::Path;
 
@@ -145,8 +147,12 @@ pub fn get_file_info(path: &str) -> Option<File> {
 
     let metadata = file_path.metadata().unwrap();
 
-    // 根据扩展名从映射表中获取文件类型
-    match file_type_mapping().get(extension.as_str()) {
+    // 根据扩展名从映射表中获取文件类型；数据库扩展名还需确认文件头，避免把 Thumbs.db 等当作数据库
+    let file_type = file_type_mapping()
+        .get(extension.as_str())
+        .copied()
+        .filter(|&file_type| file_type != "Database" || is_sqlite(file_path));
+    match file_type {
         Some(file_type) => Some(File::new(
             file_type,
             path_str,
@@ -159,6 +165,14 @@ pub fn get_file_info(path: &str) -> Option<File> {
     }
 }
 
+// 文件头是否为 SQLite 数据库
+fn is_sqlite(path: &Path) -> bool {
+    let mut header = [0u8; 16];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .is_ok_and(|_| &header == quicklook_docs::sqlite::SQLITE_MAGIC)
+}
+
 // 返回一个文件扩展名到文件类型的映射
 fn file_type_mapping() -> HashMap<&'static str, &'static str> {
     HashMap::from([
@@ -166,8 +180,9 @@ fn file_type_mapping() -> HashMap<&'static str, &'static str> {
         ("markdown", "Markdown"),
         ("md", "Markdown"),
         // DOC 文件
-        // ("doc", "Doc"),
+        ("doc", "Doc"),
         ("docx", "Doc"),
+        ("rtf", "Doc"),
         ("xls", "Doc"),
         ("xlsx", "Doc"),
         ("xlsm", "Doc"),
@@ -175,9 +190,20 @@ fn file_type_mapping() -> HashMap<&'static str, &'static str> {
         ("xla", "Doc"),
         ("xlam", "Doc"),
         ("ods", "Doc"),
+        ("odt", "Doc"),
+        ("odp", "Doc"),
         ("csv", "Doc"),
-        // ("ppt", "Doc"),
-        // ("pptx", "Doc"),
+        ("tsv", "Doc"),
+        ("psv", "Doc"),
+        ("tab", "Doc"),
+        ("ppt", "Doc"),
+        ("pptx", "Doc"),
+        ("ipynb", "Doc"),
+        ("parquet", "Doc"),
+        ("arrow", "Doc"),
+        ("arrows", "Doc"),
+        ("feather", "Doc"),
+        ("ipc", "Doc"),
         // 字体文件
         ("ttf", "Font"),
         ("otf", "Font"),
@@ -221,8 +247,20 @@ fn file_type_mapping() -> HashMap<&'static str, &'static str> {
         ("tbz2", "Archive"), // tar.bz2 的简写
         ("xz", "Archive"),   // xz 压缩文件
         ("txz", "Archive"),  // tar.xz 的简写
+        ("zst", "Archive"),  // zstd 压缩文件
+        ("tzst", "Archive"), // tar.zst 的简写
+        // 漫画文件（cbr 是 RAR 格式，quicklook-archive 暂不支持，不做映射）
+        ("cbz", "Comic"),
+        ("cb7", "Comic"),
+        ("cbt", "Comic"),
         // 书籍文件
         ("pdf", "Book"),
+        ("epub", "Book"),
+        // 数据库文件
+        ("db", "Database"),
+        ("db3", "Database"),
+        ("sqlite", "Database"),
+        ("sqlite3", "Database"),
         // 代码文件
         ("txt", "Code"),
         ("cpp", "Code"),
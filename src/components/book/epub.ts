@@ -0,0 +1,47 @@
+import { invoke } from '@tauri-apps/api/core'
+
+// 与 quicklook-docs 中 epub 模块的结构保持一致
+export interface BookMetadata {
+    title?: string
+    authors: string[]
+    language?: string
+    publisher?: string
+    description?: string
+    identifier?: string
+    date?: string
+    subjects: string[]
+    version?: string
+}
+
+export interface Chapter {
+    href: string
+    title?: string
+    /** linear="no" 的章节不在正常阅读顺序中 */
+    linear: boolean
+}
+
+export interface TocEntry {
+    title: string
+    chapter?: number
+    anchor?: string
+    children?: TocEntry[]
+}
+
+export interface Book {
+    metadata: BookMetadata
+    /** 封面图片的 data URI */
+    cover?: string
+    chapters: Chapter[]
+    toc: TocEntry[]
+}
+
+/** 书内链接为 href="#"，目标在 data-chapter 与 data-anchor 属性中 */
+export interface ChapterContent {
+    index: number
+    html: string
+    styles: string[]
+}
+
+export const openBook = (path: string) => invoke<Book>('book', { path })
+
+export const readChapter = (path: string, index: number) => invoke<ChapterContent>('book_chapter', { path, index })
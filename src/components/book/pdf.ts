@@ -0,0 +1,45 @@
+import { invoke } from '@tauri-apps/api/core'
+import type { DocumentMetadata } from '@/components/document/metadata'
+
+// 与 quicklook-docs 中 pdf 模块的结构保持一致
+export interface PageSize {
+    /** 已按旋转角度交换宽高，单位为点 */
+    width: number
+    height: number
+    rotation: number
+}
+
+export interface PdfEncryption {
+    /** 需要打开密码，只设置了权限密码时为 false */
+    password_required: boolean
+    /** 能否读取元数据与文本，加密算法不受支持时为 false，页面仍由 pdf.js 渲染 */
+    readable: boolean
+    filter: string
+    version: number
+    revision: number
+}
+
+export interface OutlineItem {
+    title: string
+    page?: number
+    children?: OutlineItem[]
+}
+
+export interface PdfInfo {
+    version: string
+    page_count: number
+    pages: PageSize[]
+    encryption?: PdfEncryption
+    metadata: DocumentMetadata
+    outline: OutlineItem[]
+}
+
+export interface PageText {
+    page: number
+    text: string
+}
+
+export const readPdfInfo = (path: string) => invoke<PdfInfo>('pdf_info', { path })
+
+/** `pages` 从 1 开始，不传时提取全部页 */
+export const readPdfText = (path: string, pages?: number[]) => invoke<PageText[]>('pdf_text', { path, pages })
@@ -0,0 +1,52 @@
+// 与 quicklook-docs 中 columnar 模块的结构保持一致
+export interface ColumnSchema {
+    name: string
+    /** 逻辑类型，如 int64、timestamp[ms, UTC]、list<string> */
+    data_type: string
+    /** Parquet 的物理类型 */
+    physical_type?: string
+    nullable: boolean
+}
+
+export interface ColumnChunkInfo {
+    column: string
+    codec?: string
+    encodings: string[]
+    compressed_size?: number
+    uncompressed_size?: number
+    null_count?: number
+    distinct_count?: number
+    min?: string
+    max?: string
+}
+
+export interface RowGroupInfo {
+    row_count: number
+    compressed_size?: number
+    uncompressed_size?: number
+    columns: ColumnChunkInfo[]
+}
+
+export interface ColumnarInfo {
+    format: 'parquet' | 'arrow'
+    row_count: number
+    columns: ColumnSchema[]
+    /** Parquet 的行组，或 Arrow 的记录批次 */
+    row_groups: RowGroupInfo[]
+    metadata: Record<string, string>
+    created_by?: string
+}
+
+export const COLUMNAR_FORMATS = ['parquet', 'arrow']
+
+export const formatBytes = (size?: number) => {
+    if (size === undefined || size === null) return ''
+    const units = ['B', 'KB', 'MB', 'GB', 'TB']
+    let value = size
+    let unit = 0
+    while (value >= 1024 && unit < units.length - 1) {
+        value /= 1024
+        unit++
+    }
+    return unit === 0 ? `${value} B` : `${value.toFixed(1)} ${units[unit]}`
+}
@@ -0,0 +1,21 @@
+/** quicklook-docs 返回的错误，code 取值稳定 */
+export interface DocsError {
+    code: string
+    message: string
+    part?: string
+}
+
+const errorMessages: Record<string, string> = {
+    encrypted: '文档已加密，需要密码才能查看',
+    corrupt: '文档已损坏',
+    unsupported: '不支持的文档格式或版本',
+    sheet_not_found: '工作表不存在',
+    limit_exceeded: '文档超出大小限制',
+}
+
+export const formatDocsError = (err: unknown) => {
+    if (typeof err !== 'object' || err === null || !('code' in err)) return String(err)
+    const { code, message, part } = err as DocsError
+    const text = errorMessages[code] || message
+    return part ? `${text}（${part}）` : text
+}
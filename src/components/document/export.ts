@@ -0,0 +1,20 @@
+import { invoke } from '@tauri-apps/api/core'
+
+export type ExportFormat = { type: 'csv'; delimiter?: string } | { type: 'json' } | { type: 'markdown' }
+
+/** 行列与 sheet_window 一致，相对于已使用区域，首尾都包含 */
+export interface SheetRange {
+    first_row: number
+    first_col: number
+    last_row: number
+    last_col: number
+}
+
+/** 导出工作表；给出 output 时写入文件并返回 null，否则返回文本 */
+export const exportSheet = (
+    path: string,
+    sheet: string,
+    format: ExportFormat,
+    range?: SheetRange,
+    output?: string,
+) => invoke<string | null>('sheet_export', { path, sheet, range, format, output })
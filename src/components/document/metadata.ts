@@ -0,0 +1,19 @@
+/** 文档属性（docProps 或 ODF meta.xml） */
+export interface DocumentMetadata {
+    title?: string
+    subject?: string
+    description?: string
+    keywords: string[]
+    author?: string
+    last_modified_by?: string
+    /** ISO 8601 */
+    created?: string
+    /** ISO 8601 */
+    modified?: string
+    application?: string
+    revision?: string
+    page_count?: number
+    word_count?: number
+    slide_count?: number
+    custom: Record<string, string>
+}
@@ -0,0 +1,35 @@
+// 与 quicklook-docs 中 notebook 模块的结构保持一致
+export interface OutputData {
+    plain?: string
+    /** 应在沙箱中显示 */
+    html?: string
+    /** image/png 或 image/jpeg 的 data URI */
+    image?: string
+}
+
+export type CellOutput =
+    | { type: 'stream'; name: string; text: string }
+    | { type: 'data'; execution_count?: number; data: OutputData }
+    | { type: 'error'; ename: string; evalue: string; traceback: string }
+
+export type NotebookCell =
+    | { type: 'markdown'; source: string }
+    | { type: 'code'; source: string; language?: string; execution_count?: number; outputs: CellOutput[] }
+    | { type: 'raw'; source: string; format?: string }
+
+export interface Notebook {
+    nbformat: number
+    nbformat_minor: number
+    language?: string
+    kernel?: string
+    title?: string
+    authors: string[]
+    cells: NotebookCell[]
+}
+
+/** 以比源码中最长的反引号串更长的围栏包裹，交给 markdown-it 高亮 */
+export const fenceCode = (source: string, language?: string) => {
+    const longest = Math.max(2, ...(source.match(/`+/g) ?? []).map(s => s.length))
+    const fence = '`'.repeat(longest + 1)
+    return `${fence}${language ?? ''}\n${source}\n${fence}`
+}
@@ -0,0 +1,33 @@
+import { Channel, invoke } from '@tauri-apps/api/core'
+
+export interface SearchOptions {
+    case_sensitive?: boolean
+    whole_word?: boolean
+    /** 最多返回的命中数 */
+    limit?: number
+}
+
+/** 命中位置：单元格的行列相对于已使用区域，与 sheet_window 的行号一致 */
+export type SearchLocation =
+    | { type: 'cell'; sheet: string; row: number; col: number }
+    | { type: 'paragraph'; index: number }
+    | { type: 'slide'; slide: number; notes: boolean }
+    | { type: 'page'; page: number }
+
+export type SearchHit = SearchLocation & {
+    before: string
+    matched: string
+    after: string
+}
+
+/** 全文搜索，命中结果逐个回调，返回命中总数 */
+export const searchDocument = (
+    path: string,
+    query: string,
+    options: SearchOptions,
+    onHit: (hit: SearchHit) => void,
+) => {
+    const channel = new Channel<SearchHit>()
+    channel.onmessage = onHit
+    return invoke<number>('document_search', { path, query, options, onHit: channel })
+}
@@ -0,0 +1,87 @@
+// 与 quicklook-docs 中 sheet 模块的结构保持一致
+export type CellValue =
+    | { type: 'empty' }
+    | { type: 'string'; value: string }
+    | { type: 'int'; value: number }
+    | { type: 'float'; value: number }
+    | { type: 'bool'; value: boolean }
+    | { type: 'datetime'; value: string }
+    | { type: 'duration'; value: number }
+    | { type: 'error'; value: string }
+
+export type Cell = CellValue & { display: string }
+
+export interface MergedRange {
+    row: number
+    col: number
+    row_span: number
+    col_span: number
+}
+
+export interface SheetLayout {
+    merges: MergedRange[]
+    col_widths: Record<number, number>
+    row_heights: Record<number, number>
+    hidden_rows: number[]
+    hidden_cols: number[]
+    freeze?: { rows: number; cols: number }
+    default_col_width?: number
+    default_row_height?: number
+}
+
+export interface Comment {
+    author?: string
+    text: string
+}
+
+export interface Hyperlink {
+    /** 工作簿内的位置以 `#` 开头，如 `#Sheet2!A1` */
+    target: string
+    tooltip?: string
+}
+
+export interface Sheet {
+    name: string
+    hidden: boolean
+    /** 已使用区域左上角的 [行, 列] */
+    origin: [number, number]
+    /** 分隔符文本中识别出的表头，不包含在 rows 中 */
+    header?: string[]
+    rows: Cell[][]
+    layout: SheetLayout
+    /** 以下均以 A1 引用为键 */
+    formulas: Record<string, string>
+    comments: Record<string, Comment>
+    hyperlinks: Record<string, Hyperlink>
+}
+
+/** 分页读取时的工作表概要，不含单元格数据 */
+export type SheetInfo = Omit<Sheet, 'rows' | 'formulas'> & {
+    row_count: number
+    col_count: number
+}
+
+/** 分页读取的行窗口，`row_offset` 相对于 origin */
+export interface SheetWindow {
+    sheet: string
+    row_offset: number
+    rows: Cell[][]
+    formulas: Record<string, string>
+}
+
+/** 从 0 开始的行列号转为 A1 引用 */
+export const cellReference = (row: number, col: number) => {
+    let letters = ''
+    for (let n = col + 1; n > 0; n = Math.floor((n - 1) / 26)) {
+        letters = String.fromCharCode(65 + ((n - 1) % 26)) + letters
+    }
+    return `${letters}${row + 1}`
+}
+
+/** 解析 A1 引用为从 0 开始的 [行, 列] */
+export const parseReference = (reference: string): [number, number] | null => {
+    const match = /^\$?([A-Z]+)\$?(\d+)$/i.exec(reference)
+    if (!match) return null
+    const col = [...match[1].toUpperCase()].reduce((n, c) => n * 26 + c.charCodeAt(0) - 64, 0)
+    return [Number(match[2]) - 1, col - 1]
+}
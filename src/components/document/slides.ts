@@ -0,0 +1,29 @@
+// 与 quicklook-docs 中 slides 模块的结构保持一致
+import type { Block, Image, Table } from './word'
+
+export interface Frame {
+    x: number
+    y: number
+    width: number
+    height: number
+}
+
+export type Shape = { name?: string; frame?: Frame } & (
+    | { type: 'text'; blocks: Block[] }
+    | ({ type: 'image' } & Omit<Image, 'type'>)
+    | ({ type: 'table' } & Table)
+)
+
+export interface Slide {
+    index: number
+    title?: string
+    hidden: boolean
+    shapes: Shape[]
+    notes: Block[]
+}
+
+export interface Presentation {
+    width: number
+    height: number
+    slides: Slide[]
+}
@@ -0,0 +1,58 @@
+// 与 quicklook-docs 中 document 模块的结构保持一致
+
+export interface Run {
+    type: 'text'
+    text: string
+    bold?: boolean
+    italic?: boolean
+    underline?: boolean
+    strike?: boolean
+    superscript?: boolean
+    subscript?: boolean
+    color?: string
+    size?: number
+    font?: string
+    href?: string
+}
+
+export interface Image {
+    type: 'image'
+    src: string
+    alt?: string
+    width?: number
+    height?: number
+}
+
+export type Inline = Run | Image | { type: 'break' } | { type: 'note'; id: string }
+
+export interface Paragraph {
+    style?: string
+    heading?: number
+    list?: { level: number; ordered: boolean }
+    align?: string
+    inlines: Inline[]
+}
+
+export interface TableCell {
+    blocks: Block[]
+    col_span: number
+    row_span: number
+}
+
+export interface Table {
+    rows: Array<{ cells: TableCell[] }>
+}
+
+export type Block = ({ type: 'paragraph' } & Paragraph) | ({ type: 'table' } & Table)
+
+export interface Note {
+    id: string
+    blocks: Block[]
+}
+
+export interface WordDocument {
+    blocks: Block[]
+    headers: Block[][]
+    footers: Block[][]
+    footnotes: Note[]
+}
@@ -65,6 +65,16 @@ const router = createRouter({
                     name: 'previewDocument',
                     component: () => import('@/views/preview/document.vue'),
                 },
+                {
+                    path: '/preview/comic',
+                    name: 'previewComic',
+                    component: () => import('@/views/preview/comic.vue'),
+                },
+                {
+                    path: '/preview/database',
+                    name: 'previewDatabase',
+                    component: () => import('@/views/preview/database.vue'),
+                },
             ],
         },
         {